@@ -447,6 +447,16 @@ const Tile = (props: Props) => {
                                 key={subTask.name.translation}
                                 options={getTranslationArgs(subTask.name)}
                               />
+                              <Show when={subTask.download_hosts.length > 0}>
+                                <div class="text-lightSlate-700">
+                                  <Trans
+                                    key="instance.downloading_from"
+                                    options={{
+                                      hosts: subTask.download_hosts.join(", ")
+                                    }}
+                                  />
+                                </div>
+                              </Show>
                             </div>
                           )}
                         </For>
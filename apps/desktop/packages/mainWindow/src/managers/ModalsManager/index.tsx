@@ -124,6 +124,10 @@ const defaultModals = {
   accountExpired: {
     component: lazy(() => import("./modals/AccountExpired")),
     title: "Account Expired"
+  },
+  interruptedInstalls: {
+    component: lazy(() => import("./modals/InterruptedInstalls")),
+    title: "Interrupted Installs"
   }
 };
 
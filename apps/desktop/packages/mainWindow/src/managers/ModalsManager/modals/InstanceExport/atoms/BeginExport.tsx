@@ -43,8 +43,10 @@ const BeginExport = () => {
   const validatePayload = (payload: ExportArgs) => {
     if (typeof payload.instance_id !== "number") return false;
     if (typeof payload.save_path !== "string") return false;
+    if (payload.target === "Packwiz") return true;
     const extension = _.last(payload.save_path.split("."));
-    if (extension !== "zip" && extension !== "mrpack") return false;
+    if (!["zip", "mrpack", "gdlpack"].includes(extension as string))
+      return false;
     return true;
   };
 
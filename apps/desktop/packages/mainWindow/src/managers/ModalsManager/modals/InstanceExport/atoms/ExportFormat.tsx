@@ -22,12 +22,36 @@ const ExportFormat = () => {
       entity: "Modrinth",
       supported: true,
       selection_type: "file" satisfies ImportEntitySelectionType
+    },
+    {
+      entity: "MultiMC",
+      supported: true,
+      selection_type: "file" satisfies ImportEntitySelectionType
+    },
+    {
+      entity: "Packwiz",
+      supported: true,
+      selection_type: "directory" satisfies ImportEntitySelectionType
+    },
+    {
+      entity: "GDLPack",
+      supported: true,
+      selection_type: "file" satisfies ImportEntitySelectionType
     }
   ];
 
+  const serverPack: ImportEntityStatus = {
+    entity: "MinecraftLauncher",
+    supported: true,
+    selection_type: "file" satisfies ImportEntitySelectionType
+  };
+
   const exportTargets: Partial<Record<ImportEntity, ExportTarget>> = {
     CurseForge: "Curseforge",
-    Modrinth: "Modrinth"
+    Modrinth: "Modrinth",
+    MultiMC: "MultiMC",
+    Packwiz: "Packwiz",
+    GDLPack: "GDLPack"
   };
 
   return (
@@ -45,6 +69,13 @@ const ExportFormat = () => {
             />
           )}
         </For>
+        <Card
+          entity={serverPack}
+          icon={ENTITIES.MinecraftLauncher.icon}
+          translation="instance.export_server_pack"
+          onClick={[() => {}, serverPack]}
+          instanceTitle="ServerPack"
+        />
       </ul>
     </div>
   );
@@ -37,17 +37,40 @@ const ExportPath = () => {
         <div class="flex items-center justify-center p-2 bg-[#1D2028] rounded-lg">
           <div
             onClick={async () => {
+              if (payload.target === "Packwiz") {
+                const result = await window.openFileDialog({
+                  title: "Select Pack Directory",
+                  defaultPath: path() || "",
+                  properties: ["openDirectory", "createDirectory"]
+                });
+
+                if (result.canceled) {
+                  return;
+                }
+
+                setPath(result.filePaths[0]);
+
+                setPayload({ ...payload, save_path: result.filePaths[0] });
+                return;
+              }
+
               const result = await window.showSaveDialog({
                 title: "Select Runtime Path",
                 defaultPath: path() || "",
                 filters: [
                   {
                     name:
-                      payload.target === "Curseforge"
-                        ? "ZIP Files"
-                        : "MRPACK Files",
+                      payload.target === "Modrinth"
+                        ? "MRPACK Files"
+                        : payload.target === "GDLPack"
+                          ? "GDLPACK Files"
+                          : "ZIP Files",
                     extensions: [
-                      payload.target === "Curseforge" ? "zip" : "mrpack"
+                      payload.target === "Modrinth"
+                        ? "mrpack"
+                        : payload.target === "GDLPack"
+                          ? "gdlpack"
+                          : "zip"
                     ]
                   }
                 ]
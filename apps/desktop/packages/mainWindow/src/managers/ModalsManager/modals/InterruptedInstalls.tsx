@@ -0,0 +1,97 @@
+import { rspc } from "@/utils/rspcClient";
+import { ModalProps, useModal } from "..";
+import ModalLayout from "../ModalLayout";
+import { Button, createNotification } from "@gd/ui";
+import { Trans, useTransContext } from "@gd/i18n";
+import { For, createEffect } from "solid-js";
+import { useGlobalStore } from "@/components/GlobalStoreContext";
+
+const InterruptedInstalls = (props: ModalProps) => {
+  const [t] = useTransContext();
+  const modalsContext = useModal();
+  const globalStore = useGlobalStore();
+  const addNotification = createNotification();
+
+  const interruptedInstalls = rspc.createQuery(() => ({
+    queryKey: ["instance.getInterruptedInstalls"]
+  }));
+
+  const onError = (error: { message: string }) => {
+    addNotification({
+      name: "Cannot handle interrupted install",
+      content: error.message,
+      type: "error"
+    });
+  };
+
+  const resumeMutation = rspc.createMutation(() => ({
+    mutationKey: ["instance.resumeInterruptedInstall"],
+    onError
+  }));
+
+  const dismissMutation = rspc.createMutation(() => ({
+    mutationKey: ["instance.dismissInterruptedInstall"],
+    onError
+  }));
+
+  const instanceName = (id: number) =>
+    globalStore.instances.data?.find((instance) => instance.id === id)?.name ||
+    id.toString();
+
+  createEffect(() => {
+    if (interruptedInstalls.data?.length === 0) {
+      modalsContext?.closeModal();
+    }
+  });
+
+  return (
+    <ModalLayout
+      noHeader={props.noHeader}
+      title={props?.title}
+      height="h-80"
+      width="w-120"
+    >
+      <div class="flex flex-col gap-4 h-full overflow-y-auto">
+        <div class="text-lightSlate-300">
+          <Trans key="interrupted_installs.text" />
+        </div>
+        <For each={interruptedInstalls.data}>
+          {(install) => (
+            <div class="flex justify-between items-center gap-4">
+              <div class="flex flex-col">
+                <span class="font-bold">
+                  {instanceName(install.instance_id)}
+                </span>
+                <span class="text-sm text-lightSlate-700">
+                  {t("interrupted_installs.files", {
+                    files: install.files,
+                    partial_files: install.partial_files
+                  })}
+                </span>
+              </div>
+              <div class="flex gap-2">
+                <Button
+                  type="secondary"
+                  onClick={() => {
+                    dismissMutation.mutate(install.instance_id);
+                  }}
+                >
+                  {t("interrupted_installs.dismiss")}
+                </Button>
+                <Button
+                  onClick={() => {
+                    resumeMutation.mutate(install.instance_id);
+                  }}
+                >
+                  {t("interrupted_installs.resume")}
+                </Button>
+              </div>
+            </div>
+          )}
+        </For>
+      </div>
+    </ModalLayout>
+  );
+};
+
+export default InterruptedInstalls;
@@ -224,7 +224,9 @@ const SingleEntity = (props: {
                               extensions:
                                 props.entity.entity === "CurseForgeZip"
                                   ? ["zip"]
-                                  : ["mrpack"]
+                                  : props.entity.entity === "GDLPack"
+                                    ? ["gdlpack"]
+                                    : ["mrpack"]
                             },
                             { name: "All Files", extensions: ["*"] }
                           ]
@@ -1,10 +1,13 @@
 import Sidebar from "@/components/Sidebar/library";
 import { Outlet } from "@solidjs/router";
 import ContentWrapper from "@/components/ContentWrapper";
-import { Show, onMount } from "solid-js";
+import { Show, createEffect, on, onMount } from "solid-js";
 import { rspc } from "@/utils/rspcClient";
 import { useModal } from "@/managers/ModalsManager";
 
+// only offer to resume interrupted installs once per app start
+let interruptedInstallsOffered = false;
+
 function Library() {
   const gridLayout = () => false;
   const modalsManager = useModal();
@@ -14,6 +17,9 @@ function Library() {
   const updateSettings = rspc.createMutation(() => ({
     mutationKey: ["settings.setSettings"]
   }));
+  const interruptedInstalls = rspc.createQuery(() => ({
+    queryKey: ["instance.getInterruptedInstalls"]
+  }));
 
   onMount(() => {
     console.log(settings.data?.lastAppVersion, __APP_VERSION__);
@@ -30,6 +36,20 @@ function Library() {
     }
   });
 
+  createEffect(
+    on(
+      () => interruptedInstalls.data,
+      (installs) => {
+        if (installs && installs.length > 0 && !interruptedInstallsOffered) {
+          interruptedInstallsOffered = true;
+          modalsManager?.openModal({
+            name: "interruptedInstalls"
+          });
+        }
+      }
+    )
+  );
+
   return (
     <>
       <Show when={gridLayout()}>
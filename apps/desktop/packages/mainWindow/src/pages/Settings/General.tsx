@@ -8,6 +8,7 @@ import { Show, createEffect } from "solid-js";
 import { createStore } from "solid-js/store";
 import {
   FELauncherActionOnGameLaunch,
+  FEProxySettingsUpdate,
   FEReleaseChannel,
   FESettings
 } from "@gd/core_module/bindings";
@@ -36,6 +37,23 @@ const General = () => {
     if (routeData.data.data) setSettings(routeData.data.data);
   });
 
+  // the stored password is only replaced when `password` is part of the update
+  const updateProxy = (proxy: Partial<FEProxySettingsUpdate>) => {
+    settingsMutation.mutate({
+      proxy: {
+        Set: {
+          http: settings.proxy?.http ?? null,
+          https: settings.proxy?.https ?? null,
+          socks5: settings.proxy?.socks5 ?? null,
+          username: settings.proxy?.username ?? null,
+          noProxy: settings.proxy?.noProxy ?? [],
+          caBundle: settings.proxy?.caBundle ?? null,
+          ...proxy
+        }
+      }
+    });
+  };
+
   const templateGameResolution = () => {
     return [
       { label: "854 x 480 (100%)", key: "Standard:854x480" },
@@ -121,6 +139,243 @@ const General = () => {
             />
           </RightHandSide>
         </Row>
+        <Row>
+          <Title
+            description={
+              <Trans key="settings:concurrent_downloads_per_host_text" />
+            }
+          >
+            <Trans key="settings:concurrent_downloads_per_host_title" />
+          </Title>
+          <RightHandSide>
+            <Dropdown
+              value={
+                settings.concurrentDownloadsPerHost?.toString() || "unlimited"
+              }
+              options={[
+                { label: t("settings:unlimited"), key: "unlimited" },
+                ...Array.from({ length: 20 }, (_, i) => ({
+                  label: (i + 1).toString(),
+                  key: (i + 1).toString()
+                }))
+              ]}
+              onChange={(downloads) => {
+                settingsMutation.mutate({
+                  concurrentDownloadsPerHost: {
+                    Set:
+                      downloads.key === "unlimited"
+                        ? null
+                        : parseInt(downloads.key as string, 10)
+                  }
+                });
+              }}
+            />
+          </RightHandSide>
+        </Row>
+        <Row>
+          <Title
+            description={<Trans key="settings:download_bandwidth_limit_text" />}
+          >
+            <Trans key="settings:download_bandwidth_limit_title" />
+          </Title>
+          <RightHandSide>
+            <Dropdown
+              value={settings.downloadBandwidthLimit?.toString() || "unlimited"}
+              options={[
+                { label: t("settings:unlimited"), key: "unlimited" },
+                ...[1, 2, 5, 10, 25, 50, 100].map((mb) => ({
+                  label: `${mb} MB/s`,
+                  key: (mb * 1024 * 1024).toString()
+                }))
+              ]}
+              onChange={(limit) => {
+                settingsMutation.mutate({
+                  downloadBandwidthLimit: {
+                    Set:
+                      limit.key === "unlimited"
+                        ? null
+                        : parseInt(limit.key as string, 10)
+                  }
+                });
+              }}
+            />
+          </RightHandSide>
+        </Row>
+        <Row>
+          <Title description={<Trans key="settings:mojang_mirror_text" />}>
+            <Trans key="settings:mojang_mirror_title" />
+          </Title>
+          <RightHandSide>
+            <Input
+              class="w-64"
+              placeholder="https://bmclapi2.bangbang93.com"
+              value={settings.mojangMirror || ""}
+              onChange={(e) => {
+                settingsMutation.mutate({
+                  mojangMirror: {
+                    Set: e.currentTarget.value.trim() || null
+                  }
+                });
+              }}
+            />
+          </RightHandSide>
+        </Row>
+        <Row>
+          <Title description={<Trans key="settings:offline_mode_text" />}>
+            <Trans key="settings:offline_mode_title" />
+          </Title>
+          <RightHandSide>
+            <Switch
+              checked={settings.offlineMode}
+              onChange={(e) => {
+                settingsMutation.mutate({
+                  offlineMode: {
+                    Set: e.currentTarget.checked
+                  }
+                });
+              }}
+            />
+          </RightHandSide>
+        </Row>
+        <Row>
+          <Title
+            description={<Trans key="settings:http_cache_size_limit_text" />}
+          >
+            <Trans key="settings:http_cache_size_limit_title" />
+          </Title>
+          <RightHandSide>
+            <Dropdown
+              value={settings.httpCacheSizeLimit?.toString()}
+              options={[64, 128, 256, 512, 1024].map((mb) => ({
+                label: `${mb} MB`,
+                key: mb.toString()
+              }))}
+              onChange={(limit) => {
+                settingsMutation.mutate({
+                  httpCacheSizeLimit: {
+                    Set: parseInt(limit.key as string, 10)
+                  }
+                });
+              }}
+            />
+          </RightHandSide>
+        </Row>
+        <Row>
+          <Title description={<Trans key="settings:proxy_text" />}>
+            <Trans key="settings:proxy_title" />
+          </Title>
+          <RightHandSide>
+            <div class="flex flex-col items-end gap-4">
+              <div class="flex items-center justify-end gap-4">
+                <div>
+                  <Trans key="settings:proxy_http" />
+                </div>
+                <Input
+                  class="w-64"
+                  placeholder="proxy.example.com:3128"
+                  value={settings.proxy?.http || ""}
+                  onChange={(e) => {
+                    updateProxy({
+                      http: e.currentTarget.value.trim() || null
+                    });
+                  }}
+                />
+              </div>
+              <div class="flex items-center justify-end gap-4">
+                <div>
+                  <Trans key="settings:proxy_https" />
+                </div>
+                <Input
+                  class="w-64"
+                  placeholder="proxy.example.com:3128"
+                  value={settings.proxy?.https || ""}
+                  onChange={(e) => {
+                    updateProxy({
+                      https: e.currentTarget.value.trim() || null
+                    });
+                  }}
+                />
+              </div>
+              <div class="flex items-center justify-end gap-4">
+                <div>
+                  <Trans key="settings:proxy_socks5" />
+                </div>
+                <Input
+                  class="w-64"
+                  placeholder="proxy.example.com:1080"
+                  value={settings.proxy?.socks5 || ""}
+                  onChange={(e) => {
+                    updateProxy({
+                      socks5: e.currentTarget.value.trim() || null
+                    });
+                  }}
+                />
+              </div>
+              <div class="flex items-center justify-end gap-4">
+                <div>
+                  <Trans key="settings:proxy_username" />
+                </div>
+                <Input
+                  class="w-64"
+                  value={settings.proxy?.username || ""}
+                  onChange={(e) => {
+                    updateProxy({
+                      username: e.currentTarget.value.trim() || null
+                    });
+                  }}
+                />
+              </div>
+              <div class="flex items-center justify-end gap-4">
+                <div>
+                  <Trans key="settings:proxy_password" />
+                </div>
+                <Input
+                  class="w-64"
+                  type="password"
+                  placeholder={settings.proxy?.hasPassword ? "••••••••" : ""}
+                  onChange={(e) => {
+                    updateProxy({
+                      password: { Set: e.currentTarget.value || null }
+                    });
+                  }}
+                />
+              </div>
+              <div class="flex items-center justify-end gap-4">
+                <div>
+                  <Trans key="settings:proxy_ca_bundle" />
+                </div>
+                <Input
+                  class="w-64"
+                  placeholder="/path/to/ca-bundle.pem"
+                  value={settings.proxy?.caBundle || ""}
+                  onChange={(e) => {
+                    updateProxy({
+                      caBundle: e.currentTarget.value.trim() || null
+                    });
+                  }}
+                />
+              </div>
+              <div class="flex items-center justify-end gap-4">
+                <div>
+                  <Trans key="settings:proxy_no_proxy" />
+                </div>
+                <Input
+                  class="w-64"
+                  placeholder="localhost, .example.com"
+                  value={settings.proxy?.noProxy.join(", ") || ""}
+                  onChange={(e) => {
+                    updateProxy({
+                      noProxy: e.currentTarget.value
+                        .split(",")
+                        .map((host) => host.trim())
+                        .filter((host) => host.length > 0)
+                    });
+                  }}
+                />
+              </div>
+            </div>
+          </RightHandSide>
+        </Row>
         <Row>
           <Title description={<Trans key="settings:game_resolution_text" />}>
             <Trans key="settings:game_resolution_title" />
@@ -6,6 +6,7 @@ import TechnicLogo from "/assets/images/icons/technic_logo.svg";
 import PrismLogo from "/assets/images/icons/prism_logo.svg";
 import ModrinthLogo from "/assets/images/icons/modrinth_logo.svg";
 import LegacyGDL from "/assets/images/icons/legacy_gdlauncher.svg";
+import VanillaLogo from "/assets/images/icons/vanilla.png";
 import {
   CFFEModSearchSortField,
   ImportEntity,
@@ -87,5 +88,17 @@ export const ENTITIES: Record<ImportEntity, EntityValue> = {
   MRPack: {
     translation: "entity.mrpack",
     icon: ModrinthLogo
+  },
+  MinecraftLauncher: {
+    translation: "entity.minecraftlauncher",
+    icon: VanillaLogo
+  },
+  Packwiz: {
+    translation: "entity.packwiz",
+    icon: VanillaLogo
+  },
+  GDLPack: {
+    translation: "entity.gdlpack",
+    icon: LegacyGDL
   }
 };
@@ -5,6 +5,19 @@ export type LogEntry = {
   logger: string;
   thread: string;
   message: string;
+  throwable: Throwable | null;
+};
+
+export type Throwable = {
+  class: string;
+  message: string | null;
+  frames: StackFrame[];
+  cause: Throwable | null;
+};
+
+export type StackFrame = {
+  method: string;
+  location: string | null;
 };
 
 export enum LogEntrySourceKind {
@@ -0,0 +1,17 @@
+-- CreateTable
+CREATE TABLE "DownloadJournalEntry" (
+    "id" INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    "createdAt" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "instanceId" INTEGER NOT NULL,
+    "task" TEXT NOT NULL,
+    "url" TEXT NOT NULL,
+    "mirrors" TEXT NOT NULL,
+    "path" TEXT NOT NULL,
+    "partFile" TEXT NOT NULL,
+    "checksums" TEXT NOT NULL,
+    "size" BIGINT,
+    CONSTRAINT "DownloadJournalEntry_instanceId_fkey" FOREIGN KEY ("instanceId") REFERENCES "Instance" ("id") ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- CreateIndex
+CREATE INDEX "DownloadJournalEntry_instanceId_idx" ON "DownloadJournalEntry"("instanceId");
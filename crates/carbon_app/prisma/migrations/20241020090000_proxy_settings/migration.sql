@@ -0,0 +1,8 @@
+-- AlterTable
+ALTER TABLE "AppConfiguration" ADD COLUMN "proxyHttp" TEXT;
+ALTER TABLE "AppConfiguration" ADD COLUMN "proxyHttps" TEXT;
+ALTER TABLE "AppConfiguration" ADD COLUMN "proxySocks" TEXT;
+ALTER TABLE "AppConfiguration" ADD COLUMN "proxyUsername" TEXT;
+ALTER TABLE "AppConfiguration" ADD COLUMN "proxyPassword" BLOB;
+ALTER TABLE "AppConfiguration" ADD COLUMN "proxyNoProxy" TEXT NOT NULL DEFAULT '';
+ALTER TABLE "AppConfiguration" ADD COLUMN "proxyCaBundle" TEXT;
@@ -0,0 +1,3 @@
+-- AlterTable
+ALTER TABLE "HTTPCache" ADD COLUMN "staleWhileRevalidateUntil" DATETIME;
+ALTER TABLE "HTTPCache" ADD COLUMN "staleIfErrorUntil" DATETIME;
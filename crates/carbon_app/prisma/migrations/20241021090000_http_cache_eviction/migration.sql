@@ -0,0 +1,22 @@
+-- AlterTable
+ALTER TABLE "AppConfiguration" ADD COLUMN "httpCacheSizeLimit" INTEGER NOT NULL DEFAULT 256;
+
+-- RedefineTables
+PRAGMA foreign_keys=OFF;
+CREATE TABLE "new_HTTPCache" (
+    "url" TEXT NOT NULL PRIMARY KEY,
+    "status_code" INTEGER NOT NULL,
+    "data" BLOB NOT NULL,
+    "expiresAt" DATETIME DEFAULT CURRENT_TIMESTAMP,
+    "staleWhileRevalidateUntil" DATETIME,
+    "staleIfErrorUntil" DATETIME,
+    "lastModified" TEXT,
+    "etag" TEXT,
+    "lastAccessedAt" DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+INSERT INTO "new_HTTPCache" ("url", "status_code", "data", "expiresAt", "staleWhileRevalidateUntil", "staleIfErrorUntil", "lastModified", "etag", "lastAccessedAt") SELECT "url", "status_code", "data", "expiresAt", "staleWhileRevalidateUntil", "staleIfErrorUntil", "lastModified", "etag", CURRENT_TIMESTAMP FROM "HTTPCache";
+DROP TABLE "HTTPCache";
+ALTER TABLE "new_HTTPCache" RENAME TO "HTTPCache";
+CREATE INDEX "HTTPCache_lastAccessedAt_idx" ON "HTTPCache"("lastAccessedAt");
+PRAGMA foreign_key_check;
+PRAGMA foreign_keys=ON;
@@ -0,0 +1,2 @@
+-- AlterTable
+ALTER TABLE "AppConfiguration" ADD COLUMN "offlineMode" BOOLEAN NOT NULL DEFAULT false;
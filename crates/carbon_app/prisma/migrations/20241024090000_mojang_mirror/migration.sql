@@ -0,0 +1,2 @@
+-- AlterTable
+ALTER TABLE "AppConfiguration" ADD COLUMN "mojangMirror" TEXT;
@@ -185,6 +185,35 @@ pub(super) fn mount() -> RouterBuilder<App> {
             Ok(result)
         }
 
+        query GET_INTERRUPTED_INSTALLS[app, args: ()] {
+            Ok(app.instance_manager()
+                .interrupted_installs()
+                .await?
+                .into_iter()
+                .map(FEInterruptedInstall::from)
+                .collect::<Vec<_>>())
+        }
+
+        mutation RESUME_INTERRUPTED_INSTALL[app, id: FEInstanceId] {
+            let vtask_id = app.instance_manager()
+                .resume_interrupted_install(id.into())
+                .await?;
+
+            app.invalidate(GET_INTERRUPTED_INSTALLS, None);
+
+            Ok(FETaskId::from(vtask_id))
+        }
+
+        mutation DISMISS_INTERRUPTED_INSTALL[app, id: FEInstanceId] {
+            app.instance_manager()
+                .dismiss_interrupted_install(id.into())
+                .await?;
+
+            app.invalidate(GET_INTERRUPTED_INSTALLS, None);
+
+            Ok(())
+        }
+
         query INSTANCE_MODS[app, id: Option<FEInstanceId>] {
             let Some(id) = id else {
                 return Ok(None);
@@ -243,6 +272,15 @@ pub(super) fn mount() -> RouterBuilder<App> {
                .collect::<Vec<_>>())
         }
 
+        query GET_LOG_EXCEPTIONS[app, id: GameLogId] {
+            Ok(app.instance_manager()
+               .get_log_exceptions(id.into())
+               .await?
+               .into_iter()
+               .map(GameLogException::from)
+               .collect::<Vec<_>>())
+        }
+
         mutation DELETE_LOG[app, id: GameLogId] {
             app.instance_manager()
                 .delete_log(id.into())
@@ -793,6 +831,20 @@ struct GameLogEntry {
     active: bool,
 }
 
+#[derive(Type, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GameLogException {
+    class: String,
+    message: Option<String>,
+    /// The class names of the exceptions that caused this one, outermost first.
+    causes: Vec<String>,
+    /// The method that threw the exception.
+    thrown_at: Option<String>,
+    count: u32,
+    first_line: u32,
+    last_seen: DateTime<Utc>,
+}
+
 #[derive(Type, Debug, Deserialize)]
 enum CreateInstanceVersion {
     Version(GameVersion),
@@ -925,6 +977,27 @@ impl From<InstanceModpackInfo> for FEInstanceModpackInfo {
     }
 }
 
+#[derive(Type, Debug, Serialize)]
+struct FEInterruptedInstall {
+    instance_id: FEInstanceId,
+    task: Translation,
+    files: u32,
+    partial_files: u32,
+    interrupted_at: DateTime<Utc>,
+}
+
+impl From<manager::journal::InterruptedInstall> for FEInterruptedInstall {
+    fn from(value: manager::journal::InterruptedInstall) -> Self {
+        Self {
+            instance_id: value.instance_id.into(),
+            task: value.task,
+            files: value.files,
+            partial_files: value.partial_files,
+            interrupted_at: value.interrupted_at.into(),
+        }
+    }
+}
+
 #[derive(Type, Debug, Serialize, Deserialize)]
 pub struct MemoryRange {
     pub min_mb: u16,
@@ -1061,6 +1134,10 @@ struct ExportEntry {
 enum ExportTarget {
     Curseforge,
     Modrinth,
+    MultiMC,
+    Packwiz,
+    ServerPack,
+    GDLPack,
 }
 
 #[derive(Type, Deserialize, Debug)]
@@ -1084,6 +1161,9 @@ pub enum ImportEntity {
     FTB,
     MultiMC,
     PrismLauncher,
+    MinecraftLauncher,
+    Packwiz,
+    GDLPack,
 }
 
 #[derive(Type, Debug, Serialize)]
@@ -1515,6 +1595,27 @@ impl From<domain::GameLogEntry> for GameLogEntry {
     }
 }
 
+impl From<manager::log::LogException> for GameLogException {
+    fn from(value: manager::log::LogException) -> Self {
+        let throwable = value.throwable;
+
+        Self {
+            causes: throwable
+                .chain()
+                .skip(1)
+                .map(|cause| cause.class.clone())
+                .collect(),
+            thrown_at: throwable.frames.first().map(|frame| frame.method.clone()),
+            class: throwable.class,
+            message: throwable.message,
+            count: value.count,
+            first_line: value.first_line as u32,
+            last_seen: DateTime::<Utc>::from_timestamp_millis(value.last_timestamp as i64)
+                .unwrap_or_default(),
+        }
+    }
+}
+
 impl From<InstanceFolder> for domain::InstanceFolder {
     fn from(value: InstanceFolder) -> Self {
         match value {
@@ -1597,6 +1698,9 @@ impl From<ImportEntity> for importer::Entity {
             ImportEntity::FTB => Self::FTB,
             ImportEntity::MultiMC => Self::MultiMC,
             ImportEntity::PrismLauncher => Self::PrismLauncher,
+            ImportEntity::MinecraftLauncher => Self::MinecraftLauncher,
+            ImportEntity::Packwiz => Self::Packwiz,
+            ImportEntity::GDLPack => Self::GDLPack,
         }
     }
 }
@@ -1616,6 +1720,9 @@ impl From<importer::Entity> for ImportEntity {
             backend::FTB => Self::FTB,
             backend::MultiMC => Self::MultiMC,
             backend::PrismLauncher => Self::PrismLauncher,
+            backend::MinecraftLauncher => Self::MinecraftLauncher,
+            backend::Packwiz => Self::Packwiz,
+            backend::GDLPack => Self::GDLPack,
         }
     }
 }
@@ -1661,6 +1768,10 @@ impl From<ExportTarget> for domain::ExportTarget {
         match value {
             ExportTarget::Curseforge => Self::Curseforge,
             ExportTarget::Modrinth => Self::Modrinth,
+            ExportTarget::MultiMC => Self::MultiMC,
+            ExportTarget::Packwiz => Self::Packwiz,
+            ExportTarget::ServerPack => Self::ServerPack,
+            ExportTarget::GDLPack => Self::GDLPack,
         }
     }
 }
@@ -103,6 +103,7 @@ keys! {
         LAUNCH_INSTANCE                             = "launchInstance";
         KILL_INSTANCE                               = "killInstance";
         GET_LOGS                                    = "getLogs";
+        GET_LOG_EXCEPTIONS                          = "getLogExceptions";
         DELETE_LOG                                  = "deleteLog";
         OPEN_INSTANCE_FOLDER                        = "openInstanceFolder";
         ENABLE_MOD                                  = "enableMod";
@@ -122,6 +123,9 @@ keys! {
         EXPLORE                                     = "explore";
         EXPORT                                      = "export";
         GET_MODPACK_INFO                            = "getModpackInfo";
+        GET_INTERRUPTED_INSTALLS                    = "getInterruptedInstalls";
+        RESUME_INTERRUPTED_INSTALL                  = "resumeInterruptedInstall";
+        DISMISS_INTERRUPTED_INSTALL                 = "dismissInterruptedInstall";
     }
 
     vtask {
@@ -135,11 +139,17 @@ keys! {
         SET_SETTINGS                                = "setSettings";
         GET_TERMS_OF_SERVICE_BODY                   = "getTermsOfServiceBody";
         GET_PRIVACY_STATEMENT_BODY                  = "getPrivacyStatementBody";
+        GET_NETWORK_STATUS                          = "getNetworkStatus";
+        CHECK_CONNECTIVITY                          = "checkConnectivity";
     }
 
     systeminfo {
         GET_TOTAL_RAM                               = "getTotalRAM";
         GET_USED_RAM                                = "getUsedRAM";
+        GET_DOWNLOAD_STORE_USAGE                    = "getDownloadStoreUsage";
+        CLEAN_DOWNLOAD_STORE                        = "cleanDownloadStore";
+        GET_HTTP_CACHE_STATS                        = "getHttpCacheStats";
+        PURGE_HTTP_CACHE                            = "purgeHttpCache";
     }
 
     modplatforms {
@@ -7,11 +7,12 @@ use specta::Type;
 use crate::{
     api::{
         keys::settings::{
-            GET_PRIVACY_STATEMENT_BODY, GET_SETTINGS, GET_TERMS_OF_SERVICE_BODY, SET_SETTINGS,
+            CHECK_CONNECTIVITY, GET_NETWORK_STATUS, GET_PRIVACY_STATEMENT_BODY, GET_SETTINGS,
+            GET_TERMS_OF_SERVICE_BODY, SET_SETTINGS,
         },
         router::router,
     },
-    managers::App,
+    managers::{connectivity::NetworkStatus, App},
 };
 
 use super::{
@@ -48,6 +49,32 @@ pub(super) fn mount() -> RouterBuilder<App> {
                 .fetch_privacy_statement_body()
                 .await
         }
+
+        query GET_NETWORK_STATUS[app, _args: ()] {
+            Ok(FENetworkStatus::from(app.connectivity_manager().status()))
+        }
+
+        mutation CHECK_CONNECTIVITY[app, _args: ()] {
+            Ok(app.connectivity_manager().check_connectivity().await)
+        }
+    }
+}
+
+#[derive(Type, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct FENetworkStatus {
+    offline: bool,
+    forced_offline: bool,
+    unreachable: bool,
+}
+
+impl From<NetworkStatus> for FENetworkStatus {
+    fn from(value: NetworkStatus) -> Self {
+        Self {
+            offline: value.offline,
+            forced_offline: value.forced_offline,
+            unreachable: value.unreachable,
+        }
     }
 }
 
@@ -214,6 +241,11 @@ struct FESettings {
     release_channel: FEReleaseChannel,
     last_app_version: Option<String>,
     concurrent_downloads: i32,
+    concurrent_downloads_per_host: Option<i32>,
+    download_bandwidth_limit: Option<i32>,
+    mojang_mirror: Option<String>,
+    http_cache_size_limit: i32,
+    offline_mode: bool,
     download_dependencies: bool,
     launcher_action_on_game_launch: FELauncherActionOnGameLaunch,
     show_app_close_warning: bool,
@@ -235,6 +267,7 @@ struct FESettings {
     java_custom_args: String,
     auto_manage_java_system_profiles: bool,
     mod_sources: ModSources,
+    proxy: FEProxySettings,
     terms_and_privacy_accepted: bool,
     metrics_enabled: bool,
     metrics_enabled_last_update: Option<chrono::DateTime<chrono::FixedOffset>>,
@@ -254,6 +287,11 @@ impl TryFrom<crate::db::app_configuration::Data> for FESettings {
             release_channel: data.release_channel.try_into()?,
             last_app_version: data.last_app_version,
             concurrent_downloads: data.concurrent_downloads,
+            concurrent_downloads_per_host: data.concurrent_downloads_per_host,
+            download_bandwidth_limit: data.download_bandwidth_limit,
+            mojang_mirror: data.mojang_mirror,
+            http_cache_size_limit: data.http_cache_size_limit,
+            offline_mode: data.offline_mode,
             download_dependencies: data.download_dependencies,
             show_news: data.show_news,
             show_featured: data.show_featured,
@@ -296,6 +334,20 @@ impl TryFrom<crate::db::app_configuration::Data> for FESettings {
                     .map(|r| r.map(ModPlatform::from))
                     .collect::<Result<_, _>>()?,
             },
+            proxy: FEProxySettings {
+                http: data.proxy_http,
+                https: data.proxy_https,
+                socks5: data.proxy_socks,
+                username: data.proxy_username,
+                has_password: data.proxy_password.is_some(),
+                no_proxy: data
+                    .proxy_no_proxy
+                    .split(',')
+                    .filter(|host| !host.is_empty())
+                    .map(ToString::to_string)
+                    .collect(),
+                ca_bundle: data.proxy_ca_bundle,
+            },
             terms_and_privacy_accepted: data.terms_and_privacy_accepted,
             metrics_enabled: data.metrics_enabled,
             metrics_enabled_last_update: data.metrics_enabled_last_update,
@@ -305,6 +357,48 @@ impl TryFrom<crate::db::app_configuration::Data> for FESettings {
     }
 }
 
+/// The password is never sent back, only whether one is stored.
+#[derive(Type, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FEProxySettings {
+    pub http: Option<String>,
+    pub https: Option<String>,
+    pub socks5: Option<String>,
+    pub username: Option<String>,
+    pub has_password: bool,
+    pub no_proxy: Vec<String>,
+    pub ca_bundle: Option<String>,
+}
+
+#[derive(Type, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FEProxySettingsUpdate {
+    pub http: Option<String>,
+    pub https: Option<String>,
+    pub socks5: Option<String>,
+    pub username: Option<String>,
+    /// the stored password is kept when this is missing
+    #[specta(optional)]
+    pub password: Option<Set<Option<String>>>,
+    pub no_proxy: Vec<String>,
+    pub ca_bundle: Option<String>,
+}
+
+// written by hand so the password never ends up in the logs of `set_settings`
+impl std::fmt::Debug for FEProxySettingsUpdate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FEProxySettingsUpdate")
+            .field("http", &self.http)
+            .field("https", &self.https)
+            .field("socks5", &self.socks5)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "<redacted>"))
+            .field("no_proxy", &self.no_proxy)
+            .field("ca_bundle", &self.ca_bundle)
+            .finish()
+    }
+}
+
 #[derive(Type, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub enum FELauncherActionOnGameLaunch {
@@ -362,6 +456,16 @@ pub struct FESettingsUpdate {
     #[specta(optional)]
     pub concurrent_downloads: Option<Set<i32>>,
     #[specta(optional)]
+    pub concurrent_downloads_per_host: Option<Set<Option<i32>>>,
+    #[specta(optional)]
+    pub download_bandwidth_limit: Option<Set<Option<i32>>>,
+    #[specta(optional)]
+    pub mojang_mirror: Option<Set<Option<String>>>,
+    #[specta(optional)]
+    pub http_cache_size_limit: Option<Set<i32>>,
+    #[specta(optional)]
+    pub offline_mode: Option<Set<bool>>,
+    #[specta(optional)]
     pub download_dependencies: Option<Set<bool>>,
     #[specta(optional)]
     pub instances_sort_by: Option<Set<InstancesSortBy>>,
@@ -404,6 +508,8 @@ pub struct FESettingsUpdate {
     #[specta(optional)]
     pub mod_sources: Option<Set<ModSources>>,
     #[specta(optional)]
+    pub proxy: Option<Set<FEProxySettingsUpdate>>,
+    #[specta(optional)]
     pub terms_and_privacy_accepted: Option<Set<bool>>,
     #[specta(optional)]
     pub metrics_enabled: Option<Set<bool>>,
@@ -1,11 +1,16 @@
 use rspc::RouterBuilder;
+use serde::{Deserialize, Serialize};
+use specta::Type;
 
 use crate::{
     api::{
-        keys::systeminfo::{GET_TOTAL_RAM, GET_USED_RAM},
+        keys::systeminfo::{
+            CLEAN_DOWNLOAD_STORE, GET_DOWNLOAD_STORE_USAGE, GET_HTTP_CACHE_STATS, GET_TOTAL_RAM,
+            GET_USED_RAM, PURGE_HTTP_CACHE,
+        },
         router::router,
     },
-    managers::App,
+    managers::{http_cache::HttpCacheStats, App},
 };
 
 pub(super) fn mount() -> RouterBuilder<App> {
@@ -17,5 +22,110 @@ pub(super) fn mount() -> RouterBuilder<App> {
         query GET_USED_RAM[app, _args: ()] {
             Ok(app.system_info_manager().get_used_ram().await.to_string())
         }
+
+        query GET_DOWNLOAD_STORE_USAGE[app, _args: ()] {
+            app.download_manager()
+                .store_usage()
+                .await
+                .map(FEDownloadStoreUsage::from)
+        }
+
+        mutation CLEAN_DOWNLOAD_STORE[app, _args: ()] {
+            app.download_manager()
+                .gc_store()
+                .await?
+                .map(FEDownloadStoreUsage::from)
+                .ok_or_else(|| anyhow::anyhow!("The download store is in use by running downloads"))
+        }
+
+        query GET_HTTP_CACHE_STATS[app, _args: ()] {
+            app.http_cache_manager()
+                .stats()
+                .await
+                .map(FEHttpCacheStats::from)
+        }
+
+        mutation PURGE_HTTP_CACHE[app, args: FEHttpCachePurge] {
+            let removed = app.http_cache_manager()
+                .purge(&args.host_or_prefix)
+                .await?;
+
+            app.invalidate(GET_HTTP_CACHE_STATS, None);
+            Ok(removed as u32)
+        }
+    }
+}
+
+#[derive(Type, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct FEDownloadStoreUsage {
+    files: u32,
+    // stringified like the RAM values since it can exceed the JS safe integer range
+    bytes: String,
+}
+
+impl From<carbon_net::StoreUsage> for FEDownloadStoreUsage {
+    fn from(value: carbon_net::StoreUsage) -> Self {
+        Self {
+            files: value.files as u32,
+            bytes: value.bytes.to_string(),
+        }
+    }
+}
+
+#[derive(Type, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct FEHttpCacheStats {
+    entries: u32,
+    bytes: String,
+    hits: u32,
+    misses: u32,
+    /// share of requests since startup answered from the cache, between 0 and 1
+    hit_rate: f32,
+    hosts: Vec<FEHttpCacheHostStats>,
+}
+
+#[derive(Type, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct FEHttpCacheHostStats {
+    host: String,
+    entries: u32,
+    bytes: String,
+    hits: u32,
+    misses: u32,
+}
+
+#[derive(Type, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct FEHttpCachePurge {
+    /// a host like `api.modrinth.com`, or a url prefix
+    host_or_prefix: String,
+}
+
+impl From<HttpCacheStats> for FEHttpCacheStats {
+    fn from(value: HttpCacheStats) -> Self {
+        let requests = value.hits + value.misses;
+
+        Self {
+            entries: value.entries as u32,
+            bytes: value.bytes.to_string(),
+            hits: value.hits as u32,
+            misses: value.misses as u32,
+            hit_rate: match requests {
+                0 => 0.0,
+                _ => value.hits as f32 / requests as f32,
+            },
+            hosts: value
+                .hosts
+                .into_iter()
+                .map(|(host, stats)| FEHttpCacheHostStats {
+                    host,
+                    entries: stats.entries as u32,
+                    bytes: stats.bytes.to_string(),
+                    hits: stats.hits as u32,
+                    misses: stats.misses as u32,
+                })
+                .collect(),
+        }
     }
 }
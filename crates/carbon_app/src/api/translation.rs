@@ -1,7 +1,7 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use specta::Type;
 
-#[derive(Debug, Type, Serialize, Clone, PartialEq)]
+#[derive(Debug, Type, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(tag = "translation", content = "args")]
 pub enum Translation {
     #[cfg(test)]
@@ -46,6 +46,20 @@ pub enum Translation {
     InstanceImportMrpackMalformed,
     InstanceImportMrpackMissingManifest,
     InstanceImportMrpackMalformedManifest,
+    InstanceImportMmcMissingPack,
+    InstanceImportMmcMalformedPack,
+    InstanceImportAtlBadConfigFile,
+    InstanceImportAtlUnsupportedLoader,
+    InstanceImportTechnicMissingVersion,
+    InstanceImportTechnicMalformedVersion,
+    InstanceImportFtbBadConfigFile,
+    InstanceImportFtbUnsupportedLoader,
+    InstanceImportMinecraftLauncherMissingGameDir,
+    InstanceImportPackwizMalformedPack,
+    InstanceImportPackwizUnsupportedLoader,
+    InstanceImportGdlpackMalformed,
+    InstanceImportGdlpackMissingManifest,
+    InstanceImportGdlpackMalformedManifest,
     InstanceExport,
     InstanceExportScanningMods,
     InstanceExportCacheMods,
@@ -75,6 +75,7 @@ pub enum Progress {
 pub struct FESubtask {
     name: Translation,
     progress: FESubtaskProgress,
+    download_hosts: Vec<String>,
 }
 
 #[derive(Type, Serialize)]
@@ -116,6 +117,7 @@ impl From<domain::Subtask> for FESubtask {
         Self {
             name: value.name.into(),
             progress: value.progress.into(),
+            download_hosts: value.download_hosts,
         }
     }
 }
@@ -1,23 +1,188 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, PoisonError};
+
 use anyhow::anyhow;
 use axum::http::Extensions;
 use chrono::{DateTime, Duration, Utc};
+use reqwest::header::{HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH};
 use reqwest::{Method, Request, Response, StatusCode};
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, Middleware, Next, Result};
 
 use crate::{
     db::{
-        http_cache::{SetParam, WhereParam},
+        http_cache::{self, SetParam, WhereParam},
         read_filters::StringFilter,
     },
-    managers::UnsafeAppRef,
+    managers::{App, UnsafeAppRef},
 };
 
+// set on background revalidation requests so they skip the stale entry they are refreshing
+const REVALIDATE_HEADER: &str = "revalidate-cache";
+
 pub fn new_client(app: UnsafeAppRef, client_builder: ClientBuilder) -> ClientWithMiddleware {
-    client_builder.with(CacheMiddleware { app }).build()
+    client_builder
+        .with(CacheMiddleware {
+            app,
+            revalidating: Default::default(),
+        })
+        .build()
 }
 
 struct CacheMiddleware {
     app: UnsafeAppRef,
+    /// urls with a background revalidation in flight
+    revalidating: Arc<Mutex<HashSet<String>>>,
+}
+
+/// Caching rules a response carries in its headers.
+#[derive(Debug, Default)]
+struct CachePolicy {
+    expires: Option<DateTime<Utc>>,
+    stale_while_revalidate_until: Option<DateTime<Utc>>,
+    stale_if_error_until: Option<DateTime<Utc>>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl CachePolicy {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let mut expires = None::<DateTime<Utc>>;
+        let mut stale_while_revalidate = None::<u32>;
+        let mut stale_if_error = None::<u32>;
+
+        if let Some(cache_control) = headers
+            .get("cache-control")
+            .and_then(|header| header.to_str().ok())
+        {
+            let directives = cache_control.split(',').map(|s| s.trim());
+
+            let mut max_age = None::<u32>;
+            let mut no_store = false;
+
+            for directive in directives {
+                let (directive, value) = match directive.split_once('=') {
+                    Some((d, v)) => (d, Some(v)),
+                    None => (directive, None),
+                };
+
+                match (directive, value) {
+                    ("max-age", Some(value)) => {
+                        max_age = value.parse::<u32>().ok();
+                    }
+                    ("stale-while-revalidate", Some(value)) => {
+                        stale_while_revalidate = value.parse::<u32>().ok();
+                    }
+                    ("stale-if-error", Some(value)) => {
+                        stale_if_error = value.parse::<u32>().ok();
+                    }
+                    ("no-store", None) => {
+                        no_store = true;
+                    }
+                    _ => {}
+                }
+            }
+
+            if no_store {
+                return Self::default();
+            }
+
+            expires = max_age.map(|offset| Utc::now() + Duration::seconds(offset as i64));
+        }
+
+        let expires = expires.or_else(|| {
+            headers
+                .get("expires")
+                .and_then(|header| header.to_str().ok())
+                .and_then(|header| httpdate::parse_http_date(header).ok())
+                .map(DateTime::<Utc>::from)
+        });
+
+        let stale_until =
+            |extension: Option<u32>| Some(expires? + Duration::seconds(extension? as i64));
+
+        Self {
+            expires,
+            stale_while_revalidate_until: stale_until(stale_while_revalidate),
+            stale_if_error_until: stale_until(stale_if_error),
+            etag: headers
+                .get("etag")
+                .and_then(|header| header.to_str().ok())
+                .map(String::from),
+            last_modified: headers
+                .get("last-modified")
+                .and_then(|header| header.to_str().ok())
+                .map(String::from),
+        }
+    }
+
+    fn is_cacheable(&self) -> bool {
+        self.expires.is_some() || self.etag.is_some() || self.last_modified.is_some()
+    }
+
+    fn into_params(self) -> Vec<SetParam> {
+        vec![
+            SetParam::SetExpiresAt(self.expires.map(Into::into)),
+            SetParam::SetStaleWhileRevalidateUntil(
+                self.stale_while_revalidate_until.map(Into::into),
+            ),
+            SetParam::SetStaleIfErrorUntil(self.stale_if_error_until.map(Into::into)),
+            SetParam::SetLastModified(self.last_modified),
+            SetParam::SetEtag(self.etag),
+        ]
+    }
+}
+
+fn build_cached(status: i32, body: Vec<u8>, cached: bool) -> std::result::Result<Response, ()> {
+    let mut response = hyper::Response::builder()
+        .status(StatusCode::from_u16(status.try_into().map_err(|_| ())?).map_err(|_| ())?);
+
+    if cached {
+        response = response.header("Cached", "true");
+    }
+
+    Ok(response.body(body).map_err(|_| ())?.into())
+}
+
+fn offline_error(req: &Request) -> reqwest_middleware::Error {
+    reqwest_middleware::Error::Middleware(anyhow!(
+        "Cannot reach {} in offline mode, and no cached response is available",
+        req.url()
+    ))
+}
+
+impl CacheMiddleware {
+    /// Refreshes a stale entry without making the caller wait for it.
+    fn revalidate_in_background(&self, app: &App, req: &Request) {
+        let url = req.url().to_string();
+        let Some(mut req) = req.try_clone() else {
+            return;
+        };
+
+        if !self
+            .revalidating
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(url.clone())
+        {
+            return;
+        }
+
+        req.headers_mut()
+            .insert(REVALIDATE_HEADER, HeaderValue::from_static("true"));
+
+        let app = app.clone();
+        let revalidating = self.revalidating.clone();
+        tokio::spawn(async move {
+            if let Err(e) = app.reqwest_client.execute(req).await {
+                tracing::warn!({ error = ?e }, "Failed to revalidate cached response for {url}");
+            }
+
+            revalidating
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(&url);
+        });
+    }
 }
 
 #[async_trait::async_trait]
@@ -28,29 +193,21 @@ impl Middleware for CacheMiddleware {
         extensions: &mut Extensions,
         next: Next<'_>,
     ) -> Result<Response> {
+        let offline = carbon_net::is_offline();
+
         let headers = req.headers_mut();
         if let Some(_) = headers.remove("avoid-caching") {
+            if offline {
+                return Err(offline_error(&req));
+            }
+
             return next.run(req, extensions).await;
         }
+        let revalidating = headers.remove(REVALIDATE_HEADER).is_some();
 
         // SAFETY: Requests cannot be made before the appref is initialized
         let app = unsafe { self.app.upgrade() };
 
-        fn build_cached(
-            status: i32,
-            body: Vec<u8>,
-            cached: bool,
-        ) -> std::result::Result<Response, ()> {
-            let mut response = hyper::Response::builder()
-                .status(StatusCode::from_u16(status.try_into().map_err(|_| ())?).map_err(|_| ())?);
-
-            if cached {
-                response = response.header("Cached", "true");
-            }
-
-            Ok(response.body(body).map_err(|_| ())?.into())
-        }
-
         let method = req.method().clone();
 
         let mut cached = if method != Method::GET {
@@ -66,26 +223,115 @@ impl Middleware for CacheMiddleware {
                 .map_err(|e| reqwest_middleware::Error::Middleware(anyhow!(e)))?
         };
 
-        // return the cached value if fresh
-        if let Some(expires) = cached.as_ref().and_then(|c| c.expires_at) {
-            if expires > Utc::now() {
+        // in offline mode anything cached is good enough, no matter how old
+        if offline {
+            let Some(cached) = cached else {
+                return Err(offline_error(&req));
+            };
+
+            app.http_cache_manager().record_hit(&cached.url).await;
+            return build_cached(cached.status_code, cached.data, true).map_err(|_| {
+                reqwest_middleware::Error::Middleware(anyhow!("could not return cached response"))
+            });
+        }
+
+        if let Some(entry) = &cached {
+            let now = Utc::now();
+            let fresh = entry.expires_at.is_some_and(|expires| expires > now);
+            let revalidate_later = entry
+                .stale_while_revalidate_until
+                .is_some_and(|until| until > now);
+
+            // return the cached value if fresh, or stale but allowed to be served while refreshing
+            if !revalidating && (fresh || revalidate_later) {
+                if !fresh {
+                    self.revalidate_in_background(&app, &req);
+                }
+
                 let cached = cached.take().expect("cached was just asserted to be Some");
+                app.http_cache_manager().record_hit(&cached.url).await;
                 if let Ok(response) = build_cached(cached.status_code, cached.data, true) {
                     return Ok(response);
                 }
             }
         }
 
+        // ask the server whether the stale entry is still good instead of fetching it again
+        if let Some(entry) = &cached {
+            let headers = req.headers_mut();
+
+            if let Some(etag) = entry
+                .etag
+                .as_deref()
+                .and_then(|v| HeaderValue::from_str(v).ok())
+            {
+                headers.entry(IF_NONE_MATCH).or_insert(etag);
+            }
+
+            if let Some(last_modified) = entry
+                .last_modified
+                .as_deref()
+                .and_then(|v| HeaderValue::from_str(v).ok())
+            {
+                headers.entry(IF_MODIFIED_SINCE).or_insert(last_modified);
+            }
+        }
+
         let response = next.run(req, extensions).await;
+
+        let failed = match &response {
+            Ok(response) => response.status().is_server_error(),
+            Err(_) => true,
+        };
+        let serve_stale_on_error = cached
+            .as_ref()
+            .and_then(|c| c.stale_if_error_until)
+            .is_some_and(|until| until > Utc::now());
+
+        if failed && serve_stale_on_error {
+            let cached = cached.take().expect("cached was just asserted to be Some");
+            app.http_cache_manager().record_hit(&cached.url).await;
+            if let Ok(response) = build_cached(cached.status_code, cached.data, true) {
+                return Ok(response);
+            }
+        }
+
         let Ok(response) = response else {
             return response;
         };
         let headers = response.headers();
 
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cached {
+                let mut policy = CachePolicy::from_headers(headers);
+                policy.etag = policy.etag.or(cached.etag);
+                policy.last_modified = policy.last_modified.or(cached.last_modified);
+
+                let _ = app
+                    .prisma_client
+                    .http_cache()
+                    .update(
+                        http_cache::url::equals(cached.url.clone()),
+                        policy.into_params(),
+                    )
+                    .exec()
+                    .await;
+
+                app.http_cache_manager().record_hit(&cached.url).await;
+                return build_cached(cached.status_code, cached.data, true).map_err(|_| {
+                    reqwest_middleware::Error::Middleware(anyhow!(
+                        "could not return cached response"
+                    ))
+                });
+            }
+        }
+
+        // the server ignored the conditional request but the content did not change
         'use_cache: {
             if let Some(cached) = cached {
                 if let (Some(cached_etag), Some(etag)) = (cached.etag, headers.get("etag")) {
                     if Some(&cached_etag as &str) == etag.to_str().ok() {
+                        app.http_cache_manager().record_hit(&cached.url).await;
                         match build_cached(cached.status_code, cached.data, true) {
                             Ok(response) => return Ok(response),
                             Err(_) => break 'use_cache,
@@ -97,6 +343,7 @@ impl Middleware for CacheMiddleware {
                     (cached.last_modified, headers.get("last-modified"))
                 {
                     if Some(&cached_last_modified as &str) == last_modified.to_str().ok() {
+                        app.http_cache_manager().record_hit(&cached.url).await;
                         match build_cached(cached.status_code, cached.data, true) {
                             Ok(response) => return Ok(response),
                             Err(_) => break 'use_cache,
@@ -107,60 +354,13 @@ impl Middleware for CacheMiddleware {
         }
 
         if method == Method::GET {
-            let mut expires = None::<DateTime<Utc>>;
+            app.http_cache_manager().record_miss(response.url().as_str());
 
-            if let Some(cache_control) = headers
-                .get("cache-control")
-                .and_then(|header| header.to_str().ok())
-            {
-                let directives = cache_control.split(',').map(|s| s.trim());
-
-                let mut max_age = None::<u32>;
-                let mut no_store = false;
-
-                for directive in directives {
-                    let (directive, value) = match directive.split_once('=') {
-                        Some((d, v)) => (d, Some(v)),
-                        None => (directive, None),
-                    };
-
-                    match (directive, value) {
-                        ("max-age", Some(value)) => {
-                            max_age = value.parse::<u32>().ok();
-                        }
-                        ("no-store", None) => {
-                            no_store = true;
-                        }
-                        _ => {}
-                    }
-                }
-
-                if !no_store {
-                    expires = max_age.map(|offset| Utc::now() + Duration::seconds(offset as i64));
-                }
-            }
-
-            expires = expires.or_else(|| {
-                headers
-                    .get("expires")
-                    .and_then(|header| header.to_str().ok())
-                    .and_then(|header| httpdate::parse_http_date(header).ok())
-                    .map(DateTime::<Utc>::from)
-            });
-
-            let etag = headers
-                .get("etag")
-                .and_then(|header| header.to_str().ok())
-                .map(String::from);
-
-            let last_modified = headers
-                .get("last-modified")
-                .and_then(|header| header.to_str().ok())
-                .map(String::from);
+            let policy = CachePolicy::from_headers(headers);
 
             // ignoring `Vary`
 
-            if expires.is_some() || etag.is_some() || last_modified.is_some() {
+            if policy.is_cacheable() {
                 let url = response.url().to_string();
                 let status = response.status().as_u16() as i32;
                 let body = response.bytes().await?;
@@ -176,11 +376,7 @@ impl Middleware for CacheMiddleware {
                             url,
                             status,
                             body.to_vec(),
-                            vec![
-                                SetParam::SetExpiresAt(expires.map(Into::into)),
-                                SetParam::SetLastModified(last_modified),
-                                SetParam::SetEtag(etag),
-                            ],
+                            policy.into_params(),
                         ),
                     ))
                     .await;
@@ -202,9 +398,15 @@ impl Middleware for CacheMiddleware {
 
 #[cfg(test)]
 mod test {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
     use std::time::SystemTime;
 
-    use axum::{http::header, routing::get, Router};
+    use axum::{
+        http::{header, HeaderMap, StatusCode},
+        routing::get,
+        Router,
+    };
     use chrono::{Duration, Utc};
     use tokio::net::TcpListener;
 
@@ -231,6 +433,34 @@ mod test {
         }}
     }
 
+    async fn launch_router(router: Router) -> u16 {
+        let tcp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = tcp_listener.local_addr().unwrap().port();
+
+        tokio::spawn(async {
+            axum::serve(tcp_listener, router.into_make_service())
+                .await
+                .unwrap();
+        });
+
+        // let the server start
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+        port
+    }
+
+    async fn request_body(app: &App, port: u16) -> (bool, String) {
+        let response = app
+            .reqwest_client
+            .get(format!("http://127.0.0.1:{port}/"))
+            .send()
+            .await
+            .unwrap();
+
+        let cached = response.headers().get("Cached").is_some();
+        (cached, response.text().await.unwrap())
+    }
+
     async fn request_cached(app: &App, port: u16) -> bool {
         app.reqwest_client
             .get(format!("http://127.0.0.1:{port}/"))
@@ -298,4 +528,101 @@ mod test {
         assert!(!request_cached(&app, port).await);
         assert!(request_cached(&app, port).await);
     }
+
+    #[tokio::test]
+    async fn test_not_modified() {
+        let app = crate::setup_managers_for_test().await;
+
+        let full_responses = Arc::new(AtomicU32::new(0));
+        let counter = full_responses.clone();
+
+        let port = launch_router(Router::new().route(
+            "/",
+            get(move |headers: HeaderMap| {
+                let counter = counter.clone();
+                async move {
+                    let if_none_match = headers
+                        .get(header::IF_NONE_MATCH)
+                        .and_then(|v| v.to_str().ok());
+                    if if_none_match == Some("\"v1\"") {
+                        return (StatusCode::NOT_MODIFIED, [(header::ETAG, "\"v1\"")], "");
+                    }
+
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    (StatusCode::OK, [(header::ETAG, "\"v1\"")], "test")
+                }
+            }),
+        ))
+        .await;
+
+        assert_eq!(request_body(&app, port).await, (false, "test".to_string()));
+        assert_eq!(request_body(&app, port).await, (true, "test".to_string()));
+        assert_eq!(full_responses.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stale_while_revalidate() {
+        let app = crate::setup_managers_for_test().await;
+
+        let requests = Arc::new(AtomicU32::new(0));
+        let counter = requests.clone();
+
+        let port = launch_router(Router::new().route(
+            "/",
+            get(move || {
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    (
+                        [(
+                            header::CACHE_CONTROL,
+                            "max-age=1, stale-while-revalidate=60",
+                        )],
+                        format!("test{n}"),
+                    )
+                }
+            }),
+        ))
+        .await;
+
+        assert_eq!(request_body(&app, port).await, (false, "test0".to_string()));
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+        // the stale body is served right away while it is refreshed in the background
+        assert_eq!(request_body(&app, port).await, (true, "test0".to_string()));
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(request_body(&app, port).await, (true, "test1".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_stale_if_error() {
+        let app = crate::setup_managers_for_test().await;
+
+        let requests = Arc::new(AtomicU32::new(0));
+        let counter = requests.clone();
+
+        let port = launch_router(Router::new().route(
+            "/",
+            get(move || {
+                let status = match counter.fetch_add(1, Ordering::SeqCst) {
+                    0 => StatusCode::OK,
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+
+                async move {
+                    (
+                        status,
+                        [(header::CACHE_CONTROL, "max-age=1, stale-if-error=60")],
+                        "test",
+                    )
+                }
+            }),
+        ))
+        .await;
+
+        assert_eq!(request_body(&app, port).await, (false, "test".to_string()));
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        assert_eq!(request_body(&app, port).await, (true, "test".to_string()));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
 }
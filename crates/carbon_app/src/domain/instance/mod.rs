@@ -174,6 +174,10 @@ pub enum ExploreEntryType {
 pub enum ExportTarget {
     Curseforge,
     Modrinth,
+    MultiMC,
+    Packwiz,
+    ServerPack,
+    GDLPack,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
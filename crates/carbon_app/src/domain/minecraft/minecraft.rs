@@ -32,8 +32,6 @@ pub fn libraries_into_vec_downloadable(
 
         // Forge special case where downloads is not present but `url` defines the base url
         if let Some(base_url) = &library.url {
-            let checksum = None;
-
             let maven_path = library.name.into_path();
             let Ok(maven_url) = library.name.into_url(base_url) else {
                 continue;
@@ -41,8 +39,9 @@ pub fn libraries_into_vec_downloadable(
 
             files.push(carbon_net::Downloadable {
                 url: maven_url.to_string(),
+                mirrors: vec![],
                 path: PathBuf::from(base_path).join(maven_path),
-                checksum,
+                checksums: vec![],
                 size: None,
             });
         }
@@ -101,19 +100,20 @@ pub fn library_into_lib_downloadable(
 
     if let Some(artifact) = artifact {
         if let Some(url) = artifact.url {
-            let checksum = Some(carbon_net::Checksum::Sha1(artifact.sha1));
             return Some(carbon_net::Downloadable {
                 url,
+                mirrors: vec![],
                 path: PathBuf::from(base_path).join(artifact.path),
-                checksum,
+                checksums: vec![carbon_net::Checksum::Sha1(artifact.sha1)],
                 size: Some(artifact.size as u64),
             });
         }
     } else if let Some(base_url) = &library.url {
         return Some(carbon_net::Downloadable {
             url: format!("{}{}", base_url, library.name.path()),
+            mirrors: vec![],
             path: base_path.join(library.name.path()),
-            checksum: None,
+            checksums: vec![],
             size: None,
         });
     }
@@ -145,12 +145,11 @@ pub fn library_into_natives_downloadable(
         return None;
     };
 
-    let checksum = Some(carbon_net::Checksum::Sha1(mapping_class.clone().sha1));
-
     Some(carbon_net::Downloadable {
         url,
+        mirrors: vec![],
         path: PathBuf::from(base_path).join(mapping_class.clone().path),
-        checksum,
+        checksums: vec![carbon_net::Checksum::Sha1(mapping_class.clone().sha1)],
         size: Some(mapping_class.size as u64),
     })
 }
@@ -221,6 +220,30 @@ pub fn chain_lwjgl_libs_with_base_libs(
     libraries
 }
 
+/// Adds the same file on `mirror` as a fallback for files served by the Mojang hosts.
+///
+/// The mirror uses the BMCLAPI layout, libraries are under `/maven`, assets under `/assets` and
+/// everything else keeps its path.
+pub fn with_mojang_mirror(
+    downloadable: carbon_net::Downloadable,
+    mirror: &str,
+) -> carbon_net::Downloadable {
+    let Ok(url) = url::Url::parse(&downloadable.url) else {
+        return downloadable;
+    };
+
+    let prefix = match url.host_str() {
+        Some("libraries.minecraft.net") => "/maven",
+        Some("resources.download.minecraft.net") => "/assets",
+        Some("piston-data.mojang.com" | "piston-meta.mojang.com" | "launcher.mojang.com") => "",
+        _ => return downloadable,
+    };
+
+    let mirror_url = format!("{}{prefix}{}", mirror.trim_end_matches('/'), url.path());
+
+    downloadable.with_mirrors([mirror_url])
+}
+
 pub fn assets_index_into_vec_downloadable(
     assets_index: AssetsIndex,
     assets_path: &AssetsPath,
@@ -50,6 +50,41 @@ pub struct File {
     pub modules: Vec<FileModule>,
 }
 
+impl File {
+    /// Every hash curseforge publishes for the file, including the murmur2 fingerprint.
+    pub fn checksums(&self) -> Vec<carbon_net::Checksum> {
+        self.hashes
+            .iter()
+            .map(|hash| match hash.algo {
+                HashAlgo::Sha1 => carbon_net::Checksum::Sha1(hash.value.clone()),
+                HashAlgo::Md5 => carbon_net::Checksum::Md5(hash.value.clone()),
+            })
+            .chain([carbon_net::Checksum::Murmur2(self.file_fingerprint)])
+            .collect()
+    }
+}
+
+const CDN_HOSTS: [&str; 2] = [
+    "https://edge.forgecdn.net/",
+    "https://mediafilez.forgecdn.net/",
+];
+
+/// The same file on the other curseforge CDN hosts, to use as download mirrors.
+pub fn cdn_mirrors(download_url: &str) -> Vec<String> {
+    let Some(path) = CDN_HOSTS
+        .iter()
+        .find_map(|host| download_url.strip_prefix(host))
+    else {
+        return Vec::new();
+    };
+
+    CDN_HOSTS
+        .iter()
+        .filter(|host| !download_url.starts_with(*host))
+        .map(|host| format!("{host}{path}"))
+        .collect()
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FileDependency {
@@ -6,6 +6,7 @@ use serde::{de::Visitor, Deserialize, Serialize};
 
 pub mod curseforge;
 pub mod modrinth;
+pub mod packwiz;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(i32)]
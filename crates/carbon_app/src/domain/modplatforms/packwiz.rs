@@ -0,0 +1,151 @@
+//! The packwiz pack format, see <https://packwiz.infra.link/reference/pack-format/>.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Pack {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pack_format: Option<String>,
+    pub index: PackIndexReference,
+    pub versions: PackVersions,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackIndexReference {
+    pub file: String,
+    pub hash_format: HashFormat,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackVersions {
+    pub minecraft: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forge: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub neoforge: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fabric: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quilt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub liteloader: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Index {
+    pub hash_format: HashFormat,
+    #[serde(default)]
+    pub files: Vec<IndexFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct IndexFile {
+    /// path relative to the pack root, always using `/`
+    pub file: String,
+    pub hash: String,
+    /// overrides the index `hash-format` for this file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash_format: Option<HashFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub metafile: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub preserve: bool,
+}
+
+/// A `.pw.toml` file describing a single downloaded file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetaFile {
+    pub name: String,
+    pub filename: String,
+    #[serde(default)]
+    pub side: Side,
+    pub download: MetaFileDownload,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub option: Option<MetaFileOption>,
+    #[serde(default, skip_serializing_if = "MetaFileUpdate::is_empty")]
+    pub update: MetaFileUpdate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MetaFileDownload {
+    /// missing when the file has to be resolved through the platform api (`mode`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub hash_format: HashFormat,
+    pub hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetaFileOption {
+    #[serde(default)]
+    pub optional: bool,
+    #[serde(default)]
+    pub default: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetaFileUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub curseforge: Option<CurseforgeUpdate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modrinth: Option<ModrinthUpdate>,
+}
+
+impl MetaFileUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.curseforge.is_none() && self.modrinth.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CurseforgeUpdate {
+    pub file_id: u32,
+    pub project_id: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModrinthUpdate {
+    pub mod_id: String,
+    pub version: String,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Client,
+    Server,
+    #[default]
+    Both,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashFormat {
+    Sha1,
+    Sha256,
+    Sha512,
+    Md5,
+    Murmur2,
+}
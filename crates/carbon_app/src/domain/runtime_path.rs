@@ -329,6 +329,14 @@ impl DownloadPath {
     }
 }
 
+pub struct StorePath(PathBuf);
+
+impl StorePath {
+    pub fn to_path(&self) -> PathBuf {
+        self.0.clone()
+    }
+}
+
 impl RuntimePath {
     pub fn new(path: PathBuf) -> Self {
         Self(path)
@@ -369,6 +377,10 @@ impl RuntimePath {
     pub fn get_download(&self) -> DownloadPath {
         DownloadPath(self.0.join("download"))
     }
+
+    pub fn get_store(&self) -> StorePath {
+        StorePath(self.0.join("store"))
+    }
 }
 
 impl Deref for RuntimePath {
@@ -416,3 +428,69 @@ where
 
     Ok(())
 }
+
+/// Recursivley copy from `from` to `to` except when excluded by `filter`.
+/// Overwrites existing files. Unlike [`copy_dir_filter`], directories excluded by `filter` are
+/// skipped along with their contents.
+pub async fn copy_dir_pruned<F>(from: &Path, to: &Path, filter: F) -> anyhow::Result<()>
+where
+    F: for<'a> Fn(&'a Path) -> bool,
+{
+    let entries = walkdir::WalkDir::new(from)
+        .into_iter()
+        .filter_entry(|entry| {
+            let relpath = entry.path().strip_prefix(from).unwrap();
+            relpath.as_os_str().is_empty() || filter(relpath)
+        })
+        .filter_map(|entry| {
+            let Ok(entry) = entry else { return None };
+
+            let srcpath = entry.path().to_path_buf();
+            let relpath = srcpath.strip_prefix(from).unwrap();
+
+            let destpath = to.join(relpath);
+
+            Some(async move {
+                if entry.metadata()?.is_dir() {
+                    tokio::fs::create_dir_all(destpath).await?;
+                } else {
+                    tokio::fs::create_dir_all(destpath.parent().unwrap()).await?;
+                    tokio::fs::copy(srcpath, destpath).await?;
+                }
+
+                Ok::<_, anyhow::Error>(())
+            })
+        });
+
+    futures::future::join_all(entries)
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    #[tokio::test]
+    async fn test_copy_dir_pruned() {
+        let from = tempdir::TempDir::new("copy_from").unwrap();
+        let to = tempdir::TempDir::new("copy_to").unwrap();
+
+        std::fs::create_dir_all(from.path().join("mods")).unwrap();
+        std::fs::create_dir_all(from.path().join("natives/lwjgl")).unwrap();
+        std::fs::write(from.path().join("mods/a.jar"), "a").unwrap();
+        std::fs::write(from.path().join("natives/lwjgl/b.so"), "b").unwrap();
+        std::fs::write(from.path().join("instance.json"), "{}").unwrap();
+
+        super::copy_dir_pruned(from.path(), to.path(), |path| {
+            !matches!(path.to_str(), Some("instance.json" | "natives"))
+        })
+        .await
+        .unwrap();
+
+        assert!(to.path().join("mods/a.jar").is_file());
+        assert!(!to.path().join("natives").exists());
+        assert!(!to.path().join("instance.json").exists());
+    }
+}
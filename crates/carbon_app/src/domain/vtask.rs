@@ -25,6 +25,7 @@ pub enum Progress {
 pub struct Subtask {
     pub name: Translation,
     pub progress: SubtaskProgress,
+    pub download_hosts: Vec<String>,
 }
 
 #[derive(Debug, PartialEq)]
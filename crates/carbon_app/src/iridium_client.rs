@@ -59,7 +59,7 @@ pub fn get_client(gdl_base_api: String) -> reqwest_middleware::ClientBuilder {
         }
     }
 
-    let client = reqwest::Client::builder()
+    let client = carbon_net::proxy::client_builder()
         .user_agent(format!(
             "{} {}",
             env!("USER_AGENT_PREFIX"),
@@ -69,7 +69,7 @@ impl DeviceCode {
     #[tracing::instrument(skip(self))]
     pub async fn poll_ms_auth(&self) -> anyhow::Result<Result<MsAuth, DeviceCodeExpiredError>> {
         // Since we're polling, we don't want a retry system
-        let bare_client = reqwest::Client::new();
+        let bare_client = carbon_net::proxy::client_builder().build()?;
 
         loop {
             tokio::time::sleep(self.polling_interval).await;
@@ -18,7 +18,6 @@ use jwt::{Header, Token};
 use prisma_client_rust::{
     chrono::DateTime, prisma_errors::query_engine::RecordNotFound, Direction, QueryError,
 };
-use reqwest::Client;
 use reqwest_middleware::ClientBuilder;
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use serde::{Deserialize, Serialize};
@@ -708,11 +707,16 @@ impl<'s> ManagerRef<'s, AccountManager> {
     }
 
     pub async fn begin_enrollment(self) -> anyhow::Result<()> {
+        ensure!(
+            !carbon_net::is_offline(),
+            "Signing in requires a network connection, disable offline mode first"
+        );
+
         match &mut *self.active_enrollment.write().await {
             Some(_) => bail!(BeginEnrollmentStatusError::InProgress),
             enrollment @ None => {
                 let retry_policy = ExponentialBackoff::builder().build_with_max_retries(10);
-                let reqwest_client = Client::builder().build()?;
+                let reqwest_client = carbon_net::proxy::client_builder().build()?;
                 let client = ClientBuilder::new(reqwest_client)
                     .with(RetryTransientMiddleware::new_with_policy(retry_policy))
                     .build();
@@ -919,6 +923,12 @@ impl AccountRefreshService {
                     }
                 }
 
+                // keep using the stored tokens, a failed check must not invalidate them
+                if carbon_net::is_offline() {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    continue;
+                }
+
                 // TODO: there's not really a way to handle an error in here
                 if let Ok(accounts) = account_manager.get_account_entries().await {
                     // discard deleted accounts
@@ -971,6 +981,11 @@ impl AccountRefreshService {
             while let Some(app) = app.upgrade() {
                 let account_manager = app.account_manager();
 
+                if carbon_net::is_offline() {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    continue;
+                }
+
                 // TODO: there's not really a way to handle an error in here
                 if let Ok(accounts) = account_manager.get_account_entries().await {
                     for account in accounts {
@@ -0,0 +1,131 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::Duration;
+
+use crate::api::keys::settings::GET_NETWORK_STATUS;
+use crate::managers::modplatforms::modrinth::MODRINTH_API_BASE;
+
+use super::ManagerRef;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Consecutive failed checks needed before going offline, so a single slow or dropped request
+/// doesn't cut the launcher off the network.
+const FAILURES_BEFORE_UNREACHABLE: u32 = 3;
+
+/// Tracks whether the launcher should stay off the network.
+///
+/// Offline mode is on when the user enabled it or several connectivity checks in a row failed.
+/// While it is on, http requests are answered from the cache and downloads only accept files already on
+/// disk, see [`carbon_net::set_offline`].
+pub(crate) struct ConnectivityManager {
+    forced_offline: AtomicBool,
+    unreachable: AtomicBool,
+    failed_checks: AtomicU32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct NetworkStatus {
+    pub offline: bool,
+    /// offline mode was enabled by hand
+    pub forced_offline: bool,
+    /// the last connectivity checks all failed
+    pub unreachable: bool,
+}
+
+impl ConnectivityManager {
+    pub fn new() -> Self {
+        Self {
+            forced_offline: AtomicBool::new(false),
+            unreachable: AtomicBool::new(false),
+            failed_checks: AtomicU32::new(0),
+        }
+    }
+}
+
+impl ManagerRef<'_, ConnectivityManager> {
+    pub fn status(self) -> NetworkStatus {
+        NetworkStatus {
+            offline: carbon_net::is_offline(),
+            forced_offline: self.forced_offline.load(Ordering::Relaxed),
+            unreachable: self.unreachable.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn set_forced_offline(self, offline: bool) {
+        self.forced_offline.store(offline, Ordering::Relaxed);
+        self.apply();
+    }
+
+    fn apply(self) {
+        let offline =
+            self.forced_offline.load(Ordering::Relaxed) || self.unreachable.load(Ordering::Relaxed);
+
+        if carbon_net::is_offline() != offline {
+            tracing::info!(
+                "Offline mode {}",
+                if offline { "enabled" } else { "disabled" }
+            );
+            carbon_net::set_offline(offline);
+            self.app.invalidate(GET_NETWORK_STATUS, None);
+        }
+    }
+
+    /// Checks whether the launcher meta server or modrinth can be reached, updating offline mode
+    /// to match.
+    ///
+    /// Returns whether this check succeeded, offline mode is only enabled after
+    /// [`FAILURES_BEFORE_UNREACHABLE`] failed checks in a row.
+    pub async fn check_connectivity(self) -> bool {
+        let client = carbon_net::proxy::client_builder()
+            .timeout(CHECK_TIMEOUT)
+            .build();
+
+        // any response at all from any host means the network is up
+        let reachable = match client {
+            Ok(client) => {
+                let probes = [
+                    self.app.minecraft_manager().meta_base_url.to_string(),
+                    MODRINTH_API_BASE.to_string(),
+                ]
+                .map(|url| {
+                    let request = client.head(url);
+                    async move { request.send().await.is_ok() }
+                });
+
+                futures::future::join_all(probes)
+                    .await
+                    .into_iter()
+                    .any(|reachable| reachable)
+            }
+            Err(_) => false,
+        };
+
+        let unreachable = if reachable {
+            self.failed_checks.store(0, Ordering::Relaxed);
+            false
+        } else {
+            let failed_checks = self.failed_checks.fetch_add(1, Ordering::Relaxed) + 1;
+            tracing::debug!("Connectivity check failed ({failed_checks} in a row)");
+            failed_checks >= FAILURES_BEFORE_UNREACHABLE
+        };
+
+        self.unreachable.store(unreachable, Ordering::Relaxed);
+        self.apply();
+
+        reachable
+    }
+
+    pub async fn launch_background_tasks(self) {
+        let app = self.app.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                app.connectivity_manager().check_connectivity().await;
+            }
+        });
+    }
+}
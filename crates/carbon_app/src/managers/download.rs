@@ -365,6 +365,60 @@ impl ManagerRef<'_, DownloadManager> {
             complete_channel: complete_recv,
         }))
     }
+
+    /// The shared store mods are downloaded into before being linked into instances.
+    pub fn content_store(&self) -> carbon_net::ContentStore {
+        carbon_net::ContentStore::new(
+            self.app
+                .settings_manager()
+                .runtime_path
+                .get_store()
+                .to_path(),
+        )
+    }
+
+    pub async fn store_usage(&self) -> anyhow::Result<carbon_net::StoreUsage> {
+        let store = self.content_store();
+
+        Ok(tokio::task::spawn_blocking(move || store.disk_usage()).await??)
+    }
+
+    /// Remove store entries that are no longer used by any instance.
+    ///
+    /// Entries still linked into an instance folder are kept, as are the ones matching
+    /// a mod that is still indexed by the metadata cache.
+    /// Returns `None` when downloads into the store are in progress and nothing was removed.
+    pub async fn gc_store(&self) -> anyhow::Result<Option<carbon_net::StoreUsage>> {
+        let referenced = self
+            .app
+            .prisma_client
+            .mod_metadata()
+            .find_many(Vec::new())
+            .exec()
+            .await?
+            .into_iter()
+            .flat_map(|metadata| [hex::encode(metadata.sha_1), hex::encode(metadata.sha_512)])
+            .collect::<HashSet<_>>();
+
+        let store = self.content_store();
+
+        let removed =
+            tokio::task::spawn_blocking(move || store.gc(|_, hash| referenced.contains(hash)))
+                .await??;
+
+        let Some(removed) = removed else {
+            tracing::info!("Skipped cleaning the download store while downloads are in progress");
+            return Ok(None);
+        };
+
+        tracing::info!(
+            "Removed {} unreferenced files ({} bytes) from the download store",
+            removed.files,
+            removed.bytes
+        );
+
+        Ok(Some(removed))
+    }
 }
 
 pub struct DownloadHandleInner {
@@ -0,0 +1,345 @@
+use std::collections::BTreeMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use chrono::Utc;
+use prisma_client_rust::raw;
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::db::http_cache;
+
+use super::ManagerRef;
+
+const EVICTION_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+// keeps each delete well below sqlite's bound parameter limit
+const DELETE_BATCH_SIZE: usize = 500;
+
+pub(crate) struct HttpCacheManager {
+    /// requests since startup by host
+    requests: Mutex<BTreeMap<String, HostRequests>>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct HostRequests {
+    hits: u64,
+    misses: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct HttpCacheStats {
+    pub entries: u64,
+    pub bytes: u64,
+    /// requests answered from the cache since startup
+    pub hits: u64,
+    /// requests that had to fetch the full response since startup
+    pub misses: u64,
+    pub hosts: BTreeMap<String, HttpCacheHostStats>,
+}
+
+#[derive(Debug, Default)]
+pub struct HttpCacheHostStats {
+    pub entries: u64,
+    pub bytes: u64,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+#[derive(Deserialize)]
+struct EntrySize {
+    url: String,
+    size: i64,
+}
+
+impl HttpCacheManager {
+    pub fn new() -> Self {
+        Self {
+            requests: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn count_request(&self, url: &str, count: impl FnOnce(&mut HostRequests)) {
+        let mut requests = self.requests.lock().unwrap_or_else(PoisonError::into_inner);
+        count(requests.entry(host_of(url)).or_default());
+    }
+}
+
+fn host_of(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(ToString::to_string))
+        .unwrap_or_default()
+}
+
+impl ManagerRef<'_, HttpCacheManager> {
+    /// Counts a response served from the cache and marks the entry as recently used.
+    pub async fn record_hit(self, url: &str) {
+        self.count_request(url, |requests| requests.hits += 1);
+
+        let _ = self
+            .app
+            .prisma_client
+            .http_cache()
+            .update(
+                http_cache::url::equals(url.to_string()),
+                vec![http_cache::last_accessed_at::set(Utc::now().into())],
+            )
+            .exec()
+            .await;
+    }
+
+    pub fn record_miss(self, url: &str) {
+        self.count_request(url, |requests| requests.misses += 1);
+    }
+
+    /// Every cache entry with its size, least recently used first.
+    async fn entry_sizes(self) -> anyhow::Result<Vec<EntrySize>> {
+        Ok(self
+            .app
+            .prisma_client
+            ._query_raw(raw!(
+                "SELECT url, length(data) AS size FROM HTTPCache ORDER BY lastAccessedAt ASC"
+            ))
+            .exec()
+            .await?)
+    }
+
+    async fn delete_urls(self, urls: Vec<String>) -> anyhow::Result<()> {
+        for chunk in urls.chunks(DELETE_BATCH_SIZE) {
+            self.app
+                .prisma_client
+                .http_cache()
+                .delete_many(vec![http_cache::url::in_vec(chunk.to_vec())])
+                .exec()
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn stats(self) -> anyhow::Result<HttpCacheStats> {
+        let mut stats = HttpCacheStats::default();
+
+        let requests = self
+            .requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+
+        for (host, requests) in requests {
+            stats.hits += requests.hits;
+            stats.misses += requests.misses;
+
+            let host_stats = stats.hosts.entry(host).or_default();
+            host_stats.hits = requests.hits;
+            host_stats.misses = requests.misses;
+        }
+
+        for entry in self.entry_sizes().await? {
+            let size = entry.size as u64;
+            stats.entries += 1;
+            stats.bytes += size;
+
+            let host_stats = stats.hosts.entry(host_of(&entry.url)).or_default();
+            host_stats.entries += 1;
+            host_stats.bytes += size;
+        }
+
+        Ok(stats)
+    }
+
+    /// Removes every entry of `host`, or every entry whose url starts with it when it is a url.
+    ///
+    /// Returns the number of removed entries.
+    pub async fn purge(self, host_or_prefix: &str) -> anyhow::Result<u64> {
+        let urls = self
+            .entry_sizes()
+            .await?
+            .into_iter()
+            .map(|entry| entry.url)
+            .filter(|url| match host_or_prefix.contains("://") {
+                true => url.starts_with(host_or_prefix),
+                false => Url::parse(url).is_ok_and(|url| {
+                    url.host_str()
+                        .is_some_and(|host| host.eq_ignore_ascii_case(host_or_prefix))
+                }),
+            })
+            .collect::<Vec<_>>();
+
+        let removed = urls.len() as u64;
+        self.delete_urls(urls).await?;
+
+        Ok(removed)
+    }
+
+    /// Drops entries that can neither be served nor revalidated anymore, then the least
+    /// recently used ones until the cache fits in the configured size.
+    pub async fn evict(self) -> anyhow::Result<()> {
+        let now = Utc::now();
+
+        self.app
+            .prisma_client
+            .http_cache()
+            .delete_many(vec![
+                http_cache::expires_at::lt(now.into()),
+                http_cache::WhereParam::Or(vec![
+                    http_cache::stale_while_revalidate_until::equals(None),
+                    http_cache::stale_while_revalidate_until::lt(now.into()),
+                ]),
+                http_cache::WhereParam::Or(vec![
+                    http_cache::stale_if_error_until::equals(None),
+                    http_cache::stale_if_error_until::lt(now.into()),
+                ]),
+                http_cache::etag::equals(None),
+                http_cache::last_modified::equals(None),
+            ])
+            .exec()
+            .await?;
+
+        let size_limit = self
+            .app
+            .settings_manager()
+            .get_settings()
+            .await?
+            .http_cache_size_limit
+            .max(0) as u64
+            * 1024
+            * 1024;
+
+        let entries = self.entry_sizes().await?;
+        let mut total = entries.iter().map(|entry| entry.size as u64).sum::<u64>();
+
+        let urls = entries
+            .into_iter()
+            .take_while(|entry| {
+                let over = total > size_limit;
+                total = total.saturating_sub(entry.size as u64);
+                over
+            })
+            .map(|entry| entry.url)
+            .collect::<Vec<_>>();
+
+        if !urls.is_empty() {
+            tracing::info!(
+                "Evicting {} least recently used HTTP cache entries",
+                urls.len()
+            );
+            self.delete_urls(urls).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn launch_background_tasks(self) {
+        let app = self.app.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(EVICTION_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                if let Err(e) = app.http_cache_manager().evict().await {
+                    tracing::error!({ error = ?e }, "Failed to evict HTTP cache entries");
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Utc};
+
+    use crate::db::{app_configuration, http_cache};
+
+    #[tokio::test]
+    async fn test_evict_and_purge() {
+        let app = crate::setup_managers_for_test().await;
+
+        let mib = vec![0u8; 1024 * 1024];
+        for (i, url) in [
+            "https://api.modrinth.com/v2/old",
+            "https://api.modrinth.com/v2/new",
+            "https://api.curseforge.com/v1/mods",
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            app.prisma_client
+                .http_cache()
+                .create(
+                    url.to_string(),
+                    200,
+                    mib.clone(),
+                    vec![
+                        http_cache::etag::set(Some("etag".to_string())),
+                        http_cache::last_accessed_at::set(
+                            (Utc::now() - Duration::minutes(10 - i as i64)).into(),
+                        ),
+                    ],
+                )
+                .exec()
+                .await
+                .unwrap();
+        }
+
+        let requests = |stats: &super::HttpCacheStats, host: &str| {
+            stats
+                .hosts
+                .get(host)
+                .map_or((0, 0), |host| (host.hits, host.misses))
+        };
+
+        let before = app.http_cache_manager().stats().await.unwrap();
+
+        app.http_cache_manager()
+            .record_hit("https://api.modrinth.com/v2/new")
+            .await;
+        app.http_cache_manager()
+            .record_miss("https://api.curseforge.com/v1/mods/1");
+
+        let stats = app.http_cache_manager().stats().await.unwrap();
+        assert_eq!(stats.entries, 3);
+        assert_eq!(stats.hosts["api.modrinth.com"].entries, 2);
+
+        let (hits, misses) = requests(&before, "api.modrinth.com");
+        assert_eq!(requests(&stats, "api.modrinth.com"), (hits + 1, misses));
+        let (hits, misses) = requests(&before, "api.curseforge.com");
+        assert_eq!(requests(&stats, "api.curseforge.com"), (hits, misses + 1));
+
+        app.prisma_client
+            .app_configuration()
+            .update(
+                app_configuration::id::equals(0),
+                vec![app_configuration::http_cache_size_limit::set(2)],
+            )
+            .exec()
+            .await
+            .unwrap();
+
+        app.http_cache_manager().evict().await.unwrap();
+
+        let urls = app
+            .prisma_client
+            .http_cache()
+            .find_many(vec![])
+            .exec()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.url)
+            .collect::<Vec<_>>();
+        assert!(!urls.contains(&"https://api.modrinth.com/v2/old".to_string()));
+        assert_eq!(urls.len(), 2);
+
+        let removed = app
+            .http_cache_manager()
+            .purge("api.modrinth.com")
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(app.http_cache_manager().stats().await.unwrap().entries, 1);
+    }
+}
@@ -1,8 +1,7 @@
-use std::{collections::HashMap, fs::File, io::Write, path::PathBuf, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use anyhow::anyhow;
-use tokio::sync::mpsc;
-use tracing::trace;
+use carbon_compression::ArchiveFile;
 
 use crate::{
     api::translation::Translation,
@@ -14,7 +13,7 @@ use crate::{
         vtask::VisualTaskId,
     },
     managers::{
-        instance::{export::ZipMode, InstanceType, InvalidInstanceIdError},
+        instance::{InstanceType, InvalidInstanceIdError},
         modplatforms::curseforge::convert_standard_version_to_cf_version,
         vtask::{TaskState, VisualTask},
         AppInner,
@@ -131,19 +130,6 @@ pub async fn export_curseforge(
                 }
             }
 
-            t_calc_size.start_opaque();
-
-            let mut file_count = 0;
-            super::zip_excluding(
-                ZipMode::<File, ()>::Count(&mut file_count),
-                &basepath,
-                "overrides",
-                &filter,
-            )?;
-
-            t_calc_size.complete_opaque();
-            t_create_bundle.update_items(0, file_count);
-
             let manifest = Manifest {
                 minecraft: convert_standard_version_to_cf_version(version.clone())?,
                 manifest_type: String::from("minecraftModpack"),
@@ -162,6 +148,11 @@ pub async fn export_curseforge(
                     .collect(),
             };
 
+            let files = vec![ArchiveFile::new(
+                "manifest.json",
+                serde_json::to_vec_pretty(&manifest)?,
+            )];
+
             let tmpfile = app
                 .settings_manager()
                 .runtime_path
@@ -169,42 +160,16 @@ pub async fn export_curseforge(
                 .maketmpfile()
                 .await?;
 
-            let send_path = tmpfile.to_path_buf();
-            let (notify_tx, mut notify_rx) = mpsc::channel::<()>(1);
-
-            let ziptask = tokio::task::spawn_blocking(move || {
-                let mut zip = zip::ZipWriter::new(File::create(&send_path)?);
-                let options = zip::write::FileOptions::<()>::default();
-                zip.start_file("manifest.json", options)?;
-                zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
-
-                super::zip_excluding(
-                    ZipMode::Create(&mut zip, options, notify_tx),
-                    &basepath,
-                    "overrides",
-                    &filter,
-                )?;
-
-                zip.finish()?;
-                trace!("finished writing `{}`", send_path.to_string_lossy());
-                Ok::<_, anyhow::Error>(())
-            });
-
-            tokio::select! {
-                r = ziptask => r??,
-                _ = async {
-                    let mut counter = 0;
-
-                    loop {
-                        if notify_rx.recv().await.is_some() {
-                            counter += 1;
-                            t_create_bundle.update_items(counter, file_count);
-                        } else {
-                            futures::future::pending().await
-                        }
-                    }
-                } => {},
-            }
+            super::compress_excluding(
+                &basepath,
+                "overrides",
+                &filter,
+                files,
+                &tmpfile,
+                &t_calc_size,
+                &t_create_bundle,
+            )
+            .await?;
 
             tmpfile.try_rename_or_move(save_path).await?;
 
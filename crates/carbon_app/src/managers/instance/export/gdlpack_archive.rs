@@ -0,0 +1,342 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use anyhow::anyhow;
+use carbon_compression::ArchiveFile;
+
+use crate::{
+    api::translation::Translation,
+    domain::{
+        instance::{info::InstanceIcon, ExportEntry, InstanceId},
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::{
+            gdlpack::{self, FileReference, Manifest, ReferenceSource},
+            schema, InstanceType, InvalidInstanceIdError,
+        },
+        vtask::{TaskState, VisualTask},
+        AppInner,
+    },
+};
+
+use crate::db::{mod_file_cache as fcdb, mod_metadata as metadb};
+
+/// Exports the instance as a `.gdlpack`, keeping its whole config.
+///
+/// Unless `self_contained_addons_bundling` is set, mods with a known
+/// CurseForge or Modrinth identity are stored as references instead of jars.
+pub async fn export_gdlpack(
+    app: Arc<AppInner>,
+    instance_id: InstanceId,
+    save_path: PathBuf,
+    self_contained_addons_bundling: bool,
+    mut filter: ExportEntry,
+) -> anyhow::Result<VisualTaskId> {
+    let instance_manager = app.instance_manager();
+    let instances = instance_manager.instances.read().await;
+    let instance = instances
+        .get(&instance_id)
+        .ok_or(InvalidInstanceIdError(instance_id))?;
+
+    let instance_path = app
+        .settings_manager()
+        .runtime_path
+        .get_instances()
+        .get_instance_path(&instance.shortpath);
+
+    let basepath = instance_path.get_data_path();
+    let rootpath = instance_path.get_root();
+
+    let InstanceType::Valid(data) = &instance.type_ else {
+        return Err(anyhow!("Instance {instance_id} is not in a valid state"));
+    };
+
+    let config = data.config.clone();
+
+    drop(instances);
+
+    if config.game_configuration.version.is_none() {
+        return Err(anyhow!(
+            "Instance {instance_id}'s game version is not known so it cannot be exported"
+        ));
+    }
+
+    let vtask = VisualTask::new(Translation::InstanceExport);
+    let vtask_id = app.task_manager().spawn_task(&vtask).await;
+
+    tokio::spawn(async move {
+        let try_result: anyhow::Result<_> = async {
+            let mut references = Vec::new();
+
+            let t_calc_size = vtask.subtask(Translation::InstanceExportCalculateSize);
+            t_calc_size.set_weight(0.0);
+            let t_create_bundle = vtask.subtask(Translation::InstanceExportCreatingBundle);
+
+            vtask
+                .edit(|data| data.state = TaskState::KnownProgress)
+                .await;
+
+            if !self_contained_addons_bundling {
+                let mods_filter = filter.0.get_mut("mods");
+                if let Some(mods_filter) = mods_filter {
+                    let t_scan = vtask.subtask(Translation::InstanceExportScanningMods);
+                    t_calc_size.set_weight(0.5);
+                    t_scan.start_opaque();
+
+                    if mods_filter.is_none() {
+                        let mut modsdir_entries = HashMap::new();
+
+                        let mut dir = tokio::fs::read_dir(basepath.join("mods")).await?;
+                        while let Some(next) = dir.next_entry().await? {
+                            let name = next.file_name();
+                            let Some(name) = name.to_str() else { continue };
+                            modsdir_entries.insert(name.to_string(), None);
+                        }
+
+                        *mods_filter = Some(ExportEntry(modsdir_entries));
+                    }
+
+                    let mods_filter = mods_filter.as_mut().map(|v| &mut v.0).unwrap();
+
+                    app.meta_cache_manager()
+                        .override_caching_and_wait(instance_id, true, true)
+                        .await?;
+
+                    let mods = app
+                        .prisma_client
+                        .mod_file_cache()
+                        .find_many(vec![fcdb::instance_id::equals(*instance_id)])
+                        .with(
+                            fcdb::metadata::fetch()
+                                .with(metadb::curseforge::fetch())
+                                .with(metadb::modrinth::fetch()),
+                        )
+                        .exec()
+                        .await?
+                        .into_iter()
+                        .filter_map(|m| {
+                            let metadata = m.metadata?;
+
+                            // modrinth is preferred as the file can be downloaded without another api call
+                            let source = match (metadata.modrinth, metadata.curseforge) {
+                                (Some(Some(modrinth)), _) => ReferenceSource::Modrinth {
+                                    project_id: modrinth.project_id,
+                                    version_id: modrinth.version_id,
+                                    url: modrinth.file_url,
+                                },
+                                (_, Some(Some(curseforge))) => ReferenceSource::Curseforge {
+                                    project_id: curseforge.project_id as u32,
+                                    file_id: curseforge.file_id as u32,
+                                },
+                                _ => return None,
+                            };
+
+                            mods_filter.remove(&m.filename)?;
+
+                            Some(FileReference {
+                                path: format!("mods/{}", m.filename),
+                                size: m.filesize as u64,
+                                sha1: hex::encode(metadata.sha_1),
+                                source,
+                            })
+                        });
+
+                    references.extend(mods);
+                    t_scan.complete_opaque();
+                }
+            }
+
+            references.sort_by(|a, b| a.path.cmp(&b.path));
+            let manifest = gdlpack::make_manifest(Manifest { references })?;
+
+            let icon = match &config.icon {
+                InstanceIcon::RelativePath(path) => Some((
+                    format!("{}/{path}", gdlpack::ICON_PREFIX),
+                    tokio::fs::read(rootpath.join(path)).await?,
+                )),
+                InstanceIcon::Default => None,
+            };
+
+            let packinfo =
+                match tokio::fs::read_to_string(rootpath.join(gdlpack::PACKINFO_PATH)).await {
+                    Ok(packinfo) => Some(packinfo),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                    Err(e) => return Err(e.into()),
+                };
+
+            let instance_config = schema::make_instance_config(config)?;
+
+            let tmpfile = app
+                .settings_manager()
+                .runtime_path
+                .get_temp()
+                .maketmpfile()
+                .await?;
+
+            let mut files = vec![
+                ArchiveFile::new(gdlpack::MANIFEST_PATH, manifest),
+                ArchiveFile::new(gdlpack::INSTANCE_CONFIG_PATH, instance_config),
+            ];
+
+            if let Some(packinfo) = packinfo {
+                files.push(ArchiveFile::new(gdlpack::PACKINFO_PATH, packinfo));
+            }
+
+            if let Some((path, icon)) = icon {
+                files.push(ArchiveFile::new(path, icon));
+            }
+
+            super::compress_excluding(
+                &basepath,
+                gdlpack::DATA_PREFIX,
+                &filter,
+                files,
+                &tmpfile,
+                &t_calc_size,
+                &t_create_bundle,
+            )
+            .await?;
+
+            tmpfile.try_rename_or_move(save_path).await?;
+
+            t_create_bundle.complete_items();
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = try_result {
+            vtask.fail(e).await;
+        }
+    });
+
+    Ok(vtask_id)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::{HashMap, HashSet},
+        fs::File,
+        io::Read,
+    };
+
+    use tracing_test::traced_test;
+    use zip::ZipArchive;
+
+    use crate::{
+        domain::instance::{info, ExportEntry, InstanceId},
+        managers::instance::{
+            export::ExportTarget,
+            gdlpack::{self, ReferenceSource},
+            schema, InstanceVersionSource,
+        },
+    };
+
+    /// Creates an instance with a single modrinth mod installed.
+    async fn setup() -> anyhow::Result<(crate::TestEnv, InstanceId)> {
+        let app = crate::setup_managers_for_test().await;
+
+        let default_group_id = app.instance_manager().get_default_group().await?;
+        let instance_id = app
+            .instance_manager()
+            .create_instance(
+                default_group_id,
+                String::from("test"),
+                false,
+                InstanceVersionSource::Version(info::GameVersion::Standard(
+                    info::StandardVersion {
+                        release: String::from("1.16.5"),
+                        modloaders: HashSet::from([info::ModLoader {
+                            type_: info::ModLoaderType::Forge,
+                            version: String::from("36.2.34"),
+                        }]),
+                    },
+                )),
+                String::from("some notes"),
+            )
+            .await?;
+
+        let task = app
+            .instance_manager()
+            .install_modrinth_mod(
+                instance_id,
+                String::from("fPetb5Kh"),
+                String::from("o0SCfsMe"),
+                false,
+                None,
+            )
+            .await?;
+
+        app.task_manager().wait_with_log(task).await?;
+
+        Ok((app, instance_id))
+    }
+
+    #[traced_test]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn export_linked() -> anyhow::Result<()> {
+        let (app, instance_id) = setup().await?;
+
+        let target_file = app
+            .settings_manager()
+            .runtime_path
+            .get_root()
+            .to_path()
+            .join("linked.gdlpack");
+
+        let task = app
+            .instance_manager()
+            .export_manager()
+            .export_instance(
+                instance_id,
+                ExportTarget::GDLPack,
+                target_file.clone(),
+                false,
+                ExportEntry(HashMap::from([(String::from("mods"), None)])),
+            )
+            .await?;
+
+        app.task_manager().wait_with_log(task).await?;
+
+        tokio::task::spawn_blocking(move || {
+            let mut zip = ZipArchive::new(File::open(target_file)?)?;
+
+            let mut text = String::new();
+            zip.by_name(gdlpack::MANIFEST_PATH)?
+                .read_to_string(&mut text)?;
+            let manifest = gdlpack::parse_manifest(&text)?;
+
+            assert_eq!(manifest.references.len(), 1);
+            assert_eq!(
+                manifest.references[0].path,
+                "mods/NaturesCompass-1.16.5-1.9.1-forge.jar"
+            );
+            assert_eq!(
+                manifest.references[0].sha1,
+                "38c37c257dcdcf47d5b363eb3e39eebc645b7be4"
+            );
+            assert!(matches!(
+                &manifest.references[0].source,
+                ReferenceSource::Modrinth { project_id, .. } if project_id == "fPetb5Kh"
+            ));
+
+            let mut text = String::new();
+            zip.by_name(gdlpack::INSTANCE_CONFIG_PATH)?
+                .read_to_string(&mut text)?;
+            let config = schema::parse_instance_config(&text)?;
+
+            assert_eq!(config.name, "test");
+            assert_eq!(config.notes, "some notes");
+
+            assert!(zip
+                .by_name("data/mods/NaturesCompass-1.16.5-1.9.1-forge.jar")
+                .is_err());
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .await??;
+
+        Ok(())
+    }
+}
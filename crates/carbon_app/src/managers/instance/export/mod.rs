@@ -1,15 +1,11 @@
 use std::{
-    fs::{self, File},
-    io,
+    fs,
     path::{Path, PathBuf},
 };
 
+use carbon_compression::{ArchiveFile, ArchiveFormat, CompressOptions, CompressProgress};
 use itertools::Itertools;
-use tokio::sync::mpsc;
-use zip::{
-    write::{FileOptionExtension, FileOptions},
-    ZipWriter,
-};
+use tokio::sync::watch;
 
 use crate::{
     domain::{
@@ -20,7 +16,11 @@ use crate::{
 };
 
 mod curseforge_archive;
+mod gdlpack_archive;
 mod modrinth_archive;
+mod multimc_archive;
+mod packwiz;
+mod server_pack;
 
 #[derive(Debug)]
 pub struct InstanceExportManager {}
@@ -61,25 +61,131 @@ impl ManagerRef<'_, InstanceExportManager> {
                 )
                 .await
             }
+            ExportTarget::MultiMC => {
+                multimc_archive::export_multimc(
+                    self.app.clone(),
+                    instance_id,
+                    save_path,
+                    self_contained_addons_bundling,
+                    filter,
+                )
+                .await
+            }
+            ExportTarget::Packwiz => {
+                packwiz::export_packwiz(
+                    self.app.clone(),
+                    instance_id,
+                    save_path,
+                    self_contained_addons_bundling,
+                    filter,
+                )
+                .await
+            }
+            ExportTarget::ServerPack => {
+                server_pack::export_server_pack(
+                    self.app.clone(),
+                    instance_id,
+                    save_path,
+                    self_contained_addons_bundling,
+                    filter,
+                )
+                .await
+            }
+            ExportTarget::GDLPack => {
+                gdlpack_archive::export_gdlpack(
+                    self.app.clone(),
+                    instance_id,
+                    save_path,
+                    self_contained_addons_bundling,
+                    filter,
+                )
+                .await
+            }
+        }
+    }
+}
+
+/// Zips the generated `files` followed by the files of `base_path` allowed by `filter`, placed
+/// under `prefix`, into `dest`.
+///
+/// Entries get fixed timestamps and permissions, so exporting the same instance twice yields the
+/// same archive.
+async fn compress_excluding(
+    base_path: &Path,
+    prefix: &str,
+    filter: &ExportEntry,
+    files: Vec<ArchiveFile>,
+    dest: &Path,
+    t_calc_size: &Subtask,
+    t_create_bundle: &Subtask,
+) -> anyhow::Result<()> {
+    let mut include = Vec::new();
+    filter_patterns(filter, "", &mut include);
+
+    let options = CompressOptions {
+        // no patterns would include everything instead of nothing
+        exclude: match include.is_empty() {
+            true => vec![String::from("**")],
+            false => Vec::new(),
+        },
+        include,
+        prefix: prefix.to_string(),
+        files,
+        ..CompressOptions::reproducible(ArchiveFormat::Zip)
+    };
+
+    let (progress_tx, mut progress_rx) = watch::channel(CompressProgress::default());
+
+    t_calc_size.start_opaque();
+
+    let compress = carbon_compression::compress(base_path, dest, options, move |progress| {
+        let _ = progress_tx.send(progress);
+    });
+    tokio::pin!(compress);
+
+    loop {
+        tokio::select! {
+            result = &mut compress => return Ok(result?),
+            Ok(()) = progress_rx.changed() => {
+                // the first update comes once all entries are known
+                let progress = *progress_rx.borrow_and_update();
+                t_calc_size.complete_opaque();
+                t_create_bundle.update_items(
+                    progress.entries_done as u32,
+                    progress.entries_total as u32,
+                );
+            }
         }
     }
 }
 
-enum ZipMode<'a, W: io::Write + io::Seek, T: FileOptionExtension + Clone> {
-    Count(&'a mut u32),
-    Create(&'a mut ZipWriter<W>, FileOptions<'a, T>, mpsc::Sender<()>),
+/// Collects the patterns matching every path allowed by `filter`, relative to `parent`.
+fn filter_patterns(filter: &ExportEntry, parent: &str, patterns: &mut Vec<String>) {
+    for (name, subfilter) in &filter.0 {
+        let path = match parent {
+            "" => carbon_compression::escape_pattern(name),
+            parent => format!("{parent}/{}", carbon_compression::escape_pattern(name)),
+        };
+
+        match subfilter {
+            Some(subfilter) => filter_patterns(subfilter, &path, patterns),
+            None => patterns.push(format!("{path}/**")),
+        }
+
+        patterns.push(path);
+    }
 }
 
-fn zip_excluding<W: io::Write + io::Seek, T: FileOptionExtension + Clone>(
-    mut mode: ZipMode<W, T>,
+/// Calls `visit` with the full path and the `/` separated path relative to
+/// `base_path` of every file allowed by `filter`.
+fn visit_excluding(
     base_path: &Path,
-    prefix: &str,
     filter: &ExportEntry,
+    visit: &mut impl FnMut(&Path, &str) -> anyhow::Result<()>,
 ) -> anyhow::Result<()> {
-    fn walk_recursive<W: io::Write + io::Seek, T: FileOptionExtension + Clone>(
-        mode: &mut ZipMode<W, T>,
+    fn walk_recursive(
+        visit: &mut impl FnMut(&Path, &str) -> anyhow::Result<()>,
         path: &Path,
-        prefix: &str,
         relpath: &[&str],
         filter: Option<&ExportEntry>,
     ) -> anyhow::Result<()> {
@@ -96,28 +202,53 @@ fn zip_excluding<W: io::Write + io::Seek, T: FileOptionExtension + Clone>(
                 continue;
             };
 
-            let pathstr =
-                String::from(prefix) + "/" + &relpath.iter().chain([&*name].iter()).join("/");
-
             if entry.metadata()?.is_dir() {
                 let relpath = &[relpath, &[&*name][..]].concat()[..];
-                walk_recursive(mode, &entry.path(), prefix, relpath, subfilter.as_ref())?;
+                walk_recursive(visit, &entry.path(), relpath, subfilter.as_ref())?;
             } else {
-                match mode {
-                    ZipMode::Count(counter) => {
-                        **counter += 1;
-                    }
-                    ZipMode::Create(zip, options, notify) => {
-                        zip.start_file(pathstr, options.clone())?;
-                        io::copy(&mut File::open(entry.path())?, zip)?;
-                        let _ = notify.blocking_send(());
-                    }
-                }
+                let pathstr = relpath.iter().chain([&*name].iter()).join("/");
+                visit(&entry.path(), &pathstr)?;
             }
         }
 
         Ok(())
     }
 
-    walk_recursive(&mut mode, base_path, prefix, &[], Some(filter))
+    walk_recursive(visit, base_path, &[], Some(filter))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::domain::instance::ExportEntry;
+
+    #[test]
+    fn test_filter_patterns() {
+        let filter = ExportEntry(HashMap::from([
+            (String::from("config"), None),
+            (
+                String::from("mods"),
+                Some(ExportEntry(HashMap::from([(
+                    String::from("[1.20] sodium.jar"),
+                    None,
+                )]))),
+            ),
+        ]));
+
+        let mut patterns = Vec::new();
+        super::filter_patterns(&filter, "", &mut patterns);
+        patterns.sort();
+
+        assert_eq!(
+            patterns,
+            [
+                "config",
+                "config/**",
+                "mods",
+                "mods/[[]1.20[]] sodium.jar",
+                "mods/[[]1.20[]] sodium.jar/**",
+            ]
+        );
+    }
 }
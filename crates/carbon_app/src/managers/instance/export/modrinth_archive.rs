@@ -1,7 +1,7 @@
-use std::{collections::HashMap, fs::File, io::Write, path::PathBuf, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use anyhow::anyhow;
-use tokio::sync::mpsc;
+use carbon_compression::ArchiveFile;
 
 use crate::{
     api::translation::Translation,
@@ -25,8 +25,6 @@ use crate::{
 
 use crate::db::{mod_file_cache as fcdb, mod_metadata as metadb};
 
-use super::ZipMode;
-
 pub async fn export_modrinth(
     app: Arc<AppInner>,
     instance_id: InstanceId,
@@ -142,19 +140,6 @@ pub async fn export_modrinth(
                 }
             }
 
-            t_calc_size.start_opaque();
-
-            let mut file_count = 0;
-            super::zip_excluding(
-                ZipMode::<File, ()>::Count(&mut file_count),
-                &basepath,
-                "overrides",
-                &filter,
-            )?;
-
-            t_calc_size.complete_opaque();
-            t_create_bundle.update_items(0, file_count);
-
             let manifest = ModpackIndex {
                 format_version: 1,
                 game: ModrinthGame::Minecraft,
@@ -180,6 +165,11 @@ pub async fn export_modrinth(
                 dependencies: convert_standard_version_to_mr_version(version),
             };
 
+            let files = vec![ArchiveFile::new(
+                "modrinth.index.json",
+                serde_json::to_vec_pretty(&manifest)?,
+            )];
+
             let tmpfile = app
                 .settings_manager()
                 .runtime_path
@@ -187,41 +177,16 @@ pub async fn export_modrinth(
                 .maketmpfile()
                 .await?;
 
-            let send_path = tmpfile.to_path_buf();
-            let (notify_tx, mut notify_rx) = mpsc::channel::<()>(1);
-
-            let ziptask = tokio::task::spawn_blocking(move || {
-                let mut zip = zip::ZipWriter::new(File::create(send_path)?);
-                let options = zip::write::FileOptions::<()>::default();
-                zip.start_file("modrinth.index.json", options)?;
-                zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
-
-                super::zip_excluding(
-                    ZipMode::Create(&mut zip, options, notify_tx),
-                    &basepath,
-                    "overrides",
-                    &filter,
-                )?;
-
-                zip.finish()?;
-                Ok::<_, anyhow::Error>(())
-            });
-
-            tokio::select! {
-                r = ziptask => r??,
-                _ = async {
-                    let mut counter = 0;
-
-                    loop {
-                        if notify_rx.recv().await.is_some() {
-                            counter += 1;
-                            t_create_bundle.update_items(counter, file_count);
-                        } else {
-                            futures::future::pending().await
-                        }
-                    }
-                } => {},
-            }
+            super::compress_excluding(
+                &basepath,
+                "overrides",
+                &filter,
+                files,
+                &tmpfile,
+                &t_calc_size,
+                &t_create_bundle,
+            )
+            .await?;
 
             tmpfile.try_rename_or_move(save_path).await?;
 
@@ -0,0 +1,345 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::anyhow;
+use carbon_compression::ArchiveFile;
+use serde::Serialize;
+
+use crate::{
+    api::translation::Translation,
+    domain::{
+        instance::{
+            info::{
+                self, GameResolution, GameVersion, JavaOverride, ModLoaderType, StandardVersion,
+            },
+            ExportEntry, InstanceId,
+        },
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::{InstanceType, InvalidInstanceIdError},
+        vtask::{TaskState, VisualTask},
+        AppInner,
+    },
+};
+
+/// Exports the instance as a zip that MultiMC and Prism Launcher can import directly.
+///
+/// MultiMC has no concept of remote addons, so mods are always bundled and
+/// `self_contained_addons_bundling` is ignored.
+pub async fn export_multimc(
+    app: Arc<AppInner>,
+    instance_id: InstanceId,
+    save_path: PathBuf,
+    _self_contained_addons_bundling: bool,
+    filter: ExportEntry,
+) -> anyhow::Result<VisualTaskId> {
+    let instance_manager = app.instance_manager();
+    let instances = instance_manager.instances.read().await;
+    let instance = instances
+        .get(&instance_id)
+        .ok_or(InvalidInstanceIdError(instance_id))?;
+
+    let basepath = app
+        .settings_manager()
+        .runtime_path
+        .get_instances()
+        .get_instance_path(&instance.shortpath)
+        .get_data_path();
+
+    let InstanceType::Valid(data) = &instance.type_ else {
+        return Err(anyhow!("Instance {instance_id} is not in a valid state"));
+    };
+
+    let config = data.config.clone();
+
+    drop(instances);
+
+    let Some(version) = config.game_configuration.version.clone() else {
+        return Err(anyhow!(
+            "Instance {instance_id}'s game version is not known so it cannot be exported"
+        ));
+    };
+
+    let GameVersion::Standard(version) = version else {
+        return Err(anyhow!(
+            "Instance {instance_id} has a custom game version file so it cannot be exported"
+        ));
+    };
+
+    let vtask = VisualTask::new(Translation::InstanceExport);
+    let vtask_id = app.task_manager().spawn_task(&vtask).await;
+
+    tokio::spawn(async move {
+        let try_result: anyhow::Result<_> = async {
+            let t_calc_size = vtask.subtask(Translation::InstanceExportCalculateSize);
+            t_calc_size.set_weight(0.0);
+            let t_create_bundle = vtask.subtask(Translation::InstanceExportCreatingBundle);
+
+            vtask
+                .edit(|data| data.state = TaskState::KnownProgress)
+                .await;
+
+            let files = vec![
+                ArchiveFile::new("instance.cfg", instance_cfg(&config)),
+                ArchiveFile::new(
+                    "mmc-pack.json",
+                    serde_json::to_vec_pretty(&mmc_pack(version))?,
+                ),
+            ];
+
+            let tmpfile = app
+                .settings_manager()
+                .runtime_path
+                .get_temp()
+                .maketmpfile()
+                .await?;
+
+            super::compress_excluding(
+                &basepath,
+                ".minecraft",
+                &filter,
+                files,
+                &tmpfile,
+                &t_calc_size,
+                &t_create_bundle,
+            )
+            .await?;
+
+            tmpfile.try_rename_or_move(save_path).await?;
+
+            t_create_bundle.complete_items();
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = try_result {
+            vtask.fail(e).await;
+        }
+    });
+
+    Ok(vtask_id)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MmcPack {
+    components: Vec<MmcComponent>,
+    format_version: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct MmcComponent {
+    uid: &'static str,
+    version: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    important: bool,
+    #[serde(rename = "dependencyOnly", skip_serializing_if = "std::ops::Not::not")]
+    dependency_only: bool,
+}
+
+impl MmcComponent {
+    fn new(uid: &'static str, version: String) -> Self {
+        Self {
+            uid,
+            version,
+            important: false,
+            dependency_only: false,
+        }
+    }
+}
+
+/// Builds the component list for the minecraft version and modloader.
+/// Fabric and Quilt additionally need the intermediary mappings for the game version.
+fn mmc_pack(version: StandardVersion) -> MmcPack {
+    let mut components = vec![MmcComponent {
+        important: true,
+        ..MmcComponent::new("net.minecraft", version.release.clone())
+    }];
+
+    let mut modloaders = version.modloaders.into_iter().collect::<Vec<_>>();
+    modloaders.sort_by_key(|modloader| modloader.type_ as u8);
+
+    for info::ModLoader {
+        type_,
+        version: loader_version,
+    } in modloaders
+    {
+        let uid = match type_ {
+            ModLoaderType::Forge => "net.minecraftforge",
+            ModLoaderType::Neoforge => "net.neoforged",
+            ModLoaderType::Fabric => "net.fabricmc.fabric-loader",
+            ModLoaderType::Quilt => "org.quiltmc.quilt-loader",
+        };
+
+        if matches!(type_, ModLoaderType::Fabric | ModLoaderType::Quilt) {
+            components.push(MmcComponent {
+                dependency_only: true,
+                ..MmcComponent::new("net.fabricmc.intermediary", version.release.clone())
+            });
+        }
+
+        components.push(MmcComponent::new(uid, loader_version));
+    }
+
+    MmcPack {
+        components,
+        format_version: 1,
+    }
+}
+
+/// Renders the instance settings as a Qt INI `instance.cfg`.
+fn instance_cfg(config: &info::Instance) -> String {
+    let game = &config.game_configuration;
+    let mut values = vec![
+        ("ConfigVersion", String::from("1.2")),
+        ("InstanceType", String::from("OneSix")),
+        ("name", config.name.clone()),
+        ("iconKey", String::from("default")),
+        ("notes", config.notes.clone()),
+        ("totalTimePlayed", config.seconds_played.to_string()),
+    ];
+
+    if let Some(last_played) = config.last_played {
+        values.push(("lastLaunchTime", last_played.timestamp_millis().to_string()));
+    }
+
+    if let Some((min, max)) = game.memory {
+        values.push(("OverrideMemory", String::from("true")));
+        values.push(("MinMemAlloc", min.to_string()));
+        values.push(("MaxMemAlloc", max.to_string()));
+    }
+
+    if let Some(args) = &game.extra_java_args {
+        values.push(("OverrideJavaArgs", String::from("true")));
+        values.push(("JvmArgs", args.clone()));
+    }
+
+    if let Some(JavaOverride::Path(Some(path))) = &game.java_override {
+        values.push(("OverrideJavaLocation", String::from("true")));
+        values.push(("JavaPath", path.clone()));
+    }
+
+    if let Some(GameResolution::Standard(width, height) | GameResolution::Custom(width, height)) =
+        game.game_resolution
+    {
+        values.push(("OverrideWindow", String::from("true")));
+        values.push(("MinecraftWinWidth", width.to_string()));
+        values.push(("MinecraftWinHeight", height.to_string()));
+    }
+
+    let mut cfg = String::from("[General]\n");
+    for (key, value) in values {
+        cfg.push_str(key);
+        cfg.push('=');
+        cfg.push_str(&escape_ini_value(&value));
+        cfg.push('\n');
+    }
+
+    cfg
+}
+
+/// Quotes and escapes values the way QSettings expects when they contain special characters.
+fn escape_ini_value(value: &str) -> String {
+    if !value.contains(['\n', '\r', '\t', '"', '\\', ';', ',', '=', '#']) && value.trim() == value {
+        return value.to_string();
+    }
+
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+
+    for c in value.chars() {
+        match c {
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            '\r' => result.push_str("\\r"),
+            '"' | '\\' => {
+                result.push('\\');
+                result.push(c);
+            }
+            c => result.push(c),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::domain::instance::info::{ModLoader, ModLoaderType, StandardVersion};
+
+    #[test]
+    fn test_escape_ini_value() {
+        assert_eq!(super::escape_ini_value("My Pack"), "My Pack");
+        assert_eq!(
+            super::escape_ini_value("line one\nsaid \"hi\""),
+            r#""line one\nsaid \"hi\"""#
+        );
+        assert_eq!(
+            super::escape_ini_value("-XX:+UseG1GC, -Dfoo=bar"),
+            r#""-XX:+UseG1GC, -Dfoo=bar""#
+        );
+    }
+
+    #[test]
+    fn test_mmc_pack_fabric() {
+        let pack = super::mmc_pack(StandardVersion {
+            release: String::from("1.20.1"),
+            modloaders: HashSet::from([ModLoader {
+                type_: ModLoaderType::Fabric,
+                version: String::from("0.15.11"),
+            }]),
+        });
+
+        crate::assert_eq_display!(
+            serde_json::to_string_pretty(&pack).unwrap(),
+            r#"{
+  "components": [
+    {
+      "uid": "net.minecraft",
+      "version": "1.20.1",
+      "important": true
+    },
+    {
+      "uid": "net.fabricmc.intermediary",
+      "version": "1.20.1",
+      "dependencyOnly": true
+    },
+    {
+      "uid": "net.fabricmc.fabric-loader",
+      "version": "0.15.11"
+    }
+  ],
+  "formatVersion": 1
+}"#
+        );
+    }
+
+    #[test]
+    fn test_mmc_pack_forge() {
+        let pack = super::mmc_pack(StandardVersion {
+            release: String::from("1.12.2"),
+            modloaders: HashSet::from([ModLoader {
+                type_: ModLoaderType::Forge,
+                version: String::from("14.23.5.2860"),
+            }]),
+        });
+
+        let uids = pack
+            .components
+            .iter()
+            .map(|c| (c.uid, c.version.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            uids,
+            [
+                ("net.minecraft", "1.12.2"),
+                ("net.minecraftforge", "14.23.5.2860")
+            ]
+        );
+    }
+}
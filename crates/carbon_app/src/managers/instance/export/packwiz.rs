@@ -0,0 +1,648 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc;
+use tracing::{trace, warn};
+
+use crate::{
+    api::translation::Translation,
+    domain::{
+        instance::{
+            info::{GameVersion, ModLoaderType, StandardVersion},
+            ExportEntry, InstanceId,
+        },
+        modplatforms::packwiz::{
+            CurseforgeUpdate, HashFormat, Index, IndexFile, MetaFile, MetaFileDownload,
+            MetaFileUpdate, ModrinthUpdate, Pack, PackIndexReference, PackVersions, Side,
+        },
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::{InstanceType, InvalidInstanceIdError},
+        vtask::{TaskState, VisualTask},
+        AppInner,
+    },
+    util::is_safe_relative_path,
+};
+
+use crate::db::{mod_file_cache as fcdb, mod_metadata as metadb};
+
+const PACK_FORMAT: &str = "packwiz:1.1.0";
+
+/// A mod with a known CurseForge and/or Modrinth identity.
+#[derive(Debug)]
+struct LinkedMod {
+    filename: String,
+    sha1: Vec<u8>,
+    sha512: Vec<u8>,
+    curseforge: Option<CurseforgeLink>,
+    modrinth: Option<ModrinthLink>,
+}
+
+#[derive(Debug)]
+struct CurseforgeLink {
+    project_id: u32,
+    file_id: u32,
+    name: String,
+    slug: String,
+}
+
+#[derive(Debug)]
+struct ModrinthLink {
+    project_id: String,
+    version_id: String,
+    title: String,
+    slug: String,
+    file_url: String,
+}
+
+/// Exports the instance as an unpacked packwiz pack into the `save_path` directory.
+///
+/// Linked mods are written as `.pw.toml` metafiles, everything else allowed by
+/// `filter` is copied as is and listed in the index.
+pub async fn export_packwiz(
+    app: Arc<AppInner>,
+    instance_id: InstanceId,
+    save_path: PathBuf,
+    self_contained_addons_bundling: bool,
+    mut filter: ExportEntry,
+) -> anyhow::Result<VisualTaskId> {
+    let instance_manager = app.instance_manager();
+    let instances = instance_manager.instances.read().await;
+    let instance = instances
+        .get(&instance_id)
+        .ok_or(InvalidInstanceIdError(instance_id))?;
+
+    let basepath = app
+        .settings_manager()
+        .runtime_path
+        .get_instances()
+        .get_instance_path(&instance.shortpath)
+        .get_data_path();
+
+    let InstanceType::Valid(data) = &instance.type_ else {
+        return Err(anyhow!("Instance {instance_id} is not in a valid state"));
+    };
+
+    let config = data.config.clone();
+
+    drop(instances);
+
+    let Some(version) = config.game_configuration.version else {
+        return Err(anyhow!(
+            "Instance {instance_id}'s game version is not known so it cannot be exported"
+        ));
+    };
+
+    let GameVersion::Standard(version) = version else {
+        return Err(anyhow!(
+            "Instance {instance_id} has a custom game version file so it cannot be exported"
+        ));
+    };
+
+    let vtask = VisualTask::new(Translation::InstanceExport);
+    let vtask_id = app.task_manager().spawn_task(&vtask).await;
+
+    tokio::spawn(async move {
+        let try_result: anyhow::Result<_> = async {
+            let mut mods = Vec::new();
+
+            let t_calc_size = vtask.subtask(Translation::InstanceExportCalculateSize);
+            t_calc_size.set_weight(0.0);
+            let t_create_bundle = vtask.subtask(Translation::InstanceExportCreatingBundle);
+
+            vtask
+                .edit(|data| data.state = TaskState::KnownProgress)
+                .await;
+
+            if !self_contained_addons_bundling {
+                let mods_filter = filter.0.get_mut("mods");
+                if let Some(mods_filter) = mods_filter {
+                    let t_scan = vtask.subtask(Translation::InstanceExportScanningMods);
+                    t_calc_size.set_weight(0.5);
+                    t_scan.start_opaque();
+
+                    if mods_filter.is_none() {
+                        let mut modsdir_entries = HashMap::new();
+
+                        let mut dir = tokio::fs::read_dir(basepath.join("mods")).await?;
+                        while let Some(next) = dir.next_entry().await? {
+                            let name = next.file_name();
+                            let Some(name) = name.to_str() else { continue };
+                            modsdir_entries.insert(name.to_string(), None);
+                        }
+
+                        *mods_filter = Some(ExportEntry(modsdir_entries));
+                    }
+
+                    let mods_filter = mods_filter.as_mut().map(|v| &mut v.0).unwrap();
+
+                    app.meta_cache_manager()
+                        .override_caching_and_wait(instance_id, true, true)
+                        .await?;
+
+                    let mods2 = app
+                        .prisma_client
+                        .mod_file_cache()
+                        .find_many(vec![fcdb::instance_id::equals(*instance_id)])
+                        .with(
+                            fcdb::metadata::fetch()
+                                .with(metadb::curseforge::fetch())
+                                .with(metadb::modrinth::fetch()),
+                        )
+                        .exec()
+                        .await?
+                        .into_iter()
+                        .filter_map(|m| {
+                            let metadata = m.metadata?;
+
+                            let curseforge =
+                                metadata.curseforge.flatten().map(|cf| CurseforgeLink {
+                                    project_id: cf.project_id as u32,
+                                    file_id: cf.file_id as u32,
+                                    name: cf.name,
+                                    slug: cf.urlslug,
+                                });
+
+                            let modrinth = metadata.modrinth.flatten().map(|mr| ModrinthLink {
+                                project_id: mr.project_id,
+                                version_id: mr.version_id,
+                                title: mr.title,
+                                slug: mr.urlslug,
+                                file_url: mr.file_url,
+                            });
+
+                            if curseforge.is_none() && modrinth.is_none() {
+                                return None;
+                            }
+
+                            mods_filter.remove(&m.filename)?;
+
+                            Some(LinkedMod {
+                                filename: m.filename,
+                                sha1: metadata.sha_1,
+                                sha512: metadata.sha_512,
+                                curseforge,
+                                modrinth,
+                            })
+                        });
+
+                    mods.extend(mods2);
+                    t_scan.complete_opaque();
+                }
+            }
+
+            t_calc_size.start_opaque();
+
+            let mut file_count = mods.len() as u32;
+            super::visit_excluding(&basepath, &filter, &mut |_, _| {
+                file_count += 1;
+                Ok(())
+            })?;
+
+            t_calc_size.complete_opaque();
+            t_create_bundle.update_items(0, file_count);
+
+            let (notify_tx, mut notify_rx) = mpsc::channel::<()>(1);
+
+            let writetask = tokio::task::spawn_blocking(move || {
+                fs::create_dir_all(&save_path)?;
+                remove_previous_export(&save_path)?;
+
+                let mut index_files = Vec::new();
+
+                super::visit_excluding(&basepath, &filter, &mut |path, relpath| {
+                    let target = save_path.join(relpath);
+                    if let Some(parent) = target.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+
+                    let mut hasher = Sha256::new();
+                    io::copy(&mut File::open(path)?, &mut hasher)?;
+                    fs::copy(path, &target)?;
+
+                    index_files.push(IndexFile {
+                        file: relpath.to_string(),
+                        hash: hex::encode(hasher.finalize()),
+                        hash_format: None,
+                        alias: None,
+                        metafile: false,
+                        preserve: false,
+                    });
+
+                    let _ = notify_tx.blocking_send(());
+                    Ok(())
+                })?;
+
+                let mut used_paths = HashSet::new();
+                for linked in mods {
+                    let path = metafile_path(&linked, &mut used_paths);
+                    let content = toml::to_string(&metafile(linked))?;
+
+                    let target = save_path.join(&path);
+                    fs::create_dir_all(target.parent().expect("metafiles are never at the root"))?;
+                    fs::write(target, &content)?;
+
+                    index_files.push(IndexFile {
+                        file: path,
+                        hash: sha256_hex(content.as_bytes()),
+                        hash_format: None,
+                        alias: None,
+                        metafile: true,
+                        preserve: false,
+                    });
+
+                    let _ = notify_tx.blocking_send(());
+                }
+
+                // keep the output stable so re-exports produce minimal diffs
+                index_files.sort_by(|a, b| a.file.cmp(&b.file));
+
+                let index = toml::to_string(&Index {
+                    hash_format: HashFormat::Sha256,
+                    files: index_files,
+                })?;
+                fs::write(save_path.join("index.toml"), &index)?;
+
+                let pack = Pack {
+                    name: config.name,
+                    author: None,
+                    version: None,
+                    description: None,
+                    pack_format: Some(String::from(PACK_FORMAT)),
+                    index: PackIndexReference {
+                        file: String::from("index.toml"),
+                        hash_format: HashFormat::Sha256,
+                        hash: sha256_hex(index.as_bytes()),
+                    },
+                    versions: pack_versions(version),
+                };
+                fs::write(save_path.join("pack.toml"), toml::to_string(&pack)?)?;
+
+                Ok::<_, anyhow::Error>(())
+            });
+
+            tokio::select! {
+                r = writetask => r??,
+                _ = async {
+                    let mut counter = 0;
+
+                    loop {
+                        if notify_rx.recv().await.is_some() {
+                            counter += 1;
+                            t_create_bundle.update_items(counter, file_count);
+                        } else {
+                            futures::future::pending().await
+                        }
+                    }
+                } => {},
+            }
+
+            t_create_bundle.complete_items();
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = try_result {
+            vtask.fail(e).await;
+        }
+    });
+
+    Ok(vtask_id)
+}
+
+/// Removes the files listed by a pack previously exported to `path`, so
+/// files that are no longer part of the instance don't linger.
+fn remove_previous_export(path: &Path) -> anyhow::Result<()> {
+    let Ok(pack) = fs::read_to_string(path.join("pack.toml")) else {
+        return Ok(());
+    };
+
+    let Ok(pack) = toml::from_str::<Pack>(&pack) else {
+        warn!("Existing pack.toml in export directory is not a valid packwiz pack, leaving it");
+        return Ok(());
+    };
+
+    if !is_safe_relative_path(&pack.index.file) {
+        return Ok(());
+    }
+
+    let index_path = path.join(&pack.index.file);
+    let Ok(index) = fs::read_to_string(&index_path) else {
+        return Ok(());
+    };
+
+    let Ok(index) = toml::from_str::<Index>(&index) else {
+        warn!("Existing packwiz index in export directory is malformed, leaving it");
+        return Ok(());
+    };
+
+    let index_root = index_path.parent().unwrap_or(path);
+    for file in index.files {
+        if !is_safe_relative_path(&file.file) {
+            continue;
+        }
+
+        trace!("Removing previously exported file {}", file.file);
+        match fs::remove_file(index_root.join(&file.file)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+fn pack_versions(version: StandardVersion) -> PackVersions {
+    let mut versions = PackVersions {
+        minecraft: version.release,
+        forge: None,
+        neoforge: None,
+        fabric: None,
+        quilt: None,
+        liteloader: None,
+    };
+
+    for modloader in version.modloaders {
+        let slot = match modloader.type_ {
+            ModLoaderType::Forge => &mut versions.forge,
+            ModLoaderType::Neoforge => &mut versions.neoforge,
+            ModLoaderType::Fabric => &mut versions.fabric,
+            ModLoaderType::Quilt => &mut versions.quilt,
+        };
+
+        *slot = Some(modloader.version);
+    }
+
+    versions
+}
+
+/// Picks a unique `mods/<slug>.pw.toml` path for the metafile of `linked`.
+fn metafile_path(linked: &LinkedMod, used: &mut HashSet<String>) -> String {
+    let slug = linked
+        .modrinth
+        .as_ref()
+        .map(|mr| &mr.slug)
+        .or(linked.curseforge.as_ref().map(|cf| &cf.slug))
+        .filter(|slug| !slug.is_empty() && is_safe_relative_path(slug))
+        .cloned()
+        .unwrap_or_else(|| {
+            linked
+                .filename
+                .strip_suffix(".disabled")
+                .unwrap_or(&linked.filename)
+                .trim_end_matches(".jar")
+                .to_string()
+        });
+
+    let mut path = format!("mods/{slug}.pw.toml");
+    let mut n = 2;
+    while !used.insert(path.clone()) {
+        path = format!("mods/{slug}-{n}.pw.toml");
+        n += 1;
+    }
+
+    path
+}
+
+/// Builds the metafile for a linked mod, downloading from Modrinth when
+/// possible since packwiz can use the url directly.
+fn metafile(linked: LinkedMod) -> MetaFile {
+    let download = match &linked.modrinth {
+        Some(mr) => MetaFileDownload {
+            url: Some(mr.file_url.clone()),
+            hash_format: HashFormat::Sha512,
+            hash: hex::encode(&linked.sha512),
+            mode: None,
+        },
+        None => MetaFileDownload {
+            url: None,
+            hash_format: HashFormat::Sha1,
+            hash: hex::encode(&linked.sha1),
+            mode: Some(String::from("metadata:curseforge")),
+        },
+    };
+
+    let name = match (&linked.modrinth, &linked.curseforge) {
+        (Some(mr), _) => mr.title.clone(),
+        (None, Some(cf)) => cf.name.clone(),
+        (None, None) => linked.filename.clone(),
+    };
+
+    MetaFile {
+        name,
+        filename: linked.filename,
+        side: Side::Both,
+        download,
+        option: None,
+        update: MetaFileUpdate {
+            curseforge: linked.curseforge.map(|cf| CurseforgeUpdate {
+                file_id: cf.file_id,
+                project_id: cf.project_id,
+            }),
+            modrinth: linked.modrinth.map(|mr| ModrinthUpdate {
+                mod_id: mr.project_id,
+                version: mr.version_id,
+            }),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::{HashMap, HashSet},
+        path::PathBuf,
+    };
+
+    use tracing_test::traced_test;
+
+    use crate::{
+        domain::instance::{info, ExportEntry, InstanceId},
+        managers::instance::{export::ExportTarget, InstanceVersionSource},
+    };
+
+    use super::{CurseforgeLink, LinkedMod};
+
+    #[test]
+    fn curseforge_only_metafile() {
+        let linked = LinkedMod {
+            filename: String::from("jei-1.20.1-forge-15.2.0.27.jar"),
+            sha1: vec![0xab, 0xcd],
+            sha512: vec![0x12, 0x34],
+            curseforge: Some(CurseforgeLink {
+                project_id: 238222,
+                file_id: 4712866,
+                name: String::from("Just Enough Items (JEI)"),
+                slug: String::from("jei"),
+            }),
+            modrinth: None,
+        };
+
+        let mut used = HashSet::from([String::from("mods/jei.pw.toml")]);
+        assert_eq!(
+            super::metafile_path(&linked, &mut used),
+            "mods/jei-2.pw.toml"
+        );
+
+        crate::assert_eq_display!(
+            toml::to_string(&super::metafile(linked)).unwrap(),
+            r#"name = "Just Enough Items (JEI)"
+filename = "jei-1.20.1-forge-15.2.0.27.jar"
+side = "both"
+
+[download]
+hash-format = "sha1"
+hash = "abcd"
+mode = "metadata:curseforge"
+
+[update.curseforge]
+file-id = 4712866
+project-id = 238222
+"#
+        );
+    }
+
+    /// Creates an instance with a single modrinth mod installed.
+    async fn setup() -> anyhow::Result<(crate::TestEnv, InstanceId)> {
+        let app = crate::setup_managers_for_test().await;
+
+        let default_group_id = app.instance_manager().get_default_group().await?;
+        let instance_id = app
+            .instance_manager()
+            .create_instance(
+                default_group_id,
+                String::from("test"),
+                false,
+                InstanceVersionSource::Version(info::GameVersion::Standard(
+                    info::StandardVersion {
+                        release: String::from("1.16.5"),
+                        modloaders: HashSet::from([info::ModLoader {
+                            type_: info::ModLoaderType::Forge,
+                            version: String::from("36.2.34"),
+                        }]),
+                    },
+                )),
+                String::new(),
+            )
+            .await?;
+
+        let task = app
+            .instance_manager()
+            .install_modrinth_mod(
+                instance_id,
+                String::from("fPetb5Kh"),
+                String::from("o0SCfsMe"),
+                false,
+                None,
+            )
+            .await?;
+
+        app.task_manager().wait_with_log(task).await?;
+
+        Ok((app, instance_id))
+    }
+
+    async fn run_export(
+        app: &crate::TestEnv,
+        instance_id: InstanceId,
+        dirname: &str,
+        export_entry: ExportEntry,
+        self_contained_addons_bundling: bool,
+    ) -> anyhow::Result<PathBuf> {
+        let target_dir = app
+            .settings_manager()
+            .runtime_path
+            .get_root()
+            .to_path()
+            .join(dirname);
+
+        let task = app
+            .instance_manager()
+            .export_manager()
+            .export_instance(
+                instance_id,
+                ExportTarget::Packwiz,
+                target_dir.clone(),
+                self_contained_addons_bundling,
+                export_entry,
+            )
+            .await?;
+
+        app.task_manager().wait_with_log(task).await?;
+
+        Ok(target_dir)
+    }
+
+    #[traced_test]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn export_linked() -> anyhow::Result<()> {
+        let (app, instance_id) = setup().await?;
+
+        let dir = run_export(
+            &app,
+            instance_id,
+            "packwiz_linked",
+            ExportEntry(HashMap::from([(String::from("mods"), None)])),
+            false,
+        )
+        .await?;
+
+        let metafile = tokio::fs::read_to_string(dir.join("mods/natures-compass.pw.toml")).await?;
+        assert!(metafile.contains(
+            "url = \"https://cdn.modrinth.com/data/fPetb5Kh/versions/o0SCfsMe/NaturesCompass-1.16.5-1.9.1-forge.jar\""
+        ));
+        assert!(metafile.contains("[update.modrinth]"));
+        assert!(!dir
+            .join("mods/NaturesCompass-1.16.5-1.9.1-forge.jar")
+            .exists());
+
+        let pack = tokio::fs::read_to_string(dir.join("pack.toml")).await?;
+        assert!(pack.contains("minecraft = \"1.16.5\""));
+        assert!(pack.contains("forge = \"36.2.34\""));
+
+        let index = tokio::fs::read_to_string(dir.join("index.toml")).await?;
+        assert!(index.contains("file = \"mods/natures-compass.pw.toml\""));
+        assert!(index.contains("metafile = true"));
+
+        Ok(())
+    }
+
+    #[traced_test]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn export_unlinked() -> anyhow::Result<()> {
+        let (app, instance_id) = setup().await?;
+
+        let dir = run_export(
+            &app,
+            instance_id,
+            "packwiz_unlinked",
+            ExportEntry(HashMap::from([(String::from("mods"), None)])),
+            true,
+        )
+        .await?;
+
+        assert!(!dir.join("mods/natures-compass.pw.toml").exists());
+        assert!(dir
+            .join("mods/NaturesCompass-1.16.5-1.9.1-forge.jar")
+            .exists());
+
+        let index = tokio::fs::read_to_string(dir.join("index.toml")).await?;
+        assert!(index.contains("file = \"mods/NaturesCompass-1.16.5-1.9.1-forge.jar\""));
+        assert!(!index.contains("metafile = true"));
+
+        Ok(())
+    }
+}
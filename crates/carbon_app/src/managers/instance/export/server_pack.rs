@@ -0,0 +1,496 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use carbon_compression::ArchiveFile;
+use daedalus::minecraft::DownloadType;
+use tracing::{trace, warn};
+
+use crate::{
+    api::translation::Translation,
+    domain::{
+        instance::{
+            info::{GameVersion, ModLoaderType, StandardVersion},
+            ExportEntry, InstanceId,
+        },
+        modplatforms::{
+            curseforge::filters::{FilesParameters, FilesParametersBody},
+            modrinth::{project::ProjectSupportRange, search::ProjectIDs},
+        },
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::{InstanceType, InvalidInstanceIdError},
+        vtask::{TaskState, VisualTask},
+        AppInner,
+    },
+};
+
+use crate::db::{mod_file_cache as fcdb, mod_metadata as metadb};
+
+const FABRIC_INSTALLER_VERSION: &str = "1.0.1";
+const QUILT_INSTALLER_VERSION: &str = "0.9.1";
+
+/// Top level entries that are only meaningful to a client and never go in a server pack.
+const CLIENT_ONLY_ENTRIES: &[&str] = &[
+    "resourcepacks",
+    "shaderpacks",
+    "screenshots",
+    "saves",
+    "logs",
+    "crash-reports",
+    "options.txt",
+    "optionsof.txt",
+    "optionsshaders.txt",
+    "servers.dat",
+];
+
+/// How the server for the instance's loader gets installed and launched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ServerLoader {
+    Vanilla { server_url: String },
+    Forge { installer_url: String },
+    Neoforge { installer_url: String },
+    Fabric { minecraft: String, loader: String },
+    Quilt { minecraft: String, loader: String },
+}
+
+/// Exports a zip meant to be extracted on a dedicated server.
+///
+/// Client only mods are dropped based on the Modrinth project sides and
+/// CurseForge game version flags, and a `start.sh` is generated that installs
+/// and launches the server for the instance's modloader.
+/// Servers cannot resolve remote addons, so mods are always bundled and
+/// `self_contained_addons_bundling` is ignored.
+pub async fn export_server_pack(
+    app: Arc<AppInner>,
+    instance_id: InstanceId,
+    save_path: PathBuf,
+    _self_contained_addons_bundling: bool,
+    mut filter: ExportEntry,
+) -> anyhow::Result<VisualTaskId> {
+    let instance_manager = app.instance_manager();
+    let instances = instance_manager.instances.read().await;
+    let instance = instances
+        .get(&instance_id)
+        .ok_or(InvalidInstanceIdError(instance_id))?;
+
+    let basepath = app
+        .settings_manager()
+        .runtime_path
+        .get_instances()
+        .get_instance_path(&instance.shortpath)
+        .get_data_path();
+
+    let InstanceType::Valid(data) = &instance.type_ else {
+        return Err(anyhow!("Instance {instance_id} is not in a valid state"));
+    };
+
+    let config = data.config.clone();
+
+    drop(instances);
+
+    let Some(version) = config.game_configuration.version.clone() else {
+        return Err(anyhow!(
+            "Instance {instance_id}'s game version is not known so it cannot be exported"
+        ));
+    };
+
+    let GameVersion::Standard(version) = version else {
+        return Err(anyhow!(
+            "Instance {instance_id} has a custom game version file so it cannot be exported"
+        ));
+    };
+
+    let vtask = VisualTask::new(Translation::InstanceExport);
+    let vtask_id = app.task_manager().spawn_task(&vtask).await;
+
+    tokio::spawn(async move {
+        let try_result: anyhow::Result<_> = async {
+            let t_calc_size = vtask.subtask(Translation::InstanceExportCalculateSize);
+            t_calc_size.set_weight(0.0);
+            let t_create_bundle = vtask.subtask(Translation::InstanceExportCreatingBundle);
+
+            vtask
+                .edit(|data| data.state = TaskState::KnownProgress)
+                .await;
+
+            for entry in CLIENT_ONLY_ENTRIES {
+                filter.0.remove(*entry);
+            }
+
+            if basepath.join("config").is_dir() {
+                filter.0.entry(String::from("config")).or_insert(None);
+            }
+
+            if let Some(mods_filter) = filter.0.get_mut("mods") {
+                let t_scan = vtask.subtask(Translation::InstanceExportScanningMods);
+                t_calc_size.set_weight(0.5);
+                t_scan.start_opaque();
+
+                if mods_filter.is_none() {
+                    let mut modsdir_entries = HashMap::new();
+
+                    let mut dir = tokio::fs::read_dir(basepath.join("mods")).await?;
+                    while let Some(next) = dir.next_entry().await? {
+                        let name = next.file_name();
+                        let Some(name) = name.to_str() else { continue };
+                        modsdir_entries.insert(name.to_string(), None);
+                    }
+
+                    *mods_filter = Some(ExportEntry(modsdir_entries));
+                }
+
+                let mods_filter = mods_filter.as_mut().map(|v| &mut v.0).unwrap();
+                mods_filter.retain(|name, _| !name.ends_with(".disabled"));
+
+                app.meta_cache_manager()
+                    .override_caching_and_wait(instance_id, true, true)
+                    .await?;
+
+                match find_client_only_mods(&app, instance_id).await {
+                    Ok(client_only) => {
+                        for filename in client_only {
+                            if mods_filter.remove(&filename).is_some() {
+                                trace!("Excluding client only mod {filename} from server pack");
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        warn!({ error = ?e }, "Could not determine client only mods, bundling all mods");
+                    }
+                }
+
+                t_scan.complete_opaque();
+            }
+
+            let loader = server_loader(&app, version).await?;
+            let start_script = start_script(&config.name, &loader, config.game_configuration.memory);
+
+            let tmpfile = app
+                .settings_manager()
+                .runtime_path
+                .get_temp()
+                .maketmpfile()
+                .await?;
+
+            super::compress_excluding(
+                &basepath,
+                "",
+                &filter,
+                vec![ArchiveFile::new("start.sh", start_script).executable()],
+                &tmpfile,
+                &t_calc_size,
+                &t_create_bundle,
+            )
+            .await?;
+
+            tmpfile.try_rename_or_move(save_path).await?;
+
+            t_create_bundle.complete_items();
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = try_result {
+            vtask.fail(e).await;
+        }
+    });
+
+    Ok(vtask_id)
+}
+
+/// Returns the filenames of mods that the platforms mark as client only.
+/// Modrinth's project sides take precedence over CurseForge's game version flags.
+async fn find_client_only_mods(
+    app: &Arc<AppInner>,
+    instance_id: InstanceId,
+) -> anyhow::Result<HashSet<String>> {
+    let mods = app
+        .prisma_client
+        .mod_file_cache()
+        .find_many(vec![fcdb::instance_id::equals(*instance_id)])
+        .with(
+            fcdb::metadata::fetch()
+                .with(metadb::curseforge::fetch())
+                .with(metadb::modrinth::fetch()),
+        )
+        .exec()
+        .await?;
+
+    let mut modrinth_mods = HashMap::new();
+    let mut curseforge_mods = HashMap::new();
+
+    for m in mods {
+        let Some(metadata) = m.metadata else { continue };
+
+        if let Some(Some(modrinth)) = metadata.modrinth {
+            modrinth_mods.insert(modrinth.project_id, m.filename);
+        } else if let Some(Some(curseforge)) = metadata.curseforge {
+            curseforge_mods.insert(curseforge.file_id, m.filename);
+        }
+    }
+
+    let mut client_only = HashSet::new();
+
+    if !modrinth_mods.is_empty() {
+        let projects = app
+            .modplatforms_manager()
+            .modrinth
+            .get_projects(ProjectIDs {
+                ids: modrinth_mods.keys().cloned().collect(),
+            })
+            .await?;
+
+        for project in projects.0 {
+            if project.server_side == ProjectSupportRange::Unsupported {
+                if let Some(filename) = modrinth_mods.remove(&project.id) {
+                    client_only.insert(filename);
+                }
+            }
+        }
+    }
+
+    if !curseforge_mods.is_empty() {
+        let files = app
+            .modplatforms_manager()
+            .curseforge
+            .get_files(FilesParameters {
+                body: FilesParametersBody {
+                    file_ids: curseforge_mods.keys().copied().collect(),
+                },
+            })
+            .await?
+            .data;
+
+        for file in files {
+            if is_curseforge_client_only(&file.game_versions, file.is_server_pack) {
+                if let Some(filename) = curseforge_mods.remove(&file.id) {
+                    client_only.insert(filename);
+                }
+            }
+        }
+    }
+
+    Ok(client_only)
+}
+
+/// CurseForge lists the supported environments as `Client`/`Server` game versions.
+/// Files without either flag predate them and are assumed to work on both sides.
+fn is_curseforge_client_only(game_versions: &[String], is_server_pack: Option<bool>) -> bool {
+    if is_server_pack == Some(true) {
+        return false;
+    }
+
+    let has = |flag: &str| game_versions.iter().any(|v| v.eq_ignore_ascii_case(flag));
+    has("Client") && !has("Server")
+}
+
+async fn server_loader(
+    app: &Arc<AppInner>,
+    version: StandardVersion,
+) -> anyhow::Result<ServerLoader> {
+    let minecraft = version.release;
+
+    let Some(modloader) = version.modloaders.into_iter().next() else {
+        let version_info = app
+            .minecraft_manager()
+            .get_minecraft_version(&minecraft)
+            .await?;
+
+        let server_url = version_info
+            .downloads
+            .get(&DownloadType::Server)
+            .ok_or_else(|| anyhow!("Minecraft {minecraft} does not have a server download"))?
+            .url
+            .clone();
+
+        return Ok(ServerLoader::Vanilla { server_url });
+    };
+
+    Ok(modded_server_loader(
+        minecraft,
+        modloader.type_,
+        modloader.version,
+    ))
+}
+
+fn modded_server_loader(minecraft: String, type_: ModLoaderType, version: String) -> ServerLoader {
+    match type_ {
+        ModLoaderType::Forge => {
+            // 1.7.10 artifacts carry the minecraft version as a suffix as well
+            let full_version = match minecraft.as_str() {
+                "1.7.10" => format!("{minecraft}-{version}-{minecraft}"),
+                _ => format!("{minecraft}-{version}"),
+            };
+
+            ServerLoader::Forge {
+                installer_url: format!(
+                    "https://maven.minecraftforge.net/net/minecraftforge/forge/{full_version}/forge-{full_version}-installer.jar"
+                ),
+            }
+        }
+        ModLoaderType::Neoforge => {
+            // neoforge for 1.20.1 was still published under the forge artifact
+            let installer_url = match minecraft.as_str() {
+                "1.20.1" => {
+                    let version = version.trim_start_matches("1.20.1-");
+                    format!(
+                        "https://maven.neoforged.net/releases/net/neoforged/forge/1.20.1-{version}/forge-1.20.1-{version}-installer.jar"
+                    )
+                }
+                _ => format!(
+                    "https://maven.neoforged.net/releases/net/neoforged/neoforge/{version}/neoforge-{version}-installer.jar"
+                ),
+            };
+
+            ServerLoader::Neoforge { installer_url }
+        }
+        ModLoaderType::Fabric => ServerLoader::Fabric {
+            minecraft,
+            loader: version,
+        },
+        ModLoaderType::Quilt => ServerLoader::Quilt {
+            minecraft,
+            loader: version,
+        },
+    }
+}
+
+/// Generates a POSIX shell script that installs the server on first run and launches it.
+fn start_script(name: &str, loader: &ServerLoader, memory: Option<(u16, u16)>) -> String {
+    let (install, launch) = match loader {
+        ServerLoader::Vanilla { server_url } => (
+            format!("curl -fL -o server.jar \"{server_url}\""),
+            String::from("\"$JAVA\" $JVM_ARGS -jar server.jar nogui"),
+        ),
+        ServerLoader::Forge { installer_url } | ServerLoader::Neoforge { installer_url } => (
+            format!(
+                "curl -fL -o installer.jar \"{installer_url}\"\n    \"$JAVA\" -jar installer.jar --installServer\n    rm -f installer.jar installer.jar.log"
+            ),
+            String::from(
+                "if [ -f run.sh ]; then\n    echo \"$JVM_ARGS\" > user_jvm_args.txt\n    sh run.sh nogui\nelse\n    \"$JAVA\" $JVM_ARGS -jar \"$(ls forge-*.jar | head -n 1)\" nogui\nfi",
+            ),
+        ),
+        ServerLoader::Fabric { minecraft, loader } => (
+            format!(
+                "curl -fL -o installer.jar \"https://maven.fabricmc.net/net/fabricmc/fabric-installer/{FABRIC_INSTALLER_VERSION}/fabric-installer-{FABRIC_INSTALLER_VERSION}.jar\"\n    \"$JAVA\" -jar installer.jar server -mcversion {minecraft} -loader {loader} -downloadMinecraft\n    rm -f installer.jar"
+            ),
+            String::from("\"$JAVA\" $JVM_ARGS -jar fabric-server-launch.jar nogui"),
+        ),
+        ServerLoader::Quilt { minecraft, loader } => (
+            format!(
+                "curl -fL -o installer.jar \"https://maven.quiltmc.org/repository/release/org/quiltmc/quilt-installer/{QUILT_INSTALLER_VERSION}/quilt-installer-{QUILT_INSTALLER_VERSION}.jar\"\n    \"$JAVA\" -jar installer.jar install server {minecraft} {loader} --download-server --install-dir=.\n    rm -f installer.jar"
+            ),
+            String::from("\"$JAVA\" $JVM_ARGS -jar quilt-server-launch.jar nogui"),
+        ),
+    };
+
+    let max_memory = memory.map(|(_, max)| max).unwrap_or(4096);
+    let name = name.replace('\n', " ");
+
+    format!(
+        r#"#!/usr/bin/env sh
+# Server for {name}, generated by GDLauncher.
+# Accept the Minecraft EULA in eula.txt before the first launch.
+set -e
+cd "$(dirname "$0")"
+
+JAVA="${{JAVA:-java}}"
+JVM_ARGS="${{JVM_ARGS:--Xmx{max_memory}M}}"
+
+if [ ! -f .gdl-server-installed ]; then
+    {install}
+    touch .gdl-server-installed
+fi
+
+{launch}
+"#
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::instance::info::ModLoaderType;
+
+    use super::ServerLoader;
+
+    #[test]
+    fn test_curseforge_client_only() {
+        let versions = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert!(super::is_curseforge_client_only(
+            &versions(&["1.20.1", "Forge", "Client"]),
+            None
+        ));
+        assert!(!super::is_curseforge_client_only(
+            &versions(&["1.20.1", "Forge", "Client", "Server"]),
+            None
+        ));
+        assert!(!super::is_curseforge_client_only(
+            &versions(&["1.12.2", "Forge"]),
+            None
+        ));
+        assert!(!super::is_curseforge_client_only(
+            &versions(&["1.20.1", "Client"]),
+            Some(true)
+        ));
+    }
+
+    #[test]
+    fn test_modded_server_loader() {
+        assert_eq!(
+            super::modded_server_loader(
+                String::from("1.16.5"),
+                ModLoaderType::Forge,
+                String::from("36.2.34")
+            ),
+            ServerLoader::Forge {
+                installer_url: String::from("https://maven.minecraftforge.net/net/minecraftforge/forge/1.16.5-36.2.34/forge-1.16.5-36.2.34-installer.jar")
+            }
+        );
+
+        assert_eq!(
+            super::modded_server_loader(
+                String::from("1.20.1"),
+                ModLoaderType::Neoforge,
+                String::from("47.1.106")
+            ),
+            ServerLoader::Neoforge {
+                installer_url: String::from("https://maven.neoforged.net/releases/net/neoforged/forge/1.20.1-47.1.106/forge-1.20.1-47.1.106-installer.jar")
+            }
+        );
+
+        assert_eq!(
+            super::modded_server_loader(
+                String::from("1.21.1"),
+                ModLoaderType::Neoforge,
+                String::from("21.1.77")
+            ),
+            ServerLoader::Neoforge {
+                installer_url: String::from("https://maven.neoforged.net/releases/net/neoforged/neoforge/21.1.77/neoforge-21.1.77-installer.jar")
+            }
+        );
+    }
+
+    #[test]
+    fn test_start_script_fabric() {
+        let script = super::start_script(
+            "My Pack",
+            &ServerLoader::Fabric {
+                minecraft: String::from("1.20.1"),
+                loader: String::from("0.15.11"),
+            },
+            Some((2048, 6144)),
+        );
+
+        assert!(script.starts_with("#!/usr/bin/env sh\n"));
+        assert!(script.contains("JVM_ARGS=\"${JVM_ARGS:--Xmx6144M}\""));
+        assert!(script.contains("server -mcversion 1.20.1 -loader 0.15.11 -downloadMinecraft"));
+        assert!(script.contains("-jar fabric-server-launch.jar nogui"));
+    }
+}
@@ -0,0 +1,101 @@
+//! Native `.gdlpack` instance archives.
+//!
+//! An archive contains:
+//! - `gdlpack.json`: the [`Manifest`]
+//! - `instance.json`: the instance config, in the same format as on disk
+//! - `packinfo.json`: the modpack file hashes, if the instance has them
+//! - `icon/<name>`: the instance icon, if it has a custom one
+//! - `data/`: the instance data directory, minus the referenced files
+
+use serde::{Deserialize, Serialize};
+
+pub const MANIFEST_PATH: &str = "gdlpack.json";
+pub const INSTANCE_CONFIG_PATH: &str = "instance.json";
+pub const PACKINFO_PATH: &str = "packinfo.json";
+pub const ICON_PREFIX: &str = "icon";
+pub const DATA_PREFIX: &str = "data";
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "_version")]
+enum ManifestWrapper {
+    #[serde(rename = "1")]
+    V1(Manifest),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// files of the data directory that are downloaded on import instead of being stored
+    pub references: Vec<FileReference>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileReference {
+    /// path relative to the data directory, always using `/`
+    pub path: String,
+    pub size: u64,
+    pub sha1: String,
+    pub source: ReferenceSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "platform", rename_all = "lowercase")]
+pub enum ReferenceSource {
+    Curseforge {
+        project_id: u32,
+        file_id: u32,
+    },
+    Modrinth {
+        project_id: String,
+        version_id: String,
+        url: String,
+    },
+}
+
+pub fn parse_manifest(manifest_str: &str) -> Result<Manifest, serde_json::Error> {
+    let manifest = serde_json::from_str::<ManifestWrapper>(manifest_str)?;
+
+    Ok(match manifest {
+        ManifestWrapper::V1(manifest) => manifest,
+    })
+}
+
+pub fn make_manifest(manifest: Manifest) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&ManifestWrapper::V1(manifest))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FileReference, Manifest, ReferenceSource};
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let manifest = Manifest {
+            references: vec![
+                FileReference {
+                    path: String::from("mods/jei-1.20.1-forge-15.2.0.27.jar"),
+                    size: 1234,
+                    sha1: String::from("abcd"),
+                    source: ReferenceSource::Curseforge {
+                        project_id: 238222,
+                        file_id: 4712866,
+                    },
+                },
+                FileReference {
+                    path: String::from("mods/sodium-fabric-0.5.8+mc1.20.1.jar.disabled"),
+                    size: 5678,
+                    sha1: String::from("ef01"),
+                    source: ReferenceSource::Modrinth {
+                        project_id: String::from("AANobbMI"),
+                        version_id: String::from("OihdIimA"),
+                        url: String::from("https://cdn.modrinth.com/data/AANobbMI/versions/OihdIimA/sodium-fabric-0.5.8+mc1.20.1.jar"),
+                    },
+                },
+            ],
+        };
+
+        let text = super::make_manifest(manifest.clone()).unwrap();
+        assert!(text.contains(r#""_version": "1""#));
+        assert!(text.contains(r#""platform": "curseforge""#));
+        assert_eq!(super::parse_manifest(&text).unwrap(), manifest);
+    }
+}
@@ -0,0 +1,422 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::anyhow;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::trace;
+
+use crate::{
+    api::keys::instance::*,
+    api::translation::Translation,
+    domain::{
+        instance::info::{CurseforgeModpack, GameVersion, JavaOverride, Modpack, ModrinthModpack},
+        modplatforms::modrinth::version::ModrinthPackDependencies,
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::InstanceVersionSource,
+        modplatforms::modrinth::convert_mr_version_to_standard_version, AppInner,
+    },
+};
+
+use super::{
+    ImportScanStatus, ImportableInstance, ImportedGameSettings, ImporterState, InstanceImporter,
+    InternalImportEntry, InvalidImportEntry,
+};
+
+#[derive(Debug, Clone)]
+struct Importable {
+    filename: String,
+    path: PathBuf,
+    config: AtlInstance,
+    dependencies: ModrinthPackDependencies,
+}
+
+impl From<Importable> for ImportableInstance {
+    fn from(value: Importable) -> Self {
+        Self {
+            filename: value.filename,
+            instance_name: value.config.launcher.name,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ATLauncherImporter {
+    state: RwLock<ImporterState<Importable>>,
+}
+
+impl ATLauncherImporter {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(ImporterState::NoResults),
+        }
+    }
+
+    pub async fn get_default_scan_path() -> anyhow::Result<PathBuf> {
+        let basedirs = directories::BaseDirs::new().ok_or(anyhow!("Cannot build basedirs"))?;
+
+        #[cfg(target_os = "linux")]
+        let p = basedirs.data_dir().join("atlauncher");
+        #[cfg(not(target_os = "linux"))]
+        let p = basedirs.data_dir().join("ATLauncher");
+
+        Ok(p.join("instances"))
+    }
+
+    async fn scan_instance(
+        &self,
+        path: PathBuf,
+    ) -> anyhow::Result<Option<InternalImportEntry<Importable>>> {
+        let config = path.join("instance.json");
+        if !config.is_file() {
+            return Ok(None);
+        }
+
+        let filename = path
+            .file_name()
+            .expect("filename cannot be empty")
+            .to_string_lossy()
+            .to_string();
+
+        let config = tokio::fs::read_to_string(config).await?;
+        let Ok(config) = serde_json::from_str::<AtlInstance>(&config) else {
+            return Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason: Translation::InstanceImportAtlBadConfigFile,
+            })));
+        };
+
+        match config.to_dependencies() {
+            Some(dependencies) => Ok(Some(InternalImportEntry::Valid(Importable {
+                filename,
+                path,
+                config,
+                dependencies,
+            }))),
+            None => Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason: Translation::InstanceImportAtlUnsupportedLoader,
+            }))),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl InstanceImporter for ATLauncherImporter {
+    async fn scan(&self, app: &Arc<AppInner>, scan_path: PathBuf) -> anyhow::Result<()> {
+        if scan_path.join("instance.json").is_file() {
+            if let Ok(Some(entry)) = self.scan_instance(scan_path).await {
+                self.state.write().await.set_single(entry).await;
+                app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+            }
+        } else if scan_path.is_dir() {
+            let Ok(mut dir) = tokio::fs::read_dir(&scan_path).await else {
+                return Ok(());
+            };
+
+            while let Some(path) = dir.next_entry().await? {
+                if path.metadata().await?.is_dir() {
+                    if let Ok(Some(entry)) = self.scan_instance(path.path()).await {
+                        self.state.write().await.push_multi(entry).await;
+                        app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_status(&self) -> ImportScanStatus {
+        self.state.read().await.clone().into()
+    }
+
+    async fn begin_import(
+        &self,
+        app: &Arc<AppInner>,
+        index: u32,
+        name: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        trace!(?index, ?name, "Beginning atlauncher import");
+
+        let instance = self
+            .state
+            .read()
+            .await
+            .get(index)
+            .await
+            .cloned()
+            .ok_or_else(|| anyhow!("invalid importable instance index {index}"))?;
+
+        let standard_version =
+            convert_mr_version_to_standard_version(app.clone(), instance.dependencies.clone())
+                .await?;
+
+        let version = GameVersion::Standard(standard_version);
+
+        let instance_version_source = match instance.config.launcher.modpack() {
+            Some(modpack) => InstanceVersionSource::ModpackWithKnownVersion(version, modpack, true),
+            None => InstanceVersionSource::Version(version),
+        };
+
+        let icon_path = instance.path.join("instance.png");
+        let icon = match icon_path.is_file() {
+            true => app.instance_manager().load_icon(icon_path).await.ok(),
+            false => None,
+        };
+
+        let initializer = |instance_path: PathBuf| {
+            let instance = &instance;
+            async move {
+                let path = instance_path.join("instance");
+
+                tokio::fs::create_dir_all(instance_path.join(".setup").join("modpack-complete"))
+                    .await?;
+
+                trace!("Copying files from atlauncher instance");
+                crate::domain::runtime_path::copy_dir_pruned(&instance.path, &path, |path| {
+                    !matches!(
+                        path.to_str(),
+                        Some("instance.json" | "instance.png" | "natives" | "disabledmods")
+                    )
+                })
+                .await?;
+
+                Ok(())
+            }
+        };
+
+        let id = app
+            .instance_manager()
+            .create_instance_ext(
+                app.instance_manager().get_default_group().await?,
+                name.unwrap_or_else(|| instance.config.launcher.name.clone()),
+                icon,
+                instance.config.launcher.total_play_time,
+                None,
+                instance_version_source,
+                String::new(),
+                initializer,
+            )
+            .await?;
+
+        instance
+            .config
+            .launcher
+            .game_settings()
+            .apply(app, id)
+            .await?;
+
+        app.instance_manager()
+            .prepare_game(id, None, None, true)
+            .await
+            .map(|r| r.1)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AtlInstance {
+    /// minecraft version
+    id: String,
+    launcher: AtlLauncher,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AtlLauncher {
+    name: String,
+    loader_version: Option<AtlLoaderVersion>,
+    initial_memory: Option<u16>,
+    maximum_memory: Option<u16>,
+    java_arguments: Option<String>,
+    java_path: Option<String>,
+    /// seconds
+    total_play_time: Option<u32>,
+    curse_forge_project: Option<AtlCurseforgeProject>,
+    curse_forge_file: Option<AtlCurseforgeFile>,
+    modrinth_project: Option<AtlModrinthProject>,
+    modrinth_version: Option<AtlModrinthVersion>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AtlLoaderVersion {
+    version: String,
+    #[serde(rename = "type")]
+    type_: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AtlCurseforgeProject {
+    id: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AtlCurseforgeFile {
+    id: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AtlModrinthProject {
+    id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AtlModrinthVersion {
+    id: String,
+}
+
+impl AtlInstance {
+    /// Returns `None` if the instance uses a loader that is not supported.
+    fn to_dependencies(&self) -> Option<ModrinthPackDependencies> {
+        let mut dependencies = ModrinthPackDependencies {
+            minecraft: Some(self.id.clone()),
+            forge: None,
+            neoforge: None,
+            fabric_loader: None,
+            quilt_loader: None,
+        };
+
+        if let Some(loader) = &self.launcher.loader_version {
+            let version = Some(loader.version.clone());
+
+            match &*loader.type_ {
+                "Forge" => dependencies.forge = version,
+                "NeoForge" => dependencies.neoforge = version,
+                "Fabric" => dependencies.fabric_loader = version,
+                "Quilt" => dependencies.quilt_loader = version,
+                _ => return None,
+            }
+        }
+
+        Some(dependencies)
+    }
+}
+
+impl AtlLauncher {
+    fn modpack(&self) -> Option<Modpack> {
+        if let (Some(project), Some(file)) = (&self.curse_forge_project, &self.curse_forge_file) {
+            return Some(Modpack::Curseforge(CurseforgeModpack {
+                project_id: project.id,
+                file_id: file.id,
+            }));
+        }
+
+        if let (Some(project), Some(version)) = (&self.modrinth_project, &self.modrinth_version) {
+            return Some(Modpack::Modrinth(ModrinthModpack {
+                project_id: project.id.clone(),
+                version_id: version.id.clone(),
+            }));
+        }
+
+        None
+    }
+
+    fn game_settings(&self) -> ImportedGameSettings {
+        ImportedGameSettings {
+            extra_java_args: self
+                .java_arguments
+                .clone()
+                .filter(|args| !args.trim().is_empty()),
+            memory: self.initial_memory.zip(self.maximum_memory),
+            java_override: self
+                .java_path
+                .clone()
+                .map(|path| JavaOverride::Path(Some(path))),
+            game_resolution: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::instance::info::{CurseforgeModpack, Modpack, ModrinthModpack};
+
+    use super::AtlInstance;
+
+    fn parse(text: &str) -> AtlInstance {
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_forge_curseforge_pack() {
+        let instance = parse(include_str!(
+            "test_fixtures/atlauncher/forge-curseforge.json"
+        ));
+        let dependencies = instance.to_dependencies().unwrap();
+
+        assert_eq!(dependencies.minecraft.as_deref(), Some("1.20.1"));
+        assert_eq!(dependencies.forge.as_deref(), Some("47.2.0"));
+        assert_eq!(dependencies.fabric_loader, None);
+
+        assert_eq!(
+            instance.launcher.modpack(),
+            Some(Modpack::Curseforge(CurseforgeModpack {
+                project_id: 715572,
+                file_id: 4925467,
+            }))
+        );
+
+        let settings = instance.launcher.game_settings();
+        assert_eq!(settings.memory, Some((2048, 8192)));
+        assert_eq!(settings.extra_java_args.as_deref(), Some("-XX:+UseG1GC"));
+        assert_eq!(instance.launcher.total_play_time, Some(5400));
+    }
+
+    #[test]
+    fn test_fabric_modrinth_pack() {
+        let instance = parse(include_str!(
+            "test_fixtures/atlauncher/fabric-modrinth.json"
+        ));
+        let dependencies = instance.to_dependencies().unwrap();
+
+        assert_eq!(dependencies.minecraft.as_deref(), Some("1.20.4"));
+        assert_eq!(dependencies.fabric_loader.as_deref(), Some("0.15.7"));
+        assert_eq!(dependencies.forge, None);
+
+        assert_eq!(
+            instance.launcher.modpack(),
+            Some(Modpack::Modrinth(ModrinthModpack {
+                project_id: String::from("1KVo5zza"),
+                version_id: String::from("Xt3xVnLs"),
+            }))
+        );
+
+        let settings = instance.launcher.game_settings();
+        assert_eq!(settings.memory, None);
+        assert_eq!(settings.extra_java_args, None);
+    }
+
+    #[test]
+    fn test_vanilla_atlauncher_pack() {
+        let instance = parse(
+            r#"{
+                "id": "1.12.2",
+                "launcher": {
+                    "name": "Vanilla Minecraft",
+                    "pack": "Vanilla Minecraft",
+                    "version": "1.12.2"
+                }
+            }"#,
+        );
+        let dependencies = instance.to_dependencies().unwrap();
+
+        assert_eq!(dependencies.minecraft.as_deref(), Some("1.12.2"));
+        assert_eq!(instance.launcher.modpack(), None);
+    }
+
+    #[test]
+    fn test_unsupported_loader() {
+        let instance = parse(
+            r#"{
+                "id": "1.8.9",
+                "launcher": {
+                    "name": "Legacy",
+                    "loaderVersion": { "version": "0.14.21", "type": "LegacyFabric" }
+                }
+            }"#,
+        );
+
+        assert!(instance.to_dependencies().is_none());
+    }
+}
@@ -0,0 +1,487 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::{trace, warn};
+
+use crate::{
+    api::keys::instance::*,
+    api::translation::Translation,
+    domain::{
+        instance::info::{CurseforgeModpack, GameResolution, GameVersion, JavaOverride, Modpack},
+        modplatforms::{
+            curseforge::{
+                filters::{
+                    ModFilesParameters, ModFilesParametersQuery, ModSearchParameters,
+                    ModSearchParametersQuery,
+                },
+                ClassId,
+            },
+            modrinth::version::ModrinthPackDependencies,
+        },
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::InstanceVersionSource,
+        modplatforms::modrinth::convert_mr_version_to_standard_version, AppInner,
+    },
+};
+
+use super::{
+    ImportScanStatus, ImportableInstance, ImportedGameSettings, ImporterState, InstanceImporter,
+    InternalImportEntry, InvalidImportEntry,
+};
+
+const MINECRAFT_GAME_ID: i32 = 432;
+
+#[derive(Debug, Clone)]
+struct Importable {
+    filename: String,
+    path: PathBuf,
+    config: FtbInstance,
+    dependencies: ModrinthPackDependencies,
+}
+
+impl From<Importable> for ImportableInstance {
+    fn from(value: Importable) -> Self {
+        Self {
+            filename: value.filename,
+            instance_name: value.config.name,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FTBImporter {
+    state: RwLock<ImporterState<Importable>>,
+}
+
+impl FTBImporter {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(ImporterState::NoResults),
+        }
+    }
+
+    pub async fn get_default_scan_path() -> anyhow::Result<PathBuf> {
+        let basedirs = directories::BaseDirs::new().ok_or(anyhow!("Cannot build basedirs"))?;
+
+        #[cfg(target_os = "linux")]
+        let p = basedirs.home_dir().join(".ftba");
+        #[cfg(target_os = "windows")]
+        let p = basedirs.data_local_dir().join(".ftba");
+        #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+        let p = basedirs.data_dir().join(".ftba");
+
+        Ok(p.join("instances"))
+    }
+
+    async fn scan_instance(
+        &self,
+        path: PathBuf,
+    ) -> anyhow::Result<Option<InternalImportEntry<Importable>>> {
+        let config = path.join("instance.json");
+        if !config.is_file() {
+            return Ok(None);
+        }
+
+        let filename = path
+            .file_name()
+            .expect("filename cannot be empty")
+            .to_string_lossy()
+            .to_string();
+
+        let config = tokio::fs::read_to_string(config).await?;
+        let Ok(config) = serde_json::from_str::<FtbInstance>(&config) else {
+            return Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason: Translation::InstanceImportFtbBadConfigFile,
+            })));
+        };
+
+        match config.to_dependencies() {
+            Some(dependencies) => Ok(Some(InternalImportEntry::Valid(Importable {
+                filename,
+                path,
+                config,
+                dependencies,
+            }))),
+            None => Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason: Translation::InstanceImportFtbUnsupportedLoader,
+            }))),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl InstanceImporter for FTBImporter {
+    async fn scan(&self, app: &Arc<AppInner>, scan_path: PathBuf) -> anyhow::Result<()> {
+        if scan_path.join("instance.json").is_file() {
+            if let Ok(Some(entry)) = self.scan_instance(scan_path).await {
+                self.state.write().await.set_single(entry).await;
+                app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+            }
+        } else if scan_path.is_dir() {
+            let Ok(mut dir) = tokio::fs::read_dir(&scan_path).await else {
+                return Ok(());
+            };
+
+            while let Some(path) = dir.next_entry().await? {
+                if path.metadata().await?.is_dir() {
+                    if let Ok(Some(entry)) = self.scan_instance(path.path()).await {
+                        self.state.write().await.push_multi(entry).await;
+                        app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_status(&self) -> ImportScanStatus {
+        self.state.read().await.clone().into()
+    }
+
+    async fn begin_import(
+        &self,
+        app: &Arc<AppInner>,
+        index: u32,
+        name: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        trace!(?index, ?name, "Beginning ftb import");
+
+        let instance = self
+            .state
+            .read()
+            .await
+            .get(index)
+            .await
+            .cloned()
+            .ok_or_else(|| anyhow!("invalid importable instance index {index}"))?;
+
+        let standard_version =
+            convert_mr_version_to_standard_version(app.clone(), instance.dependencies.clone())
+                .await?;
+
+        let version = GameVersion::Standard(standard_version);
+
+        let modpack = match find_curseforge_modpack(app, &instance.config).await {
+            Ok(modpack) => modpack,
+            Err(e) => {
+                warn!({ error = ?e }, "Could not link ftb pack to curseforge");
+                None
+            }
+        };
+
+        let instance_version_source = match modpack {
+            Some(modpack) => InstanceVersionSource::ModpackWithKnownVersion(
+                version,
+                Modpack::Curseforge(modpack),
+                !instance.config.is_modified,
+            ),
+            None => InstanceVersionSource::Version(version),
+        };
+
+        let icon_path = instance.path.join("folder.jpg");
+        let icon = match icon_path.is_file() {
+            true => app.instance_manager().load_icon(icon_path).await.ok(),
+            false => None,
+        };
+
+        let last_played = instance
+            .config
+            .last_played
+            .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0));
+
+        let initializer = |instance_path: PathBuf| {
+            let instance = &instance;
+            async move {
+                let path = instance_path.join("instance");
+
+                tokio::fs::create_dir_all(instance_path.join(".setup").join("modpack-complete"))
+                    .await?;
+
+                trace!("Copying files from ftb instance");
+                crate::domain::runtime_path::copy_dir_pruned(&instance.path, &path, |path| {
+                    !matches!(
+                        path.to_str(),
+                        Some("instance.json" | "folder.jpg" | ".ftbapp" | "natives")
+                    )
+                })
+                .await?;
+
+                Ok(())
+            }
+        };
+
+        let id = app
+            .instance_manager()
+            .create_instance_ext(
+                app.instance_manager().get_default_group().await?,
+                name.unwrap_or_else(|| instance.config.name.clone()),
+                icon,
+                instance.config.total_play_time.map(|ms| (ms / 1000) as u32),
+                last_played,
+                instance_version_source,
+                String::new(),
+                initializer,
+            )
+            .await?;
+
+        instance.config.game_settings().apply(app, id).await?;
+
+        app.instance_manager()
+            .prepare_game(id, None, None, true)
+            .await
+            .map(|r| r.1)
+    }
+}
+
+/// Packs installed from curseforge through the FTB App carry their curseforge ids directly,
+/// FTB's own packs are looked up on curseforge by name and version.
+async fn find_curseforge_modpack(
+    app: &Arc<AppInner>,
+    config: &FtbInstance,
+) -> anyhow::Result<Option<CurseforgeModpack>> {
+    if config.pack_type == FTB_PACK_TYPE_CURSEFORGE {
+        return Ok(Some(CurseforgeModpack {
+            project_id: config.id,
+            file_id: config.version_id,
+        }));
+    }
+
+    let curseforge = &app.modplatforms_manager().curseforge;
+
+    let projects = curseforge
+        .search(ModSearchParameters {
+            query: ModSearchParametersQuery {
+                game_id: MINECRAFT_GAME_ID,
+                search_filter: Some(config.name.clone()),
+                game_version: Some(config.mc_version.clone()),
+                category_ids: None,
+                sort_order: None,
+                sort_field: None,
+                class_id: Some(ClassId::Modpacks),
+                mod_loader_types: None,
+                game_version_type_id: None,
+                author_id: None,
+                slug: None,
+                index: None,
+                page_size: Some(20),
+            },
+        })
+        .await?
+        .data;
+
+    let Some(project) = projects.into_iter().find(|project| {
+        project.name.eq_ignore_ascii_case(&config.name)
+            && project.authors.iter().any(|author| {
+                let author = author.name.to_lowercase();
+                author.contains("ftb") || author.contains("feed the beast")
+            })
+    }) else {
+        return Ok(None);
+    };
+
+    let files = curseforge
+        .get_mod_files(ModFilesParameters {
+            mod_id: project.id,
+            query: ModFilesParametersQuery {
+                game_version: Some(config.mc_version.clone()),
+                mod_loader_type: None,
+                game_version_type_id: None,
+                index: None,
+                page_size: Some(50),
+            },
+        })
+        .await?
+        .data;
+
+    let file = files.into_iter().find(|file| {
+        contains_version(&file.display_name, &config.version)
+            || contains_version(&file.file_name, &config.version)
+    });
+
+    Ok(file.map(|file| CurseforgeModpack {
+        project_id: project.id as u32,
+        file_id: file.id as u32,
+    }))
+}
+
+/// Checks that `version` appears in `text` as a whole token, so `1.2` does not match `1.2.1`.
+fn contains_version(text: &str, version: &str) -> bool {
+    if version.is_empty() {
+        return false;
+    }
+
+    text.match_indices(version).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let mut after = text[start + version.len()..].chars();
+
+        let before_ok = !matches!(before, Some(c) if c.is_ascii_alphanumeric() || c == '.');
+        // a trailing `.` is fine as long as it does not continue the version (`.zip`)
+        let after_ok = match after.next() {
+            Some('.') => !matches!(after.next(), Some(c) if c.is_ascii_digit()),
+            Some(c) => !c.is_ascii_alphanumeric(),
+            None => true,
+        };
+
+        before_ok && after_ok
+    })
+}
+
+const FTB_PACK_TYPE_CURSEFORGE: u8 = 1;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FtbInstance {
+    /// ftb (or curseforge) pack id
+    id: u32,
+    /// ftb (or curseforge) pack version id
+    version_id: u32,
+    name: String,
+    /// pack version name
+    #[serde(default)]
+    version: String,
+    mc_version: String,
+    mod_loader: Option<String>,
+    #[serde(default)]
+    pack_type: u8,
+    #[serde(default)]
+    is_modified: bool,
+    jvm_args: Option<String>,
+    min_memory: Option<u16>,
+    memory: Option<u16>,
+    #[serde(default)]
+    embedded_jre: bool,
+    jre_path: Option<String>,
+    width: Option<u16>,
+    height: Option<u16>,
+    /// milliseconds
+    total_play_time: Option<u64>,
+    /// seconds since epoch
+    last_played: Option<i64>,
+}
+
+impl FtbInstance {
+    /// Returns `None` if the instance uses a loader that is not supported.
+    fn to_dependencies(&self) -> Option<ModrinthPackDependencies> {
+        let mut dependencies = ModrinthPackDependencies {
+            minecraft: Some(self.mc_version.clone()),
+            forge: None,
+            neoforge: None,
+            fabric_loader: None,
+            quilt_loader: None,
+        };
+
+        let Some(mod_loader) = self.mod_loader.as_deref().filter(|l| !l.is_empty()) else {
+            return Some(dependencies);
+        };
+
+        // written as `<mc>-<loader>-<version>` or `<loader>-loader-<version>-<mc>`
+        let mut parts = mod_loader
+            .split('-')
+            .skip_while(|part| !matches!(*part, "forge" | "neoforge" | "fabric" | "quilt"));
+
+        let loader = parts.next()?;
+        let version = parts
+            .find(|part| *part != "loader" && *part != self.mc_version)?
+            .to_string();
+
+        match loader {
+            "forge" => dependencies.forge = Some(version),
+            "neoforge" => dependencies.neoforge = Some(version),
+            "fabric" => dependencies.fabric_loader = Some(version),
+            "quilt" => dependencies.quilt_loader = Some(version),
+            _ => unreachable!(),
+        }
+
+        Some(dependencies)
+    }
+
+    fn game_settings(&self) -> ImportedGameSettings {
+        ImportedGameSettings {
+            extra_java_args: self.jvm_args.clone().filter(|args| !args.trim().is_empty()),
+            memory: self
+                .memory
+                .map(|max| (self.min_memory.unwrap_or(max).min(max), max)),
+            java_override: match self.embedded_jre {
+                true => None,
+                false => self
+                    .jre_path
+                    .clone()
+                    .map(|path| JavaOverride::Path(Some(path))),
+            },
+            game_resolution: self
+                .width
+                .zip(self.height)
+                .map(|(width, height)| GameResolution::Custom(width, height)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::instance::info::GameResolution;
+
+    use super::{contains_version, FtbInstance};
+
+    fn parse(text: &str) -> FtbInstance {
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_forge_ftb_pack() {
+        let instance = parse(include_str!("test_fixtures/ftb/forge-instance.json"));
+        let dependencies = instance.to_dependencies().unwrap();
+
+        assert_eq!(dependencies.minecraft.as_deref(), Some("1.20.1"));
+        assert_eq!(dependencies.forge.as_deref(), Some("47.2.20"));
+        assert_eq!(dependencies.fabric_loader, None);
+        assert_eq!(instance.pack_type, 0);
+        assert_eq!(instance.version, "1.9.2");
+
+        let settings = instance.game_settings();
+        assert_eq!(settings.memory, Some((4096, 8192)));
+        assert!(settings.java_override.is_none());
+        assert_eq!(
+            settings.game_resolution,
+            Some(GameResolution::Custom(1720, 1000))
+        );
+    }
+
+    #[test]
+    fn test_fabric_curseforge_pack() {
+        let instance = parse(include_str!(
+            "test_fixtures/ftb/fabric-curseforge-instance.json"
+        ));
+        let dependencies = instance.to_dependencies().unwrap();
+
+        assert_eq!(dependencies.minecraft.as_deref(), Some("1.20.1"));
+        assert_eq!(dependencies.fabric_loader.as_deref(), Some("0.15.11"));
+        assert_eq!(instance.pack_type, 1);
+        assert_eq!((instance.id, instance.version_id), (452013, 5210482));
+        assert_eq!(instance.game_settings().extra_java_args, None);
+    }
+
+    #[test]
+    fn test_unsupported_loader() {
+        let mut instance = parse(include_str!("test_fixtures/ftb/forge-instance.json"));
+        instance.mod_loader = Some(String::from("1.12.2-liteloader-1.12.2"));
+
+        assert!(instance.to_dependencies().is_none());
+    }
+
+    #[test]
+    fn test_contains_version() {
+        assert!(contains_version("FTB Skies 1.9.2", "1.9.2"));
+        assert!(contains_version("FTBSkies-1.9.2.zip", "1.9.2"));
+        assert!(!contains_version("FTB Skies 1.9.21", "1.9.2"));
+        assert!(!contains_version("FTB Skies 11.9.2", "1.9.2"));
+        assert!(!contains_version("FTB Skies", ""));
+    }
+}
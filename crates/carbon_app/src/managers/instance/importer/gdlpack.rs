@@ -0,0 +1,387 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use carbon_net::{Checksum, DownloadOptions, Downloadable};
+use tokio::sync::RwLock;
+use tracing::trace;
+
+use crate::{
+    api::keys::instance::*,
+    api::translation::Translation,
+    domain::{
+        instance::{info, InstanceSettingsUpdate},
+        modplatforms::curseforge::filters::{FilesParameters, FilesParametersBody},
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::{
+            gdlpack::{self, Manifest, ReferenceSource},
+            schema, InstanceVersionSource,
+        },
+        AppInner,
+    },
+    util::is_safe_relative_path,
+};
+
+use super::{
+    ImportScanStatus, ImportableInstance, ImporterState, InstanceImporter, InternalImportEntry,
+    InvalidImportEntry,
+};
+
+#[derive(Debug, Clone)]
+struct Importable {
+    filename: String,
+    path: PathBuf,
+    config: info::Instance,
+    manifest: Manifest,
+}
+
+impl From<Importable> for ImportableInstance {
+    fn from(value: Importable) -> Self {
+        Self {
+            filename: value.filename,
+            instance_name: value.config.name,
+        }
+    }
+}
+
+/// Importer for native `.gdlpack` archives, see [`gdlpack`].
+#[derive(Debug)]
+pub struct GDLPackImporter {
+    state: RwLock<ImporterState<Importable>>,
+}
+
+impl GDLPackImporter {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(ImporterState::NoResults),
+        }
+    }
+
+    async fn scan_archive(
+        &self,
+        path: PathBuf,
+    ) -> anyhow::Result<Option<InternalImportEntry<Importable>>> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let filename = path
+            .file_name()
+            .expect("filename cannot be empty")
+            .to_string_lossy()
+            .to_string();
+
+        let path2 = path.clone();
+        let r = tokio::task::spawn_blocking(move || {
+            let file =
+                fs::File::open(path2).map_err(|_| Translation::InstanceImportGdlpackMalformed)?;
+            let mut zip = zip::ZipArchive::new(file)
+                .map_err(|_| Translation::InstanceImportGdlpackMalformed)?;
+
+            let mut read = |name: &str| {
+                let mut text = String::new();
+                zip.by_name(name)
+                    .map_err(|_| Translation::InstanceImportGdlpackMissingManifest)?
+                    .read_to_string(&mut text)
+                    .map_err(|_| Translation::InstanceImportGdlpackMalformedManifest)?;
+                Ok::<_, Translation>(text)
+            };
+
+            let manifest = gdlpack::parse_manifest(&read(gdlpack::MANIFEST_PATH)?)
+                .map_err(|_| Translation::InstanceImportGdlpackMalformedManifest)?;
+
+            let config = schema::parse_instance_config(&read(gdlpack::INSTANCE_CONFIG_PATH)?)
+                .map_err(|_| Translation::InstanceImportGdlpackMalformedManifest)?;
+
+            if config.game_configuration.version.is_none() {
+                return Err(Translation::InstanceImportGdlpackMalformedManifest);
+            }
+
+            Ok((manifest, config))
+        })
+        .await?;
+
+        let (manifest, config) = match r {
+            Ok(t) => t,
+            Err(reason) => {
+                return Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                    name: filename,
+                    reason,
+                })))
+            }
+        };
+
+        Ok(Some(InternalImportEntry::Valid(Importable {
+            filename,
+            path,
+            config,
+            manifest,
+        })))
+    }
+}
+
+#[async_trait::async_trait]
+impl InstanceImporter for GDLPackImporter {
+    async fn scan(&self, app: &Arc<AppInner>, scan_path: PathBuf) -> anyhow::Result<()> {
+        if scan_path.is_file() {
+            if let Ok(Some(entry)) = self.scan_archive(scan_path).await {
+                self.state.write().await.set_single(entry).await;
+                app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+            }
+        } else if scan_path.is_dir() {
+            let Ok(mut dir) = tokio::fs::read_dir(&scan_path).await else {
+                return Ok(());
+            };
+
+            while let Some(entry) = dir.next_entry().await? {
+                let path = entry.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some("gdlpack") {
+                    continue;
+                }
+
+                if let Ok(Some(entry)) = self.scan_archive(path).await {
+                    self.state.write().await.push_multi(entry).await;
+                    app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_status(&self) -> ImportScanStatus {
+        self.state.read().await.clone().into()
+    }
+
+    async fn begin_import(
+        &self,
+        app: &Arc<AppInner>,
+        index: u32,
+        name: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        trace!(?index, ?name, "Beginning gdlpack import");
+
+        let instance = self
+            .state
+            .read()
+            .await
+            .get(index)
+            .await
+            .cloned()
+            .ok_or_else(|| anyhow!("invalid importable instance index {index}"))?;
+
+        let config = instance.config.clone();
+
+        let version = config
+            .game_configuration
+            .version
+            .clone()
+            .ok_or_else(|| anyhow!("gdlpack instance has no game version"))?;
+
+        let instance_version_source = match config.modpack.clone() {
+            Some(info::ModpackInfo { modpack, locked }) => {
+                InstanceVersionSource::ModpackWithKnownVersion(version, modpack, locked)
+            }
+            None => InstanceVersionSource::Version(version),
+        };
+
+        let downloads = resolve_downloads(app, &instance.manifest).await?;
+
+        let archive_path = instance.path.clone();
+        let has_modpack = config.modpack.is_some();
+        let icon = tokio::task::spawn_blocking({
+            let archive_path = archive_path.clone();
+            move || read_icon(&archive_path)
+        })
+        .await??;
+
+        let initializer = |instance_path: PathBuf| async move {
+            trace!("Extracting gdlpack archive");
+            let extract_path = instance_path.clone();
+            tokio::task::spawn_blocking(move || extract_archive(&archive_path, &extract_path))
+                .await??;
+
+            if has_modpack {
+                // the modpack files are already part of the archive
+                tokio::fs::create_dir_all(instance_path.join(".setup").join("modpack-complete"))
+                    .await?;
+            }
+
+            let data_path = instance_path.join("instance");
+            let downloads = downloads
+                .into_iter()
+                .map(|(url, path, size, sha1)| {
+                    Downloadable::new(url, data_path.join(path))
+                        .with_size(size)
+                        .with_checksum(Some(Checksum::Sha1(sha1)))
+                })
+                .collect::<Vec<_>>();
+
+            trace!("Downloading {} referenced gdlpack files", downloads.len());
+            carbon_net::download_multiple(&downloads, DownloadOptions::default()).await?;
+
+            Ok(())
+        };
+
+        let id = app
+            .instance_manager()
+            .create_instance_ext(
+                app.instance_manager().get_default_group().await?,
+                name.unwrap_or_else(|| config.name.clone()),
+                icon,
+                Some(config.seconds_played),
+                config.last_played,
+                instance_version_source,
+                config.notes.clone(),
+                initializer,
+            )
+            .await?;
+
+        let game = config.game_configuration;
+        app.instance_manager()
+            .update_instance(InstanceSettingsUpdate {
+                instance_id: id,
+                name: None,
+                use_loaded_icon: None,
+                notes: None,
+                version: None,
+                modloader: None,
+                java_override: Some(game.java_override),
+                global_java_args: Some(game.global_java_args),
+                extra_java_args: Some(game.extra_java_args),
+                memory: Some(game.memory),
+                pre_launch_hook: Some(config.pre_launch_hook),
+                post_exit_hook: Some(config.post_exit_hook),
+                wrapper_command: Some(config.wrapper_command),
+                game_resolution: Some(game.game_resolution),
+                mod_sources: Some(config.mod_sources),
+                modpack_locked: None,
+            })
+            .await?;
+
+        app.instance_manager()
+            .prepare_game(id, None, None, true)
+            .await
+            .map(|r| r.1)
+    }
+}
+
+/// Resolves the download url of every referenced file.
+/// Returns `(url, path, size, sha1)` with the path relative to the data directory.
+async fn resolve_downloads(
+    app: &Arc<AppInner>,
+    manifest: &Manifest,
+) -> anyhow::Result<Vec<(String, String, u64, String)>> {
+    let curseforge_ids = manifest
+        .references
+        .iter()
+        .filter_map(|reference| match reference.source {
+            ReferenceSource::Curseforge { file_id, .. } => Some(file_id as i32),
+            ReferenceSource::Modrinth { .. } => None,
+        })
+        .collect::<Vec<_>>();
+
+    let curseforge_urls = match curseforge_ids.is_empty() {
+        true => HashMap::new(),
+        false => app
+            .modplatforms_manager()
+            .curseforge
+            .get_files(FilesParameters {
+                body: FilesParametersBody {
+                    file_ids: curseforge_ids,
+                },
+            })
+            .await?
+            .data
+            .into_iter()
+            .filter_map(|file| Some((file.id as u32, file.download_url?)))
+            .collect::<HashMap<_, _>>(),
+    };
+
+    manifest
+        .references
+        .iter()
+        .map(|reference| {
+            let url = match &reference.source {
+                ReferenceSource::Modrinth { url, .. } => url.clone(),
+                ReferenceSource::Curseforge { file_id, .. } => curseforge_urls
+                    .get(file_id)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Failed to get download url for {}", reference.path))?,
+            };
+
+            if !is_safe_relative_path(&reference.path) {
+                return Err(anyhow!("Invalid reference path {}", reference.path));
+            }
+
+            Ok((
+                url,
+                reference.path.clone(),
+                reference.size,
+                reference.sha1.clone(),
+            ))
+        })
+        .collect()
+}
+
+fn read_icon(archive_path: &Path) -> anyhow::Result<Option<(String, Vec<u8>)>> {
+    let mut zip = zip::ZipArchive::new(fs::File::open(archive_path)?)?;
+    let prefix = format!("{}/", gdlpack::ICON_PREFIX);
+
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i)?;
+        let Some(name) = file.name().strip_prefix(&prefix).map(str::to_string) else {
+            continue;
+        };
+
+        if !is_safe_relative_path(&name) || name.contains('/') {
+            continue;
+        }
+
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        return Ok(Some((name, data)));
+    }
+
+    Ok(None)
+}
+
+/// Extracts the data directory and packinfo into a new instance directory.
+fn extract_archive(archive_path: &Path, instance_path: &Path) -> anyhow::Result<()> {
+    let mut zip = zip::ZipArchive::new(fs::File::open(archive_path)?)?;
+    let data_prefix = format!("{}/", gdlpack::DATA_PREFIX);
+
+    for i in 0..zip.len() {
+        let mut file = zip.by_index(i)?;
+        if file.is_dir() {
+            continue;
+        }
+
+        let Some(enclosed) = file.enclosed_name() else {
+            continue;
+        };
+
+        let target = if file.name() == gdlpack::PACKINFO_PATH {
+            instance_path.join(gdlpack::PACKINFO_PATH)
+        } else if let Ok(relpath) = enclosed.strip_prefix(&data_prefix) {
+            instance_path.join("instance").join(relpath)
+        } else {
+            continue;
+        };
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        io::copy(&mut file, &mut fs::File::create(target)?)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,515 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::trace;
+
+use crate::{
+    api::keys::instance::*,
+    api::translation::Translation,
+    domain::{
+        instance::info::{GameResolution, GameVersion, JavaOverride},
+        modplatforms::modrinth::version::ModrinthPackDependencies,
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::InstanceVersionSource,
+        modplatforms::modrinth::convert_mr_version_to_standard_version, AppInner,
+    },
+};
+
+use super::{
+    ImportScanStatus, ImportableInstance, ImportedGameSettings, ImporterState, InstanceImporter,
+    InternalImportEntry, InvalidImportEntry,
+};
+
+#[derive(Debug, Clone)]
+struct Importable {
+    name: String,
+    game_dir: PathBuf,
+    profile: LauncherProfile,
+    version: ProfileVersion,
+}
+
+impl From<Importable> for ImportableInstance {
+    fn from(value: Importable) -> Self {
+        Self {
+            filename: value
+                .profile
+                .last_version_id
+                .unwrap_or_else(|| value.profile.type_.clone()),
+            instance_name: value.name,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ProfileVersion {
+    LatestRelease,
+    LatestSnapshot,
+    Resolved(ModrinthPackDependencies),
+}
+
+/// Importer for profiles of the official minecraft launcher, read from `launcher_profiles.json`.
+#[derive(Debug)]
+pub struct MinecraftLauncherImporter {
+    state: RwLock<ImporterState<Importable>>,
+}
+
+impl MinecraftLauncherImporter {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(ImporterState::NoResults),
+        }
+    }
+
+    pub async fn get_default_scan_path() -> anyhow::Result<PathBuf> {
+        let basedirs = directories::BaseDirs::new().ok_or(anyhow!("Cannot build basedirs"))?;
+
+        #[cfg(target_os = "linux")]
+        let p = basedirs.home_dir().join(".minecraft");
+        #[cfg(target_os = "macos")]
+        let p = basedirs.data_dir().join("minecraft");
+        #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+        let p = basedirs.data_dir().join(".minecraft");
+
+        Ok(p)
+    }
+
+    async fn scan_profile(
+        &self,
+        minecraft_dir: &PathBuf,
+        profile: LauncherProfile,
+    ) -> anyhow::Result<InternalImportEntry<Importable>> {
+        let name = profile.display_name();
+
+        let game_dir = match &profile.game_dir {
+            Some(game_dir) => PathBuf::from(game_dir),
+            None => minecraft_dir.clone(),
+        };
+
+        if !game_dir.is_dir() {
+            return Ok(InternalImportEntry::Invalid(InvalidImportEntry {
+                name,
+                reason: Translation::InstanceImportMinecraftLauncherMissingGameDir,
+            }));
+        }
+
+        let version = match (&*profile.type_, &profile.last_version_id) {
+            ("latest-snapshot", _) => ProfileVersion::LatestSnapshot,
+            ("latest-release", _) | (_, None) => ProfileVersion::LatestRelease,
+            (_, Some(version_id)) => {
+                let version_json = minecraft_dir
+                    .join("versions")
+                    .join(version_id)
+                    .join(format!("{version_id}.json"));
+
+                let inherits_from = match tokio::fs::read_to_string(version_json).await {
+                    Ok(text) => serde_json::from_str::<CustomVersion>(&text)
+                        .ok()
+                        .and_then(|version| version.inherits_from),
+                    Err(_) => None,
+                };
+
+                ProfileVersion::Resolved(parse_version_id(version_id, inherits_from.as_deref()))
+            }
+        };
+
+        Ok(InternalImportEntry::Valid(Importable {
+            name,
+            game_dir,
+            profile,
+            version,
+        }))
+    }
+}
+
+#[async_trait::async_trait]
+impl InstanceImporter for MinecraftLauncherImporter {
+    async fn scan(&self, app: &Arc<AppInner>, scan_path: PathBuf) -> anyhow::Result<()> {
+        let profiles = scan_path.join("launcher_profiles.json");
+        if !profiles.is_file() {
+            return Ok(());
+        }
+
+        let profiles = tokio::fs::read_to_string(profiles).await?;
+        let profiles = serde_json::from_str::<LauncherProfiles>(&profiles)?;
+
+        let mut profiles = profiles.profiles.into_values().collect::<Vec<_>>();
+        // most recently used first, matching the official launcher
+        profiles.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+
+        for profile in profiles {
+            if let Ok(entry) = self.scan_profile(&scan_path, profile).await {
+                self.state.write().await.push_multi(entry).await;
+                app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_status(&self) -> ImportScanStatus {
+        self.state.read().await.clone().into()
+    }
+
+    async fn begin_import(
+        &self,
+        app: &Arc<AppInner>,
+        index: u32,
+        name: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        trace!(?index, ?name, "Beginning minecraft launcher import");
+
+        let instance = self
+            .state
+            .read()
+            .await
+            .get(index)
+            .await
+            .cloned()
+            .ok_or_else(|| anyhow!("invalid importable instance index {index}"))?;
+
+        let dependencies = match &instance.version {
+            ProfileVersion::Resolved(dependencies) => dependencies.clone(),
+            latest => {
+                let manifest = app.minecraft_manager().get_minecraft_manifest().await?;
+
+                let minecraft = match latest {
+                    ProfileVersion::LatestSnapshot => manifest.latest.snapshot,
+                    _ => manifest.latest.release,
+                };
+
+                parse_version_id(&minecraft, None)
+            }
+        };
+
+        let standard_version =
+            convert_mr_version_to_standard_version(app.clone(), dependencies).await?;
+
+        let instance_version_source =
+            InstanceVersionSource::Version(GameVersion::Standard(standard_version));
+
+        let last_played = instance
+            .profile
+            .last_used
+            .as_deref()
+            .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+            .map(|date| date.with_timezone(&Utc));
+
+        let initializer = |instance_path: PathBuf| {
+            let instance = &instance;
+            async move {
+                let path = instance_path.join("instance");
+
+                trace!("Copying files from minecraft launcher profile");
+                crate::domain::runtime_path::copy_dir_pruned(&instance.game_dir, &path, |path| {
+                    let Some(path) = path.to_str() else {
+                        return true;
+                    };
+
+                    // skip the launcher's own data when the profile uses the default game dir
+                    !(matches!(
+                        path,
+                        "versions"
+                            | "libraries"
+                            | "assets"
+                            | "runtime"
+                            | "bin"
+                            | "webcache"
+                            | "webcache2"
+                            | "logs"
+                            | "crash-reports"
+                            | "treatment_tags.json"
+                    ) || (!path.contains(std::path::MAIN_SEPARATOR)
+                        && path.starts_with("launcher")))
+                })
+                .await?;
+
+                Ok(())
+            }
+        };
+
+        let id = app
+            .instance_manager()
+            .create_instance_ext(
+                app.instance_manager().get_default_group().await?,
+                name.unwrap_or_else(|| instance.name.clone()),
+                None,
+                None,
+                last_played,
+                instance_version_source,
+                String::new(),
+                initializer,
+            )
+            .await?;
+
+        instance.profile.game_settings().apply(app, id).await?;
+
+        app.instance_manager()
+            .prepare_game(id, None, None, true)
+            .await
+            .map(|r| r.1)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct LauncherProfiles {
+    profiles: HashMap<String, LauncherProfile>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LauncherProfile {
+    #[serde(default)]
+    name: String,
+    #[serde(rename = "type", default)]
+    type_: String,
+    last_version_id: Option<String>,
+    game_dir: Option<String>,
+    java_dir: Option<String>,
+    java_args: Option<String>,
+    resolution: Option<ProfileResolution>,
+    /// RFC 3339 timestamp
+    last_used: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ProfileResolution {
+    width: u16,
+    height: u16,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CustomVersion {
+    inherits_from: Option<String>,
+}
+
+impl LauncherProfile {
+    /// The launcher leaves the name of its builtin profiles empty.
+    fn display_name(&self) -> String {
+        match (&*self.name, &*self.type_) {
+            ("", "latest-release") => String::from("Latest release"),
+            ("", "latest-snapshot") => String::from("Latest snapshot"),
+            ("", _) => self
+                .last_version_id
+                .clone()
+                .unwrap_or_else(|| String::from("Minecraft")),
+            (name, _) => name.to_string(),
+        }
+    }
+
+    /// Heap size flags are carried over as the instance memory, everything else as extra args.
+    fn game_settings(&self) -> ImportedGameSettings {
+        let mut min_memory = None;
+        let mut max_memory = None;
+        let mut extra_args = Vec::new();
+
+        for arg in self
+            .java_args
+            .as_deref()
+            .unwrap_or_default()
+            .split_whitespace()
+        {
+            if let Some(size) = arg.strip_prefix("-Xmx").and_then(parse_memory_size) {
+                max_memory = Some(size);
+            } else if let Some(size) = arg.strip_prefix("-Xms").and_then(parse_memory_size) {
+                min_memory = Some(size);
+            } else {
+                extra_args.push(arg);
+            }
+        }
+
+        ImportedGameSettings {
+            extra_java_args: (!extra_args.is_empty()).then(|| extra_args.join(" ")),
+            memory: max_memory.map(|max| (min_memory.unwrap_or(max).min(max), max)),
+            java_override: self
+                .java_dir
+                .clone()
+                .map(|path| JavaOverride::Path(Some(path))),
+            game_resolution: self
+                .resolution
+                .as_ref()
+                .map(|r| GameResolution::Custom(r.width, r.height)),
+        }
+    }
+}
+
+/// Parses a JVM heap size (`2G`, `512M`, `1048576K`) into megabytes.
+fn parse_memory_size(size: &str) -> Option<u16> {
+    let (value, unit) = size.split_at(size.find(|c: char| !c.is_ascii_digit())?);
+    let value = value.parse::<u64>().ok()?;
+
+    let mb = match unit {
+        "g" | "G" => value * 1024,
+        "m" | "M" => value,
+        "k" | "K" => value / 1024,
+        _ => return None,
+    };
+
+    u16::try_from(mb).ok()
+}
+
+/// Resolves a launcher version id into the game version and modloader, based on the names
+/// the loader installers give to the versions they create.
+fn parse_version_id(version_id: &str, inherits_from: Option<&str>) -> ModrinthPackDependencies {
+    let mut dependencies = ModrinthPackDependencies {
+        minecraft: None,
+        forge: None,
+        neoforge: None,
+        fabric_loader: None,
+        quilt_loader: None,
+    };
+
+    let loader_and_minecraft = |rest: &str| match rest.split_once('-') {
+        Some((loader, minecraft)) => (loader.to_string(), minecraft.to_string()),
+        None => (rest.to_string(), String::new()),
+    };
+
+    let minecraft = if let Some(rest) = version_id.strip_prefix("fabric-loader-") {
+        let (loader, minecraft) = loader_and_minecraft(rest);
+        dependencies.fabric_loader = Some(loader);
+        minecraft
+    } else if let Some(rest) = version_id.strip_prefix("quilt-loader-") {
+        let (loader, minecraft) = loader_and_minecraft(rest);
+        dependencies.quilt_loader = Some(loader);
+        minecraft
+    } else if let Some(loader) = version_id.strip_prefix("neoforge-") {
+        dependencies.neoforge = Some(loader.to_string());
+        neoforge_minecraft_version(loader).unwrap_or_default()
+    } else if let Some(index) = find_ignore_ascii_case(version_id, "-forge") {
+        // `<mc>-forge-<forge>`, `<mc>-forge<mc>-<forge>` or `<mc>-Forge<forge>-<mc>`
+        let minecraft = &version_id[..index];
+        let loader = version_id[index + "-forge".len()..].trim_start_matches('-');
+        let loader = loader
+            .strip_prefix(&format!("{minecraft}-"))
+            .unwrap_or(loader);
+        let loader = loader
+            .strip_suffix(&format!("-{minecraft}"))
+            .unwrap_or(loader);
+
+        dependencies.forge = Some(loader.to_string());
+        minecraft.to_string()
+    } else {
+        version_id.to_string()
+    };
+
+    dependencies.minecraft = Some(match inherits_from {
+        Some(inherits_from) => inherits_from.to_string(),
+        None => minecraft,
+    });
+
+    dependencies
+}
+
+/// Byte index of the first occurrence of the ASCII `needle` in `haystack`, ignoring ASCII case.
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .char_indices()
+        .map(|(index, _)| index)
+        .find(|&index| {
+            haystack.as_bytes()[index..]
+                .get(..needle.len())
+                .is_some_and(|bytes| bytes.eq_ignore_ascii_case(needle.as_bytes()))
+        })
+}
+
+/// NeoForge versions encode the game version, `20.4.237` is for `1.20.4`, `21.0.1` for `1.21`.
+fn neoforge_minecraft_version(loader: &str) -> Option<String> {
+    let mut parts = loader.split('.');
+    let major = parts.next()?;
+    let minor = parts.next()?;
+
+    Some(match minor {
+        "0" => format!("1.{major}"),
+        minor => format!("1.{major}.{minor}"),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::instance::info::GameResolution;
+
+    use super::{parse_memory_size, parse_version_id, LauncherProfiles};
+
+    #[test]
+    fn test_parse_version_ids() {
+        let fabric = parse_version_id("fabric-loader-0.15.11-1.20.1", None);
+        assert_eq!(fabric.minecraft.as_deref(), Some("1.20.1"));
+        assert_eq!(fabric.fabric_loader.as_deref(), Some("0.15.11"));
+
+        let quilt = parse_version_id("quilt-loader-0.26.0-1.20.4", None);
+        assert_eq!(quilt.minecraft.as_deref(), Some("1.20.4"));
+        assert_eq!(quilt.quilt_loader.as_deref(), Some("0.26.0"));
+
+        let forge = parse_version_id("1.20.1-forge-47.2.0", None);
+        assert_eq!(forge.minecraft.as_deref(), Some("1.20.1"));
+        assert_eq!(forge.forge.as_deref(), Some("47.2.0"));
+
+        let forge = parse_version_id("1.12.2-forge1.12.2-14.23.5.2847", None);
+        assert_eq!(forge.minecraft.as_deref(), Some("1.12.2"));
+        assert_eq!(forge.forge.as_deref(), Some("14.23.5.2847"));
+
+        let forge = parse_version_id("1.7.10-Forge10.13.4.1614-1.7.10", None);
+        assert_eq!(forge.minecraft.as_deref(), Some("1.7.10"));
+        assert_eq!(forge.forge.as_deref(), Some("10.13.4.1614"));
+
+        // lowercasing `İ` changes its length, indexes must come from the original id
+        let forge = parse_version_id("İ-FORGE-47.2.0", None);
+        assert_eq!(forge.minecraft.as_deref(), Some("İ"));
+        assert_eq!(forge.forge.as_deref(), Some("47.2.0"));
+
+        let neoforge = parse_version_id("neoforge-20.4.237", None);
+        assert_eq!(neoforge.minecraft.as_deref(), Some("1.20.4"));
+        assert_eq!(neoforge.neoforge.as_deref(), Some("20.4.237"));
+
+        let neoforge = parse_version_id("neoforge-21.0.167", Some("1.21"));
+        assert_eq!(neoforge.minecraft.as_deref(), Some("1.21"));
+
+        let vanilla = parse_version_id("1.20.6", None);
+        assert_eq!(vanilla.minecraft.as_deref(), Some("1.20.6"));
+        assert_eq!(vanilla.forge, None);
+        assert_eq!(vanilla.fabric_loader, None);
+    }
+
+    #[test]
+    fn test_parse_memory_size() {
+        assert_eq!(parse_memory_size("2G"), Some(2048));
+        assert_eq!(parse_memory_size("512m"), Some(512));
+        assert_eq!(parse_memory_size("1048576K"), Some(1024));
+        assert_eq!(parse_memory_size("2"), None);
+        assert_eq!(parse_memory_size("G"), None);
+    }
+
+    #[test]
+    fn test_launcher_profiles() {
+        let profiles = serde_json::from_str::<LauncherProfiles>(include_str!(
+            "test_fixtures/minecraft_launcher/launcher_profiles.json"
+        ))
+        .unwrap();
+
+        assert_eq!(profiles.profiles.len(), 3);
+
+        let release = &profiles.profiles["b7e8a3c3f0e54c6f9a0e6c1d2f3a4b5c"];
+        assert_eq!(release.display_name(), "Latest release");
+        assert!(release.game_settings().memory.is_none());
+
+        let fabric = &profiles.profiles["5f6e3c2a9b8d4e7f8a1b2c3d4e5f6a7b"];
+        assert_eq!(fabric.display_name(), "Fabric 1.20.1");
+        assert_eq!(fabric.game_dir.as_deref(), Some("/home/user/games/fabric"));
+
+        let settings = fabric.game_settings();
+        assert_eq!(settings.memory, Some((1024, 4096)));
+        assert_eq!(
+            settings.extra_java_args.as_deref(),
+            Some("-XX:+UnlockExperimentalVMOptions -XX:+UseG1GC")
+        );
+        assert_eq!(
+            settings.game_resolution,
+            Some(GameResolution::Custom(1280, 720))
+        );
+    }
+}
@@ -9,21 +9,37 @@ use tracing::{debug, trace};
 use crate::{
     api::keys::instance::*,
     api::translation::Translation,
-    domain::vtask::VisualTaskId,
+    domain::{
+        instance::{
+            info::{GameResolution, JavaOverride},
+            InstanceId, InstanceSettingsUpdate,
+        },
+        vtask::VisualTaskId,
+    },
     managers::{modplatforms::curseforge::CurseForge, AppInner, ManagerRef},
 };
 
 use self::{
-    curseforge::CurseforgeImporter, curseforge_archive::CurseforgeArchiveImporter,
-    legacy_gdlauncher::LegacyGDLauncherImporter, modrinth_archive::ModrinthArchiveImporter,
+    atlauncher::ATLauncherImporter, curseforge::CurseforgeImporter,
+    curseforge_archive::CurseforgeArchiveImporter, ftb::FTBImporter, gdlpack::GDLPackImporter,
+    legacy_gdlauncher::LegacyGDLauncherImporter, minecraft_launcher::MinecraftLauncherImporter,
+    modrinth_archive::ModrinthArchiveImporter, multimc::MultiMCImporter, packwiz::PackwizImporter,
+    technic::TechnicImporter,
 };
 
 use super::{export::InstanceExportManager, InstanceManager};
 
+mod atlauncher;
 mod curseforge;
 mod curseforge_archive;
+mod ftb;
+mod gdlpack;
 mod legacy_gdlauncher;
+mod minecraft_launcher;
 mod modrinth_archive;
+mod multimc;
+mod packwiz;
+mod technic;
 
 #[derive(Debug)]
 pub struct InstanceImportManager {
@@ -68,7 +84,21 @@ impl ManagerRef<'_, InstanceImportManager> {
                         break;
                     };
 
-                    let scanner = entity.create_importer();
+                    let scanner = match entity.create_importer() {
+                        Ok(scanner) => scanner,
+                        Err(e) => {
+                            tracing::error!({ error = ?e }, "cannot scan path {path:?}");
+
+                            *app.instance_manager()
+                                .import_manager()
+                                .scanner
+                                .write()
+                                .await = None;
+
+                            app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                            break;
+                        }
+                    };
 
                     *app.instance_manager()
                         .import_manager()
@@ -176,6 +206,9 @@ pub enum Entity {
     FTB,
     MultiMC,
     PrismLauncher,
+    MinecraftLauncher,
+    Packwiz,
+    GDLPack,
 }
 
 impl Entity {
@@ -191,17 +224,28 @@ impl Entity {
             Self::FTB => SelectionType::Directory,
             Self::MultiMC => SelectionType::Directory,
             Self::PrismLauncher => SelectionType::Directory,
+            Self::MinecraftLauncher => SelectionType::Directory,
+            Self::Packwiz => SelectionType::Directory,
+            Self::GDLPack => SelectionType::File,
         }
     }
 
     pub fn list() -> Vec<(Self, bool, SelectionType)> {
         use strum::IntoEnumIterator;
 
-        const SUPPORT: [Entity; 4] = [
+        const SUPPORT: [Entity; 12] = [
             Entity::LegacyGDLauncher,
             Entity::CurseForgeZip,
             Entity::MRPack,
             Entity::CurseForge,
+            Entity::MultiMC,
+            Entity::PrismLauncher,
+            Entity::ATLauncher,
+            Entity::Technic,
+            Entity::FTB,
+            Entity::MinecraftLauncher,
+            Entity::Packwiz,
+            Entity::GDLPack,
         ];
 
         Self::iter()
@@ -209,14 +253,21 @@ impl Entity {
             .collect()
     }
 
-    pub fn create_importer(self) -> Arc<dyn InstanceImporter> {
-        match self {
+    pub fn create_importer(self) -> anyhow::Result<Arc<dyn InstanceImporter>> {
+        Ok(match self {
             Self::LegacyGDLauncher => Arc::new(LegacyGDLauncherImporter::new()),
             Self::CurseForgeZip => Arc::new(CurseforgeArchiveImporter::new()),
             Self::MRPack => Arc::new(ModrinthArchiveImporter::new()),
             Self::CurseForge => Arc::new(CurseforgeImporter::new()),
-            _ => todo!(),
-        }
+            Self::MultiMC | Self::PrismLauncher => Arc::new(MultiMCImporter::new()),
+            Self::ATLauncher => Arc::new(ATLauncherImporter::new()),
+            Self::Technic => Arc::new(TechnicImporter::new()),
+            Self::FTB => Arc::new(FTBImporter::new()),
+            Self::MinecraftLauncher => Arc::new(MinecraftLauncherImporter::new()),
+            Self::Packwiz => Arc::new(PackwizImporter::new()),
+            Self::GDLPack => Arc::new(GDLPackImporter::new()),
+            _ => anyhow::bail!("importer not implemented for {self:?}"),
+        })
     }
 
     pub async fn get_default_scan_path(self) -> anyhow::Result<Option<PathBuf>> {
@@ -225,6 +276,13 @@ impl Entity {
                 Some(LegacyGDLauncherImporter::get_default_scan_path().await?)
             }
             Self::CurseForge => Some(CurseforgeImporter::get_default_scan_path().await?),
+            Self::PrismLauncher => Some(MultiMCImporter::get_prism_default_scan_path().await?),
+            Self::ATLauncher => Some(ATLauncherImporter::get_default_scan_path().await?),
+            Self::Technic => Some(TechnicImporter::get_default_scan_path().await?),
+            Self::FTB => Some(FTBImporter::get_default_scan_path().await?),
+            Self::MinecraftLauncher => {
+                Some(MinecraftLauncherImporter::get_default_scan_path().await?)
+            }
             _ => None,
         })
     }
@@ -271,6 +329,41 @@ pub trait InstanceImporter: std::fmt::Debug + Send + Sync {
     ) -> anyhow::Result<VisualTaskId>;
 }
 
+/// Per-instance game settings carried over from another launcher.
+/// Applied after the instance has been created, unset fields keep their defaults.
+#[derive(Debug, Clone, Default)]
+struct ImportedGameSettings {
+    extra_java_args: Option<String>,
+    memory: Option<(u16, u16)>,
+    java_override: Option<JavaOverride>,
+    game_resolution: Option<GameResolution>,
+}
+
+impl ImportedGameSettings {
+    async fn apply(self, app: &Arc<AppInner>, instance_id: InstanceId) -> anyhow::Result<()> {
+        app.instance_manager()
+            .update_instance(InstanceSettingsUpdate {
+                instance_id,
+                name: None,
+                use_loaded_icon: None,
+                notes: None,
+                version: None,
+                modloader: None,
+                java_override: self.java_override.map(Some),
+                global_java_args: None,
+                extra_java_args: self.extra_java_args.map(Some),
+                memory: self.memory.map(Some),
+                pre_launch_hook: None,
+                post_exit_hook: None,
+                wrapper_command: None,
+                game_resolution: self.game_resolution.map(Some),
+                mod_sources: None,
+                modpack_locked: None,
+            })
+            .await
+    }
+}
+
 #[derive(Debug, Clone)]
 enum ImporterState<T: Clone + Into<ImportableInstance>> {
     NoResults,
@@ -0,0 +1,468 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::trace;
+
+use crate::{
+    api::keys::instance::*,
+    api::translation::Translation,
+    domain::{
+        instance::info::{GameVersion, JavaOverride},
+        modplatforms::modrinth::version::ModrinthPackDependencies,
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::InstanceVersionSource,
+        modplatforms::modrinth::convert_mr_version_to_standard_version, AppInner,
+    },
+};
+
+use super::{
+    ImportScanStatus, ImportableInstance, ImportedGameSettings, ImporterState, InstanceImporter,
+    InternalImportEntry, InvalidImportEntry,
+};
+
+#[derive(Debug, Clone)]
+struct Importable {
+    filename: String,
+    path: PathBuf,
+    config: InstanceCfg,
+    dependencies: ModrinthPackDependencies,
+}
+
+impl From<Importable> for ImportableInstance {
+    fn from(value: Importable) -> Self {
+        Self {
+            instance_name: value.config.name.unwrap_or_else(|| value.filename.clone()),
+            filename: value.filename,
+        }
+    }
+}
+
+/// Importer for MultiMC and its forks (Prism Launcher), which share the
+/// same `instance.cfg` + `mmc-pack.json` instance layout.
+#[derive(Debug)]
+pub struct MultiMCImporter {
+    state: RwLock<ImporterState<Importable>>,
+}
+
+impl MultiMCImporter {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(ImporterState::NoResults),
+        }
+    }
+
+    pub async fn get_prism_default_scan_path() -> anyhow::Result<PathBuf> {
+        let basedirs = directories::BaseDirs::new().ok_or(anyhow!("Cannot build basedirs"))?;
+
+        Ok(basedirs.data_dir().join("PrismLauncher").join("instances"))
+    }
+
+    async fn scan_instance(
+        &self,
+        path: PathBuf,
+    ) -> anyhow::Result<Option<InternalImportEntry<Importable>>> {
+        let config = path.join("instance.cfg");
+        if !config.is_file() {
+            return Ok(None);
+        }
+
+        let filename = path
+            .file_name()
+            .expect("filename cannot be empty")
+            .to_string_lossy()
+            .to_string();
+
+        let config = InstanceCfg::parse(&tokio::fs::read_to_string(config).await?);
+
+        let Ok(pack) = tokio::fs::read_to_string(path.join("mmc-pack.json")).await else {
+            return Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason: Translation::InstanceImportMmcMissingPack,
+            })));
+        };
+
+        let dependencies = serde_json::from_str::<MmcPack>(&pack)
+            .ok()
+            .and_then(|pack| pack.to_dependencies());
+
+        match dependencies {
+            Some(dependencies) => Ok(Some(InternalImportEntry::Valid(Importable {
+                filename,
+                path,
+                config,
+                dependencies,
+            }))),
+            None => Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason: Translation::InstanceImportMmcMalformedPack,
+            }))),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl InstanceImporter for MultiMCImporter {
+    async fn scan(&self, app: &Arc<AppInner>, scan_path: PathBuf) -> anyhow::Result<()> {
+        if scan_path.join("instance.cfg").is_file() {
+            if let Ok(Some(entry)) = self.scan_instance(scan_path).await {
+                self.state.write().await.set_single(entry).await;
+                app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+            }
+        } else if scan_path.is_dir() {
+            let Ok(mut dir) = tokio::fs::read_dir(&scan_path).await else {
+                return Ok(());
+            };
+
+            while let Some(path) = dir.next_entry().await? {
+                if path.metadata().await?.is_dir() {
+                    if let Ok(Some(entry)) = self.scan_instance(path.path()).await {
+                        self.state.write().await.push_multi(entry).await;
+                        app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_status(&self) -> ImportScanStatus {
+        self.state.read().await.clone().into()
+    }
+
+    async fn begin_import(
+        &self,
+        app: &Arc<AppInner>,
+        index: u32,
+        name: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        trace!(?index, ?name, "Beginning multimc import");
+
+        let instance = self
+            .state
+            .read()
+            .await
+            .get(index)
+            .await
+            .cloned()
+            .ok_or_else(|| anyhow!("invalid importable instance index {index}"))?;
+
+        let standard_version =
+            convert_mr_version_to_standard_version(app.clone(), instance.dependencies.clone())
+                .await?;
+
+        let instance_version_source =
+            InstanceVersionSource::Version(GameVersion::Standard(standard_version));
+
+        let icon = match instance.find_icon().await {
+            Some(icon) => app.instance_manager().load_icon(icon).await.ok(),
+            None => None,
+        };
+
+        let last_played = instance
+            .config
+            .last_launch_time
+            .and_then(DateTime::<Utc>::from_timestamp_millis);
+
+        let initializer = |instance_path: PathBuf| {
+            let instance = &instance;
+            async move {
+                let path = instance_path.join("instance");
+
+                let Some(game_dir) = instance.game_dir() else {
+                    return Ok(());
+                };
+
+                trace!("Copying files from multimc instance");
+                crate::domain::runtime_path::copy_dir_filter(&game_dir, &path, |_| true).await?;
+
+                Ok(())
+            }
+        };
+
+        let id = app
+            .instance_manager()
+            .create_instance_ext(
+                app.instance_manager().get_default_group().await?,
+                name.unwrap_or_else(|| {
+                    instance
+                        .config
+                        .name
+                        .clone()
+                        .unwrap_or_else(|| instance.filename.clone())
+                }),
+                icon,
+                instance.config.total_time_played,
+                last_played,
+                instance_version_source,
+                instance.config.notes.clone().unwrap_or_default(),
+                initializer,
+            )
+            .await?;
+
+        instance.config.game_settings().apply(app, id).await?;
+
+        app.instance_manager()
+            .prepare_game(id, None, None, true)
+            .await
+            .map(|r| r.1)
+    }
+}
+
+impl Importable {
+    /// MultiMC used `minecraft` as the game directory, newer versions and
+    /// Prism Launcher use `.minecraft`.
+    fn game_dir(&self) -> Option<PathBuf> {
+        [".minecraft", "minecraft"]
+            .into_iter()
+            .map(|dir| self.path.join(dir))
+            .find(|dir| dir.is_dir())
+    }
+
+    /// Custom icons are stored in the launcher-wide `icons` folder next to `instances`.
+    async fn find_icon(&self) -> Option<PathBuf> {
+        let icon_key = self.config.icon_key.as_ref()?;
+        let icons_dir = self.path.parent()?.parent()?.join("icons");
+
+        let mut dir = tokio::fs::read_dir(icons_dir).await.ok()?;
+        while let Ok(Some(entry)) = dir.next_entry().await {
+            let path = entry.path();
+            if path.file_stem().and_then(|stem| stem.to_str()) == Some(icon_key) {
+                return Some(path);
+            }
+        }
+
+        None
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct InstanceCfg {
+    name: Option<String>,
+    icon_key: Option<String>,
+    notes: Option<String>,
+    total_time_played: Option<u32>,
+    last_launch_time: Option<i64>,
+    jvm_args: Option<String>,
+    memory: Option<(u16, u16)>,
+    java_path: Option<String>,
+}
+
+impl InstanceCfg {
+    /// Parses the Qt INI formatted `instance.cfg`.
+    /// Unknown keys are ignored, as are settings that are not overridden for the instance.
+    fn parse(text: &str) -> Self {
+        let values = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with(['[', '#', ';']))
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim(), unescape_ini_value(value.trim())))
+            .collect::<HashMap<_, _>>();
+
+        let get = |key: &str| values.get(key).filter(|v| !v.is_empty()).cloned();
+        let enabled = |key: &str| values.get(key).map(|v| v == "true").unwrap_or(false);
+
+        let memory = match enabled("OverrideMemory") {
+            true => get("MinMemAlloc")
+                .and_then(|min| min.parse::<u16>().ok())
+                .zip(get("MaxMemAlloc").and_then(|max| max.parse::<u16>().ok())),
+            false => None,
+        };
+
+        Self {
+            name: get("name"),
+            icon_key: get("iconKey").filter(|key| key != "default"),
+            notes: get("notes"),
+            total_time_played: get("totalTimePlayed").and_then(|t| t.parse().ok()),
+            last_launch_time: get("lastLaunchTime").and_then(|t| t.parse().ok()),
+            jvm_args: get("JvmArgs").filter(|_| enabled("OverrideJavaArgs")),
+            memory,
+            java_path: get("JavaPath").filter(|_| enabled("OverrideJavaLocation")),
+        }
+    }
+
+    fn game_settings(&self) -> ImportedGameSettings {
+        ImportedGameSettings {
+            extra_java_args: self.jvm_args.clone(),
+            memory: self.memory,
+            java_override: self
+                .java_path
+                .clone()
+                .map(|path| JavaOverride::Path(Some(path))),
+            game_resolution: None,
+        }
+    }
+}
+
+/// QSettings quotes values containing special characters and escapes them C-style.
+fn unescape_ini_value(value: &str) -> String {
+    let Some(value) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    else {
+        return value.to_string();
+    };
+
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some(c) => result.push(c),
+            None => {}
+        }
+    }
+
+    result
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MmcPack {
+    components: Vec<MmcComponent>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MmcComponent {
+    uid: String,
+    version: Option<String>,
+}
+
+impl MmcPack {
+    /// Maps the pack components onto the modrinth dependency format so the loader versions
+    /// can be resolved the same way as for mrpacks. Returns `None` if there is no minecraft component.
+    fn to_dependencies(&self) -> Option<ModrinthPackDependencies> {
+        let version = |uid: &str| {
+            self.components
+                .iter()
+                .find(|component| component.uid == uid)
+                .and_then(|component| component.version.clone())
+        };
+
+        Some(ModrinthPackDependencies {
+            minecraft: Some(version("net.minecraft")?),
+            forge: version("net.minecraftforge"),
+            neoforge: version("net.neoforged"),
+            fabric_loader: version("net.fabricmc.fabric-loader"),
+            quilt_loader: version("org.quiltmc.quilt-loader"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{InstanceCfg, MmcPack};
+
+    fn parse_pack(text: &str) -> MmcPack {
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_pack_components_forge() {
+        let pack = parse_pack(include_str!("test_fixtures/multimc/forge-mmc-pack.json"));
+        let dependencies = pack.to_dependencies().unwrap();
+
+        assert_eq!(dependencies.minecraft.as_deref(), Some("1.16.5"));
+        assert_eq!(dependencies.forge.as_deref(), Some("36.2.34"));
+        assert_eq!(dependencies.neoforge, None);
+        assert_eq!(dependencies.fabric_loader, None);
+        assert_eq!(dependencies.quilt_loader, None);
+    }
+
+    #[test]
+    fn test_pack_components_neoforge() {
+        let pack = parse_pack(include_str!("test_fixtures/multimc/neoforge-mmc-pack.json"));
+        let dependencies = pack.to_dependencies().unwrap();
+
+        assert_eq!(dependencies.minecraft.as_deref(), Some("1.20.4"));
+        assert_eq!(dependencies.neoforge.as_deref(), Some("20.4.237"));
+        assert_eq!(dependencies.forge, None);
+    }
+
+    #[test]
+    fn test_pack_components_fabric() {
+        let pack = parse_pack(include_str!("test_fixtures/multimc/fabric-mmc-pack.json"));
+        let dependencies = pack.to_dependencies().unwrap();
+
+        // intermediary mappings and lwjgl are implied by the loader and must be ignored
+        assert_eq!(dependencies.minecraft.as_deref(), Some("1.20.1"));
+        assert_eq!(dependencies.fabric_loader.as_deref(), Some("0.15.11"));
+        assert_eq!(dependencies.quilt_loader, None);
+        assert_eq!(dependencies.forge, None);
+    }
+
+    #[test]
+    fn test_pack_components_quilt() {
+        let pack = parse_pack(
+            r#"{
+                "components": [
+                    { "uid": "net.minecraft", "version": "1.19.2" },
+                    { "uid": "org.quiltmc.quilt-loader", "version": "0.19.1" }
+                ],
+                "formatVersion": 1
+            }"#,
+        );
+        let dependencies = pack.to_dependencies().unwrap();
+
+        assert_eq!(dependencies.minecraft.as_deref(), Some("1.19.2"));
+        assert_eq!(dependencies.quilt_loader.as_deref(), Some("0.19.1"));
+    }
+
+    #[test]
+    fn test_pack_components_missing_minecraft() {
+        let pack = parse_pack(
+            r#"{
+                "components": [
+                    { "uid": "net.fabricmc.fabric-loader", "version": "0.15.11" }
+                ],
+                "formatVersion": 1
+            }"#,
+        );
+
+        assert!(pack.to_dependencies().is_none());
+    }
+
+    #[test]
+    fn test_parse_instance_cfg() {
+        let config = InstanceCfg::parse(include_str!("test_fixtures/multimc/instance.cfg"));
+
+        assert_eq!(
+            config,
+            InstanceCfg {
+                name: Some(String::from("All The Mods")),
+                icon_key: Some(String::from("atm")),
+                notes: Some(String::from("first line\nsecond \"quoted\" line")),
+                total_time_played: Some(7260),
+                last_launch_time: Some(1700000000000),
+                jvm_args: Some(String::from("-XX:+UseG1GC -Dfml.readTimeout=180")),
+                memory: Some((2048, 8192)),
+                java_path: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_instance_cfg_without_overrides() {
+        let config = InstanceCfg::parse(
+            "[General]\nname=Vanilla\niconKey=default\nJvmArgs=-Xss4M\nOverrideJavaArgs=false\nMinMemAlloc=512\nMaxMemAlloc=1024\n",
+        );
+
+        assert_eq!(config.name.as_deref(), Some("Vanilla"));
+        assert_eq!(config.icon_key, None);
+        assert_eq!(config.jvm_args, None);
+        assert_eq!(config.memory, None);
+    }
+}
@@ -0,0 +1,577 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, bail, Context};
+use carbon_net::{Checksum, DownloadOptions, Downloadable};
+use tokio::sync::RwLock;
+use tracing::{trace, warn};
+
+use crate::{
+    api::keys::instance::*,
+    api::translation::Translation,
+    domain::{
+        instance::info::GameVersion,
+        modplatforms::{
+            curseforge::filters::ModFileParameters,
+            modrinth::version::ModrinthPackDependencies,
+            packwiz::{HashFormat, Index, MetaFile, Pack, Side},
+        },
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::{
+            installer::{CurseforgeModInstaller, IntoInstaller, ModrinthModInstaller},
+            InstanceVersionSource,
+        },
+        modplatforms::modrinth::convert_mr_version_to_standard_version,
+        AppInner,
+    },
+    util::is_safe_relative_path,
+};
+
+use super::{
+    ImportScanStatus, ImportableInstance, ImporterState, InstanceImporter, InternalImportEntry,
+    InvalidImportEntry,
+};
+
+#[derive(Debug, Clone)]
+struct Importable {
+    filename: String,
+    root: PathBuf,
+    pack: Pack,
+    dependencies: ModrinthPackDependencies,
+    files: Vec<PackFile>,
+}
+
+impl From<Importable> for ImportableInstance {
+    fn from(value: Importable) -> Self {
+        Self {
+            filename: value.filename,
+            instance_name: value.pack.name,
+        }
+    }
+}
+
+/// A file of the pack, with its path relative to the instance root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PackFile {
+    path: String,
+    source: PackFileSource,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PackFileSource {
+    /// copied from the pack directory, `path` relative to the index
+    Local(String),
+    Curseforge {
+        project_id: u32,
+        file_id: u32,
+        checksum: Option<Checksum>,
+    },
+    Modrinth {
+        project_id: String,
+        version_id: String,
+    },
+    Url {
+        url: String,
+        checksum: Option<Checksum>,
+    },
+}
+
+#[derive(Debug)]
+pub struct PackwizImporter {
+    state: RwLock<ImporterState<Importable>>,
+}
+
+impl PackwizImporter {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(ImporterState::NoResults),
+        }
+    }
+
+    async fn scan_pack(
+        &self,
+        pack_toml: PathBuf,
+    ) -> anyhow::Result<Option<InternalImportEntry<Importable>>> {
+        if !pack_toml.is_file() {
+            return Ok(None);
+        }
+
+        let root = pack_toml
+            .parent()
+            .ok_or_else(|| anyhow!("pack.toml has no parent directory"))?
+            .to_path_buf();
+
+        let filename = root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("pack.toml"));
+
+        let Ok((pack, files)) = read_pack(&pack_toml).await else {
+            return Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason: Translation::InstanceImportPackwizMalformedPack,
+            })));
+        };
+
+        let Some(dependencies) = pack_dependencies(&pack) else {
+            return Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason: Translation::InstanceImportPackwizUnsupportedLoader,
+            })));
+        };
+
+        Ok(Some(InternalImportEntry::Valid(Importable {
+            filename,
+            root,
+            pack,
+            dependencies,
+            files,
+        })))
+    }
+}
+
+#[async_trait::async_trait]
+impl InstanceImporter for PackwizImporter {
+    async fn scan(&self, app: &Arc<AppInner>, scan_path: PathBuf) -> anyhow::Result<()> {
+        let pack_toml = match scan_path.is_dir() {
+            true => scan_path.join("pack.toml"),
+            false => scan_path,
+        };
+
+        if let Ok(Some(entry)) = self.scan_pack(pack_toml).await {
+            self.state.write().await.set_single(entry).await;
+            app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+        }
+
+        Ok(())
+    }
+
+    async fn get_status(&self) -> ImportScanStatus {
+        self.state.read().await.clone().into()
+    }
+
+    async fn begin_import(
+        &self,
+        app: &Arc<AppInner>,
+        index: u32,
+        name: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        trace!(?index, ?name, "Beginning packwiz import");
+
+        let instance = self
+            .state
+            .read()
+            .await
+            .get(index)
+            .await
+            .cloned()
+            .ok_or_else(|| anyhow!("invalid importable instance index {index}"))?;
+
+        let standard_version =
+            convert_mr_version_to_standard_version(app.clone(), instance.dependencies.clone())
+                .await?;
+
+        let instance_version_source =
+            InstanceVersionSource::Version(GameVersion::Standard(standard_version));
+
+        let mut mod_installers = Vec::new();
+        let mut files = Vec::new();
+
+        for file in &instance.files {
+            match &file.source {
+                PackFileSource::Curseforge { .. } | PackFileSource::Modrinth { .. }
+                    if is_mod(&file.path) =>
+                {
+                    mod_installers.push(file.source.clone())
+                }
+                PackFileSource::Curseforge {
+                    project_id,
+                    file_id,
+                    checksum,
+                } => {
+                    // the mod installers always target the mods folder, resolve anything else directly
+                    let cf_file = app
+                        .modplatforms_manager()
+                        .curseforge
+                        .get_mod_file(ModFileParameters {
+                            mod_id: *project_id as i32,
+                            file_id: *file_id as i32,
+                        })
+                        .await?
+                        .data;
+
+                    let Some(url) = cf_file.download_url else {
+                        warn!(path = ?file.path, "Skipping curseforge file without download url");
+                        continue;
+                    };
+
+                    files.push(PackFile {
+                        path: file.path.clone(),
+                        source: PackFileSource::Url {
+                            url,
+                            checksum: checksum.clone(),
+                        },
+                    });
+                }
+                PackFileSource::Modrinth { .. } => {
+                    warn!(path = ?file.path, "Skipping modrinth file outside of mods");
+                }
+                _ => files.push(file.clone()),
+            }
+        }
+
+        let initializer = |instance_path: PathBuf| {
+            let instance = &instance;
+            let files = &files;
+            async move {
+                let path = instance_path.join("instance");
+                let mut downloads = Vec::new();
+
+                trace!("Copying files from packwiz pack");
+                for file in files {
+                    let target = path.join(&file.path);
+
+                    match &file.source {
+                        PackFileSource::Local(source) => {
+                            tokio::fs::create_dir_all(target.parent().unwrap()).await?;
+                            tokio::fs::copy(instance.root.join(source), target).await?;
+                        }
+                        PackFileSource::Url { url, checksum } => downloads
+                            .push(Downloadable::new(url, target).with_checksum(checksum.clone())),
+                        _ => {}
+                    }
+                }
+
+                carbon_net::download_multiple(&downloads, DownloadOptions::default()).await?;
+
+                Ok(())
+            }
+        };
+
+        let id = app
+            .instance_manager()
+            .create_instance_ext(
+                app.instance_manager().get_default_group().await?,
+                name.unwrap_or_else(|| instance.pack.name.clone()),
+                None,
+                None,
+                None,
+                instance_version_source,
+                instance.pack.description.clone().unwrap_or_default(),
+                initializer,
+            )
+            .await?;
+
+        for source in mod_installers {
+            let installer = match source.clone() {
+                PackFileSource::Curseforge {
+                    project_id,
+                    file_id,
+                    ..
+                } => CurseforgeModInstaller::create(app, project_id, file_id)
+                    .await
+                    .map(IntoInstaller::into_installer),
+                PackFileSource::Modrinth {
+                    project_id,
+                    version_id,
+                } => ModrinthModInstaller::create(app, project_id, version_id)
+                    .await
+                    .map(IntoInstaller::into_installer),
+                _ => unreachable!(),
+            };
+
+            installer?
+                .install(app, id, false, None)
+                .await
+                .with_context(|| format!("Failed to install packwiz mod {source:?}"))?;
+        }
+
+        app.instance_manager()
+            .prepare_game(id, None, None, true)
+            .await
+            .map(|r| r.1)
+    }
+}
+
+fn is_mod(path: &str) -> bool {
+    path.starts_with("mods/")
+}
+
+/// Reads `pack.toml`, its index and every metafile the index references.
+async fn read_pack(pack_toml: &Path) -> anyhow::Result<(Pack, Vec<PackFile>)> {
+    let root = pack_toml.parent().expect("pack.toml is a file");
+
+    let pack = toml::from_str::<Pack>(&tokio::fs::read_to_string(pack_toml).await?)?;
+
+    if !is_safe_relative_path(&pack.index.file) {
+        bail!("Invalid index path {}", pack.index.file);
+    }
+
+    let index_path = root.join(&pack.index.file);
+    let index_root = index_path.parent().unwrap_or(root);
+    let index = toml::from_str::<Index>(&tokio::fs::read_to_string(&index_path).await?)?;
+
+    for file in &index.files {
+        if !is_safe_relative_path(&file.file) {
+            bail!("Invalid index file path {}", file.file);
+        }
+    }
+
+    let mut metafiles = HashMap::new();
+    for file in index.files.iter().filter(|file| file.metafile) {
+        let metafile = tokio::fs::read_to_string(index_root.join(&file.file)).await?;
+        metafiles.insert(file.file.clone(), toml::from_str::<MetaFile>(&metafile)?);
+    }
+
+    let index_prefix = index_root
+        .strip_prefix(root)
+        .unwrap_or(Path::new(""))
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    let files = resolve_files(&index, &metafiles)
+        .into_iter()
+        .map(|mut file| {
+            // index paths are relative to the index, locate local files from the pack root
+            if let PackFileSource::Local(source) = &mut file.source {
+                if !index_prefix.is_empty() {
+                    *source = format!("{index_prefix}/{source}");
+                }
+            }
+            file
+        })
+        .collect::<Vec<_>>();
+
+    // aliases and metafile names come from the pack as well
+    if let Some(file) = files.iter().find(|file| !is_safe_relative_path(&file.path)) {
+        bail!("Invalid file path {}", file.path);
+    }
+
+    Ok((pack, files))
+}
+
+/// Returns `None` if the pack uses a loader that is not supported.
+fn pack_dependencies(pack: &Pack) -> Option<ModrinthPackDependencies> {
+    if pack.versions.liteloader.is_some() {
+        return None;
+    }
+
+    Some(ModrinthPackDependencies {
+        minecraft: Some(pack.versions.minecraft.clone()),
+        forge: pack.versions.forge.clone(),
+        neoforge: pack.versions.neoforge.clone(),
+        fabric_loader: pack.versions.fabric.clone(),
+        quilt_loader: pack.versions.quilt.clone(),
+    })
+}
+
+/// Turns the index into the list of files to place in the instance.
+/// Server only and optional (not enabled by default) metafiles are skipped.
+fn resolve_files(index: &Index, metafiles: &HashMap<String, MetaFile>) -> Vec<PackFile> {
+    index
+        .files
+        .iter()
+        .filter_map(|file| {
+            let target = file.alias.as_ref().unwrap_or(&file.file);
+
+            if !file.metafile {
+                return Some(PackFile {
+                    path: target.clone(),
+                    source: PackFileSource::Local(file.file.clone()),
+                });
+            }
+
+            let metafile = metafiles.get(&file.file)?;
+
+            if metafile.side == Side::Server {
+                return None;
+            }
+
+            if let Some(option) = &metafile.option {
+                if option.optional && !option.default {
+                    return None;
+                }
+            }
+
+            let path = match target.rsplit_once('/') {
+                Some((dir, _)) => format!("{dir}/{}", metafile.filename),
+                None => metafile.filename.clone(),
+            };
+
+            let file_checksum = checksum(metafile.download.hash_format, &metafile.download.hash);
+
+            let url_source = metafile
+                .download
+                .url
+                .clone()
+                .map(|url| PackFileSource::Url {
+                    url,
+                    checksum: file_checksum.clone(),
+                });
+
+            let curseforge_source =
+                metafile
+                    .update
+                    .curseforge
+                    .as_ref()
+                    .map(|update| PackFileSource::Curseforge {
+                        project_id: update.project_id,
+                        file_id: update.file_id,
+                        checksum: file_checksum.clone(),
+                    });
+
+            let modrinth_source =
+                metafile
+                    .update
+                    .modrinth
+                    .as_ref()
+                    .map(|update| PackFileSource::Modrinth {
+                        project_id: update.mod_id.clone(),
+                        version_id: update.version.clone(),
+                    });
+
+            // mods go through the platform installers so they are tracked,
+            // anything else is downloaded directly when possible
+            let source = match is_mod(&path) {
+                true => curseforge_source.or(modrinth_source).or(url_source),
+                false => url_source.or(curseforge_source),
+            }?;
+
+            Some(PackFile { path, source })
+        })
+        .collect()
+}
+
+fn checksum(format: HashFormat, hash: &str) -> Option<Checksum> {
+    match format {
+        HashFormat::Sha1 => Some(Checksum::Sha1(hash.to_string())),
+        HashFormat::Sha256 => Some(Checksum::Sha256(hash.to_string())),
+        HashFormat::Sha512 => Some(Checksum::Sha512(hash.to_string())),
+        HashFormat::Md5 => Some(Checksum::Md5(hash.to_string())),
+        // packwiz stores the murmur2 fingerprint as a decimal number
+        HashFormat::Murmur2 => hash.parse().ok().map(Checksum::Murmur2),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use carbon_net::Checksum;
+
+    use crate::domain::modplatforms::packwiz::{HashFormat, Index, MetaFile, Pack};
+
+    use super::{checksum, pack_dependencies, resolve_files, PackFile, PackFileSource};
+
+    #[test]
+    fn test_resolve_pack() {
+        let pack = toml::from_str::<Pack>(include_str!("test_fixtures/packwiz/pack.toml")).unwrap();
+        let dependencies = pack_dependencies(&pack).unwrap();
+
+        assert_eq!(dependencies.minecraft.as_deref(), Some("1.20.1"));
+        assert_eq!(dependencies.fabric_loader.as_deref(), Some("0.15.11"));
+        assert_eq!(pack.index.file, "index.toml");
+
+        let index =
+            toml::from_str::<Index>(include_str!("test_fixtures/packwiz/index.toml")).unwrap();
+
+        let metafiles = HashMap::from([
+            (
+                String::from("mods/sodium.pw.toml"),
+                include_str!("test_fixtures/packwiz/sodium.pw.toml"),
+            ),
+            (
+                String::from("mods/jei.pw.toml"),
+                include_str!("test_fixtures/packwiz/jei.pw.toml"),
+            ),
+            (
+                String::from("mods/spark.pw.toml"),
+                include_str!("test_fixtures/packwiz/spark.pw.toml"),
+            ),
+            (
+                String::from("mods/optional-shader.pw.toml"),
+                include_str!("test_fixtures/packwiz/optional-shader.pw.toml"),
+            ),
+            (
+                String::from("resourcepacks/faithful.pw.toml"),
+                include_str!("test_fixtures/packwiz/faithful.pw.toml"),
+            ),
+        ])
+        .into_iter()
+        .map(|(path, text)| (path, toml::from_str::<MetaFile>(text).unwrap()))
+        .collect::<HashMap<_, _>>();
+
+        let files = resolve_files(&index, &metafiles);
+
+        assert_eq!(
+            files,
+            vec![
+                PackFile {
+                    path: String::from("config/sodium-options.json"),
+                    source: PackFileSource::Local(String::from("config/sodium-options.json")),
+                },
+                PackFile {
+                    path: String::from("mods/jei-1.20.1-fabric-15.3.0.4.jar"),
+                    source: PackFileSource::Curseforge {
+                        project_id: 238222,
+                        file_id: 5101366,
+                        checksum: Some(Checksum::Sha1(String::from(
+                            "b1f5c6a2d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2"
+                        ))),
+                    },
+                },
+                PackFile {
+                    path: String::from("mods/sodium-fabric-0.5.8+mc1.20.1.jar"),
+                    source: PackFileSource::Modrinth {
+                        project_id: String::from("AANobbMI"),
+                        version_id: String::from("OihdIimA"),
+                    },
+                },
+                PackFile {
+                    path: String::from("resourcepacks/Faithful 32x - 1.20.1.zip"),
+                    source: PackFileSource::Url {
+                        url: String::from(
+                            "https://cdn.modrinth.com/data/9fbpRfKK/versions/OqBvyfEh/Faithful%2032x%20-%201.20.1.zip"
+                        ),
+                        checksum: Some(carbon_net::Checksum::Sha1(String::from(
+                            "8d3d7a8c8e1b0f7a2e8a9d2c3e4f5a6b7c8d9e0f"
+                        ))),
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_checksum_formats() {
+        assert_eq!(
+            checksum(HashFormat::Sha512, "ab12"),
+            Some(Checksum::Sha512(String::from("ab12")))
+        );
+        assert_eq!(
+            checksum(HashFormat::Murmur2, "1961219979"),
+            Some(Checksum::Murmur2(1961219979))
+        );
+        assert_eq!(checksum(HashFormat::Murmur2, "not a number"), None);
+    }
+
+    #[tokio::test]
+    async fn test_reject_unsafe_paths() {
+        let dir = tempdir::TempDir::new("packwiz_test").unwrap();
+        let pack_toml = dir.path().join("pack.toml");
+
+        std::fs::write(&pack_toml, include_str!("test_fixtures/packwiz/pack.toml")).unwrap();
+        std::fs::write(
+            dir.path().join("index.toml"),
+            "hash-format = \"sha256\"\n\n[[files]]\nfile = \"../outside.txt\"\nhash = \"00\"\n",
+        )
+        .unwrap();
+
+        assert!(super::read_pack(&pack_toml).await.is_err());
+    }
+}
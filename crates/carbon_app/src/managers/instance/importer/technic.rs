@@ -0,0 +1,419 @@
+use std::{
+    io::{Read, Seek},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::trace;
+
+use crate::{
+    api::keys::instance::*,
+    api::translation::Translation,
+    domain::{
+        instance::info::GameVersion, modplatforms::modrinth::version::ModrinthPackDependencies,
+        vtask::VisualTaskId,
+    },
+    managers::{
+        instance::InstanceVersionSource,
+        modplatforms::modrinth::convert_mr_version_to_standard_version, AppInner,
+    },
+};
+
+use super::{
+    ImportScanStatus, ImportableInstance, ImporterState, InstanceImporter, InternalImportEntry,
+    InvalidImportEntry,
+};
+
+#[derive(Debug, Clone)]
+struct Importable {
+    filename: String,
+    path: PathBuf,
+    dependencies: ModrinthPackDependencies,
+}
+
+impl From<Importable> for ImportableInstance {
+    fn from(value: Importable) -> Self {
+        Self {
+            instance_name: value.filename.clone(),
+            filename: value.filename,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TechnicImporter {
+    state: RwLock<ImporterState<Importable>>,
+}
+
+impl TechnicImporter {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(ImporterState::NoResults),
+        }
+    }
+
+    pub async fn get_default_scan_path() -> anyhow::Result<PathBuf> {
+        let basedirs = directories::BaseDirs::new().ok_or(anyhow!("Cannot build basedirs"))?;
+
+        #[cfg(target_os = "linux")]
+        let p = basedirs.home_dir().join(".technic");
+        #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+        let p = basedirs.data_dir().join(".technic");
+        #[cfg(target_os = "macos")]
+        let p = basedirs.data_dir().join("technic");
+
+        Ok(p.join("modpacks"))
+    }
+
+    async fn scan_instance(
+        &self,
+        path: PathBuf,
+    ) -> anyhow::Result<Option<InternalImportEntry<Importable>>> {
+        let bin = path.join("bin");
+        if !bin.is_dir() {
+            return Ok(None);
+        }
+
+        let filename = path
+            .file_name()
+            .expect("filename cannot be empty")
+            .to_string_lossy()
+            .to_string();
+
+        let version_json = bin.join("version.json");
+        let modpack_jar = bin.join("modpack.jar");
+
+        let version = if version_json.is_file() {
+            Some(tokio::fs::read(version_json).await?)
+        } else if modpack_jar.is_file() {
+            tokio::task::spawn_blocking(move || read_jar_version(std::fs::File::open(modpack_jar)?))
+                .await??
+        } else {
+            None
+        };
+
+        let Some(version) = version else {
+            return Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason: Translation::InstanceImportTechnicMissingVersion,
+            })));
+        };
+
+        let dependencies = serde_json::from_slice::<TechnicVersion>(&version)
+            .ok()
+            .map(|version| version.to_dependencies());
+
+        match dependencies {
+            Some(dependencies) => Ok(Some(InternalImportEntry::Valid(Importable {
+                filename,
+                path,
+                dependencies,
+            }))),
+            None => Ok(Some(InternalImportEntry::Invalid(InvalidImportEntry {
+                name: filename,
+                reason: Translation::InstanceImportTechnicMalformedVersion,
+            }))),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl InstanceImporter for TechnicImporter {
+    async fn scan(&self, app: &Arc<AppInner>, scan_path: PathBuf) -> anyhow::Result<()> {
+        // accept both the technic root and its `modpacks` folder
+        let scan_path = match scan_path.join("modpacks").is_dir() {
+            true => scan_path.join("modpacks"),
+            false => scan_path,
+        };
+
+        if scan_path.join("bin").is_dir() {
+            if let Ok(Some(entry)) = self.scan_instance(scan_path).await {
+                self.state.write().await.set_single(entry).await;
+                app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+            }
+        } else if scan_path.is_dir() {
+            let Ok(mut dir) = tokio::fs::read_dir(&scan_path).await else {
+                return Ok(());
+            };
+
+            while let Some(path) = dir.next_entry().await? {
+                if path.metadata().await?.is_dir() {
+                    if let Ok(Some(entry)) = self.scan_instance(path.path()).await {
+                        self.state.write().await.push_multi(entry).await;
+                        app.invalidate(GET_IMPORT_SCAN_STATUS, None);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_status(&self) -> ImportScanStatus {
+        self.state.read().await.clone().into()
+    }
+
+    async fn begin_import(
+        &self,
+        app: &Arc<AppInner>,
+        index: u32,
+        name: Option<String>,
+    ) -> anyhow::Result<VisualTaskId> {
+        trace!(?index, ?name, "Beginning technic import");
+
+        let instance = self
+            .state
+            .read()
+            .await
+            .get(index)
+            .await
+            .cloned()
+            .ok_or_else(|| anyhow!("invalid importable instance index {index}"))?;
+
+        let standard_version =
+            convert_mr_version_to_standard_version(app.clone(), instance.dependencies.clone())
+                .await?;
+
+        let instance_version_source =
+            InstanceVersionSource::Version(GameVersion::Standard(standard_version));
+
+        let icon = match instance.find_icon() {
+            Some(icon) => app.instance_manager().load_icon(icon).await.ok(),
+            None => None,
+        };
+
+        let initializer = |instance_path: PathBuf| {
+            let instance = &instance;
+            async move {
+                let path = instance_path.join("instance");
+
+                // `bin` holds the minecraft / forge jars which are replaced by the modloader entry
+                trace!("Copying files from technic instance");
+                crate::domain::runtime_path::copy_dir_pruned(&instance.path, &path, |path| {
+                    !matches!(path.to_str(), Some("bin" | "logs" | "crash-reports"))
+                })
+                .await?;
+
+                Ok(())
+            }
+        };
+
+        let id = app
+            .instance_manager()
+            .create_instance_ext(
+                app.instance_manager().get_default_group().await?,
+                name.unwrap_or_else(|| instance.filename.clone()),
+                icon,
+                None,
+                None,
+                instance_version_source,
+                String::new(),
+                initializer,
+            )
+            .await?;
+
+        app.instance_manager()
+            .prepare_game(id, None, None, true)
+            .await
+            .map(|r| r.1)
+    }
+}
+
+impl Importable {
+    /// Pack icons are cached in `assets/packs/<slug>` next to the `modpacks` folder.
+    fn find_icon(&self) -> Option<PathBuf> {
+        let icon = self
+            .path
+            .parent()?
+            .parent()?
+            .join("assets")
+            .join("packs")
+            .join(self.path.file_name()?)
+            .join("icon.png");
+
+        icon.is_file().then_some(icon)
+    }
+}
+
+/// Reads the `version.json` embedded in a pack's `modpack.jar`.
+/// Forge based packs ship the forge universal jar (or installer) as `modpack.jar`.
+fn read_jar_version(reader: impl Read + Seek) -> anyhow::Result<Option<Vec<u8>>> {
+    let mut zip = zip::ZipArchive::new(reader)?;
+
+    let Ok(mut entry) = zip.by_name("version.json") else {
+        return Ok(None);
+    };
+
+    let mut data = Vec::new();
+    entry.read_to_end(&mut data)?;
+
+    Ok(Some(data))
+}
+
+/// The subset of a minecraft launcher version json technic packs ship in `bin`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TechnicVersion {
+    id: String,
+    inherits_from: Option<String>,
+    jar: Option<String>,
+    #[serde(default)]
+    libraries: Vec<TechnicLibrary>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TechnicLibrary {
+    name: String,
+}
+
+impl TechnicVersion {
+    fn minecraft_version(&self) -> String {
+        self.inherits_from
+            .clone()
+            .or_else(|| self.jar.clone())
+            .unwrap_or_else(|| match self.id.split_once('-') {
+                Some((minecraft, _)) => minecraft.to_string(),
+                None => self.id.clone(),
+            })
+    }
+
+    fn to_dependencies(&self) -> ModrinthPackDependencies {
+        let minecraft = self.minecraft_version();
+
+        let mut dependencies = ModrinthPackDependencies {
+            minecraft: None,
+            forge: None,
+            neoforge: None,
+            fabric_loader: None,
+            quilt_loader: None,
+        };
+
+        for library in &self.libraries {
+            let mut parts = library.name.split(':');
+            let (Some(group), Some(artifact), Some(version)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+
+            // forge versions are written as `<mc>-<forge>` or `<mc>-<forge>-<mc>`
+            let loader_version = version
+                .strip_prefix(&format!("{minecraft}-"))
+                .unwrap_or(version);
+            let loader_version = loader_version
+                .strip_suffix(&format!("-{minecraft}"))
+                .unwrap_or(loader_version)
+                .to_string();
+
+            match (group, artifact) {
+                ("net.minecraftforge", "forge" | "minecraftforge") => {
+                    dependencies.forge = Some(loader_version)
+                }
+                // neoforge for 1.20.1 was still published under the forge artifact
+                ("net.neoforged", "neoforge" | "forge") => {
+                    dependencies.neoforge = Some(loader_version)
+                }
+                ("net.fabricmc", "fabric-loader") => {
+                    dependencies.fabric_loader = Some(loader_version)
+                }
+                ("org.quiltmc", "quilt-loader") => dependencies.quilt_loader = Some(loader_version),
+                _ => {}
+            }
+        }
+
+        dependencies.minecraft = Some(minecraft);
+        dependencies
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Cursor, Write};
+
+    use super::{read_jar_version, TechnicVersion};
+
+    fn parse(text: &[u8]) -> TechnicVersion {
+        serde_json::from_slice(text).unwrap()
+    }
+
+    #[test]
+    fn test_forge_1_12_2_version() {
+        let version = parse(include_bytes!("test_fixtures/technic/forge-1.12.2.json"));
+        let dependencies = version.to_dependencies();
+
+        assert_eq!(dependencies.minecraft.as_deref(), Some("1.12.2"));
+        assert_eq!(dependencies.forge.as_deref(), Some("14.23.5.2860"));
+        assert_eq!(dependencies.fabric_loader, None);
+    }
+
+    #[test]
+    fn test_forge_1_7_10_version() {
+        let version = parse(include_bytes!("test_fixtures/technic/forge-1.7.10.json"));
+        let dependencies = version.to_dependencies();
+
+        assert_eq!(dependencies.minecraft.as_deref(), Some("1.7.10"));
+        assert_eq!(dependencies.forge.as_deref(), Some("10.13.4.1614"));
+    }
+
+    #[test]
+    fn test_neoforge_1_20_1_version() {
+        let version = parse(include_bytes!("test_fixtures/technic/neoforge-1.20.1.json"));
+        let dependencies = version.to_dependencies();
+
+        assert_eq!(dependencies.minecraft.as_deref(), Some("1.20.1"));
+        assert_eq!(dependencies.neoforge.as_deref(), Some("47.1.106"));
+        assert_eq!(dependencies.forge, None);
+    }
+
+    #[test]
+    fn test_fabric_version() {
+        let version = parse(include_bytes!("test_fixtures/technic/fabric-1.20.1.json"));
+        let dependencies = version.to_dependencies();
+
+        assert_eq!(dependencies.minecraft.as_deref(), Some("1.20.1"));
+        assert_eq!(dependencies.fabric_loader.as_deref(), Some("0.15.11"));
+        assert_eq!(dependencies.forge, None);
+    }
+
+    #[test]
+    fn test_read_modpack_jar() {
+        let mut jar = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut jar);
+            let options = zip::write::SimpleFileOptions::default();
+            zip.start_file("version.json", options).unwrap();
+            zip.write_all(include_bytes!("test_fixtures/technic/forge-1.7.10.json"))
+                .unwrap();
+            zip.start_file("net/minecraftforge/Dummy.class", options)
+                .unwrap();
+            zip.finish().unwrap();
+        }
+
+        jar.set_position(0);
+        let version = read_jar_version(jar).unwrap().unwrap();
+        let dependencies = parse(&version).to_dependencies();
+
+        assert_eq!(dependencies.minecraft.as_deref(), Some("1.7.10"));
+        assert_eq!(dependencies.forge.as_deref(), Some("10.13.4.1614"));
+    }
+
+    #[test]
+    fn test_jar_without_version() {
+        let mut jar = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut jar);
+            zip.start_file(
+                "META-INF/MANIFEST.MF",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+            zip.finish().unwrap();
+        }
+
+        jar.set_position(0);
+        assert!(read_jar_version(jar).unwrap().is_none());
+    }
+}
@@ -484,7 +484,10 @@ impl Installer {
 
                             carbon_net::download_multiple(
                                 &[downloadable.clone()],
-                                DownloadOptions::builder().concurrency(1).build(),
+                                DownloadOptions::builder()
+                                    .concurrency(1)
+                                    .store(app_clone.download_manager().content_store())
+                                    .build(),
                             )
                             .await
                             .with_context(|| {
@@ -592,25 +595,12 @@ impl ResourceInstaller for CurseforgeModInstaller {
     async fn downloadable(&self, instance_path: &InstancePath) -> Option<Downloadable> {
         let install_path = instance_path.get_mods_path().join(&self.file.file_name);
 
-        let checksums = &self
-            .file
-            .hashes
-            .iter()
-            .map(|hash| match hash.algo {
-                crate::domain::modplatforms::curseforge::HashAlgo::Sha1 => {
-                    Checksum::Sha1(hash.value.clone())
-                }
-                crate::domain::modplatforms::curseforge::HashAlgo::Md5 => {
-                    Checksum::Md5(hash.value.clone())
-                }
-            })
-            .collect::<Vec<_>>();
-
         let size = &self.file.file_length;
 
         Some(
             Downloadable::new(&self.download_url, install_path)
-                .with_checksum(checksums.get(0).cloned())
+                .with_mirrors(curseforge::cdn_mirrors(&self.download_url))
+                .with_checksums(self.file.checksums())
                 .with_size(*size as u64),
         )
     }
@@ -888,7 +878,7 @@ impl ResourceInstaller for ModrinthModInstaller {
     async fn downloadable(&self, instance_path: &InstancePath) -> Option<Downloadable> {
         let install_path = instance_path.get_mods_path().join(&self.file.filename);
 
-        let checksum = Checksum::Sha1(self.file.hashes.sha1.clone());
+        let checksum = Checksum::Sha512(self.file.hashes.sha512.clone());
         let size = self.file.size;
 
         Some(
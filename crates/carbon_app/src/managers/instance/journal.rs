@@ -0,0 +1,479 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use anyhow::bail;
+use carbon_net::{Checksum, ContentStore, DownloadOptions, Downloadable};
+use chrono::{DateTime, FixedOffset};
+use tokio::sync::{mpsc, watch};
+
+use crate::api::keys::instance::GET_INTERRUPTED_INSTALLS;
+use crate::api::translation::Translation;
+use crate::db::download_journal_entry as jdb;
+use crate::domain::instance::InstanceId;
+use crate::domain::vtask::VisualTaskId;
+use crate::managers::vtask::{Subtask, TaskState, VisualTask};
+use crate::managers::ManagerRef;
+
+use super::{InstanceManager, InstanceType, LaunchState};
+
+// keeps each insert well below sqlite's bound parameter limit
+const JOURNAL_BATCH_SIZE: usize = 500;
+
+#[derive(Debug)]
+pub struct InterruptedInstall {
+    pub instance_id: InstanceId,
+    /// title of the visual task the downloads belonged to
+    pub task: Translation,
+    pub files: u32,
+    /// files that already have a partially downloaded part file on disk
+    pub partial_files: u32,
+    pub interrupted_at: DateTime<FixedOffset>,
+}
+
+impl ManagerRef<'_, InstanceManager> {
+    /// Records the files an install of `instance_id` is about to download,
+    /// replacing whatever was journaled for the instance before.
+    pub(super) async fn journal_downloads(
+        self,
+        instance_id: InstanceId,
+        task: &Translation,
+        downloads: &[Downloadable],
+        store: Option<&ContentStore>,
+    ) -> anyhow::Result<()> {
+        self.clear_download_journal(instance_id).await?;
+
+        let task = serde_json::to_string(task)?;
+
+        for chunk in downloads.chunks(JOURNAL_BATCH_SIZE) {
+            let entries = chunk
+                .iter()
+                .map(|downloadable| {
+                    // files going through the store are written next to their store entry
+                    let download_path = store
+                        .and_then(|store| store.path_for(&downloadable.checksums))
+                        .unwrap_or_else(|| downloadable.path.clone());
+
+                    jdb::create_unchecked(
+                        *instance_id,
+                        task.clone(),
+                        downloadable.url.clone(),
+                        downloadable.mirrors.join("\n"),
+                        downloadable.path.to_string_lossy().to_string(),
+                        carbon_net::part_file_path(&download_path)
+                            .to_string_lossy()
+                            .to_string(),
+                        encode_checksums(&downloadable.checksums),
+                        vec![jdb::size::set(downloadable.size.map(|size| size as i64))],
+                    )
+                })
+                .collect();
+
+            self.app
+                .prisma_client
+                .download_journal_entry()
+                .create_many(entries)
+                .exec()
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub(super) async fn clear_download_journal(
+        self,
+        instance_id: InstanceId,
+    ) -> anyhow::Result<()> {
+        self.app
+            .prisma_client
+            .download_journal_entry()
+            .delete_many(vec![jdb::instance_id::equals(*instance_id)])
+            .exec()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Installs that still have journaled downloads and are not currently being prepared or
+    /// resumed.
+    pub async fn interrupted_installs(self) -> anyhow::Result<Vec<InterruptedInstall>> {
+        let entries = self
+            .app
+            .prisma_client
+            .download_journal_entry()
+            .find_many(Vec::new())
+            .exec()
+            .await?;
+
+        let resuming = self.resuming_installs.lock().await;
+        let instances = self.instances.read().await;
+
+        let mut installs = BTreeMap::<i32, InterruptedInstall>::new();
+
+        for entry in entries {
+            let instance_id = InstanceId(entry.instance_id);
+
+            let Some(instance) = instances.get(&instance_id) else {
+                continue;
+            };
+
+            if resuming.contains(&instance_id)
+                || matches!(
+                    &instance.type_,
+                    InstanceType::Valid(data) if matches!(data.state, LaunchState::Preparing(_))
+                )
+            {
+                continue;
+            }
+
+            let install = installs
+                .entry(entry.instance_id)
+                .or_insert_with(|| InterruptedInstall {
+                    instance_id,
+                    // only fails when the translation changed since the install was journaled
+                    task: serde_json::from_str(&entry.task).unwrap_or_else(|_| {
+                        Translation::InstanceTaskPrepare {
+                            name: match &instance.type_ {
+                                InstanceType::Valid(data) => data.config.name.clone(),
+                                InstanceType::Invalid(_) => instance.shortpath.clone(),
+                            },
+                        }
+                    }),
+                    files: 0,
+                    partial_files: 0,
+                    interrupted_at: entry.created_at,
+                });
+
+            install.files += 1;
+            if PathBuf::from(&entry.part_file).is_file() {
+                install.partial_files += 1;
+            }
+            install.interrupted_at = install.interrupted_at.max(entry.created_at);
+        }
+
+        Ok(installs.into_values().collect())
+    }
+
+    /// Downloads the files of an interrupted install from the urls, mirrors and checksums it
+    /// journaled, picking up the part files it left behind, then prepares the instance again
+    /// to finish the install.
+    pub async fn resume_interrupted_install(
+        self,
+        instance_id: InstanceId,
+    ) -> anyhow::Result<VisualTaskId> {
+        let Some(install) = self
+            .interrupted_installs()
+            .await?
+            .into_iter()
+            .find(|install| install.instance_id == instance_id)
+        else {
+            bail!("instance {instance_id} has no interrupted install");
+        };
+
+        let entries = self
+            .app
+            .prisma_client
+            .download_journal_entry()
+            .find_many(vec![jdb::instance_id::equals(*instance_id)])
+            .exec()
+            .await?;
+
+        let mut stored = Vec::new();
+        let mut direct = Vec::new();
+
+        for entry in entries {
+            let path = PathBuf::from(&entry.path);
+            // files going through the store were written next to their store entry
+            let through_store =
+                PathBuf::from(&entry.part_file) != carbon_net::part_file_path(&path);
+
+            let mut downloadable = Downloadable::new(entry.url, path)
+                .with_mirrors(entry.mirrors.lines())
+                .with_checksums(decode_checksums(&entry.checksums)?);
+
+            if let Some(size) = entry.size {
+                downloadable = downloadable.with_size(size as u64);
+            }
+
+            match through_store {
+                true => stored.push(downloadable),
+                false => direct.push(downloadable),
+            }
+        }
+
+        if !self.resuming_installs.lock().await.insert(instance_id) {
+            bail!("the interrupted install of instance {instance_id} is already being resumed");
+        }
+
+        let task = VisualTask::new(install.task);
+        let task_id = self.app.task_manager().spawn_task(&task).await;
+
+        let t_download_modpack_files =
+            task.subtask(Translation::InstanceTaskLaunchDownloadModpackFiles);
+        let t_download_files = task.subtask(Translation::InstanceTaskLaunchDownloadFiles);
+
+        task.edit(|data| data.state = TaskState::KnownProgress)
+            .await;
+
+        let app = self.app.clone();
+
+        tokio::spawn(async move {
+            let instance_manager = app.instance_manager();
+
+            let downloaded: anyhow::Result<_> = async {
+                let settings = app.settings_manager().get_settings().await?;
+                let concurrency = settings.concurrent_downloads as usize;
+                let store = app.download_manager().content_store();
+
+                download_journaled(&stored, Some(store), concurrency, &t_download_modpack_files)
+                    .await?;
+                download_journaled(&direct, None, concurrency, &t_download_files).await?;
+
+                Ok(())
+            }
+            .await;
+
+            instance_manager
+                .resuming_installs
+                .lock()
+                .await
+                .remove(&instance_id);
+
+            let try_result = match downloaded {
+                Ok(()) => instance_manager
+                    .prepare_game(instance_id, None, None, false)
+                    .await
+                    .map(|_| ()),
+                Err(e) => {
+                    // a failed install is not an interrupted one, so the journal goes either way
+                    let _ = instance_manager.clear_download_journal(instance_id).await;
+                    Err(e.context(format!(
+                        "Failed to resume the install of instance {instance_id}"
+                    )))
+                }
+            };
+
+            app.invalidate(GET_INTERRUPTED_INSTALLS, None);
+
+            if let Err(e) = try_result {
+                task.fail(e).await;
+            }
+        });
+
+        Ok(task_id)
+    }
+
+    /// Forgets an interrupted install, deleting the part files it left behind.
+    pub async fn dismiss_interrupted_install(self, instance_id: InstanceId) -> anyhow::Result<()> {
+        if self
+            .interrupted_installs()
+            .await?
+            .iter()
+            .all(|install| install.instance_id != instance_id)
+        {
+            bail!("instance {instance_id} has no interrupted install");
+        }
+
+        let entries = self
+            .app
+            .prisma_client
+            .download_journal_entry()
+            .find_many(vec![jdb::instance_id::equals(*instance_id)])
+            .exec()
+            .await?;
+
+        for entry in entries {
+            match tokio::fs::remove_file(&entry.part_file).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => tracing::warn!("Failed to remove part file {}: {e}", entry.part_file),
+            }
+        }
+
+        self.clear_download_journal(instance_id).await
+    }
+
+    /// Removes part files in the instances and the download store that no journal entry
+    /// accounts for.
+    ///
+    /// Part files modified after `started_at` belong to downloads of this session and are kept.
+    pub(super) async fn clean_orphaned_part_files(
+        self,
+        started_at: SystemTime,
+    ) -> anyhow::Result<()> {
+        let journaled = self
+            .app
+            .prisma_client
+            .download_journal_entry()
+            .find_many(Vec::new())
+            .exec()
+            .await?
+            .into_iter()
+            .map(|entry| PathBuf::from(entry.part_file))
+            .collect::<HashSet<_>>();
+
+        let roots = [
+            self.app
+                .settings_manager()
+                .runtime_path
+                .get_instances()
+                .to_path(),
+            self.app
+                .download_manager()
+                .content_store()
+                .root()
+                .to_path_buf(),
+        ];
+
+        let removed = tokio::task::spawn_blocking(move || {
+            let mut removed = 0;
+
+            for entry in roots
+                .iter()
+                .flat_map(walkdir::WalkDir::new)
+                .filter_map(Result::ok)
+            {
+                let path = entry.path();
+
+                if !entry.file_type().is_file()
+                    || carbon_net::part_file_target(path).is_none()
+                    || journaled.contains(path)
+                {
+                    continue;
+                }
+
+                let modified = entry.metadata().ok().and_then(|m| m.modified().ok());
+                if modified.map_or(true, |modified| modified >= started_at) {
+                    continue;
+                }
+
+                match std::fs::remove_file(path) {
+                    Ok(()) => removed += 1,
+                    Err(e) => tracing::warn!("Failed to remove part file {}: {e}", path.display()),
+                }
+            }
+
+            removed
+        })
+        .await?;
+
+        tracing::info!("Removed {removed} orphaned part files");
+
+        Ok(())
+    }
+}
+
+/// Downloads `downloads`, reporting the progress to `subtask`.
+async fn download_journaled(
+    downloads: &[Downloadable],
+    store: Option<ContentStore>,
+    concurrency: usize,
+    subtask: &Subtask,
+) -> anyhow::Result<()> {
+    let (progress_tx, mut progress_rx) = watch::channel(carbon_net::Progress::new());
+    let (served_by_tx, mut served_by_rx) = mpsc::unbounded_channel::<carbon_net::ServedBy>();
+
+    let mut options = DownloadOptions::builder()
+        .concurrency(concurrency)
+        .progress_sender(progress_tx)
+        .served_by_sender(served_by_tx);
+
+    if let Some(store) = store {
+        options = options.store(store);
+    }
+
+    subtask.start_opaque();
+
+    let download = carbon_net::download_multiple(downloads, options.build());
+    tokio::pin!(download);
+
+    loop {
+        tokio::select! {
+            result = &mut download => {
+                result?;
+                break;
+            }
+            Ok(()) = progress_rx.changed() => {
+                let progress = progress_rx.borrow_and_update();
+                subtask.update_download(
+                    progress.current_size as u32,
+                    progress.total_size as u32,
+                    false,
+                );
+            }
+            Some(served_by) = served_by_rx.recv() => {
+                subtask.add_download_host(&served_by.url);
+            }
+        }
+    }
+
+    while let Ok(served_by) = served_by_rx.try_recv() {
+        subtask.add_download_host(&served_by.url);
+    }
+
+    subtask.complete_opaque();
+
+    Ok(())
+}
+
+fn encode_checksums(checksums: &[Checksum]) -> String {
+    checksums
+        .iter()
+        .map(|checksum| match checksum {
+            Checksum::Sha1(hash) => format!("sha1:{hash}"),
+            Checksum::Sha256(hash) => format!("sha256:{hash}"),
+            Checksum::Sha512(hash) => format!("sha512:{hash}"),
+            Checksum::Md5(hash) => format!("md5:{hash}"),
+            Checksum::Murmur2(hash) => format!("murmur2:{hash}"),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn decode_checksums(checksums: &str) -> anyhow::Result<Vec<Checksum>> {
+    checksums
+        .split(',')
+        .filter(|checksum| !checksum.is_empty())
+        .map(|checksum| {
+            let Some((algorithm, value)) = checksum.split_once(':') else {
+                bail!("malformed journaled checksum `{checksum}`");
+            };
+
+            Ok(match algorithm {
+                "sha1" => Checksum::Sha1(value.to_string()),
+                "sha256" => Checksum::Sha256(value.to_string()),
+                "sha512" => Checksum::Sha512(value.to_string()),
+                "md5" => Checksum::Md5(value.to_string()),
+                "murmur2" => Checksum::Murmur2(value.parse()?),
+                _ => bail!("unknown journaled checksum algorithm `{algorithm}`"),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use carbon_net::Checksum;
+
+    use super::{decode_checksums, encode_checksums};
+
+    #[test]
+    fn test_encode_checksums() {
+        assert_eq!(
+            encode_checksums(&[Checksum::Sha1("abc".to_string()), Checksum::Murmur2(42)]),
+            "sha1:abc,murmur2:42"
+        );
+        assert_eq!(encode_checksums(&[]), "");
+    }
+
+    #[test]
+    fn test_decode_checksums() {
+        let checksums = [Checksum::Sha512("def".to_string()), Checksum::Murmur2(42)];
+        assert_eq!(
+            decode_checksums(&encode_checksums(&checksums)).unwrap(),
+            checksums
+        );
+        assert_eq!(decode_checksums("").unwrap(), []);
+        assert!(decode_checksums("crc32:1").is_err());
+    }
+}
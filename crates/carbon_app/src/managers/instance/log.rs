@@ -1,6 +1,7 @@
 use itertools::Itertools;
 use serde::Serialize;
 use std::{
+    collections::{hash_map, HashMap},
     ops::{Bound, RangeBounds},
     sync::atomic::{AtomicI32, Ordering},
 };
@@ -35,6 +36,70 @@ pub struct LogEntry {
     pub level: LogEntryLevel,
     /// The entry message itself.
     pub message: String,
+    /// The exception logged with the entry.
+    pub throwable: Option<Throwable>,
+}
+
+/// A Java exception found in the log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Throwable {
+    /// The fully qualified class name of the exception.
+    pub class: String,
+    /// The exception message.
+    pub message: Option<String>,
+    /// The stack frames, starting with the one that threw.
+    pub frames: Vec<StackFrame>,
+    /// The exception that caused this one.
+    pub cause: Option<Box<Throwable>>,
+}
+
+/// A single stack frame of a [`Throwable`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackFrame {
+    /// The method, qualified by its class.
+    pub method: String,
+    /// Where in the source the method was.
+    pub location: Option<String>,
+}
+
+impl From<carbon_parsing::log::Throwable<'_>> for Throwable {
+    fn from(throwable: carbon_parsing::log::Throwable) -> Self {
+        Self {
+            class: throwable.class.into_owned(),
+            message: throwable.message.map(|message| message.into_owned()),
+            frames: throwable
+                .frames
+                .into_iter()
+                .map(|frame| StackFrame {
+                    method: frame.method.into_owned(),
+                    location: frame.location.map(|location| location.into_owned()),
+                })
+                .collect(),
+            cause: throwable.cause.map(|cause| Box::new((*cause).into())),
+        }
+    }
+}
+
+impl Throwable {
+    /// Iterates over this exception and the ones that caused it.
+    pub fn chain(&self) -> impl Iterator<Item = &Throwable> {
+        std::iter::successors(Some(self), |throwable| throwable.cause.as_deref())
+    }
+}
+
+/// A distinct exception and how often it was logged.
+#[derive(Debug, Clone)]
+pub struct LogException {
+    /// The first occurrence of the exception.
+    pub throwable: Throwable,
+    /// How many entries logged the exception.
+    pub count: u32,
+    /// The index of the first entry that logged the exception.
+    pub first_line: usize,
+    /// The timestamp of the last entry that logged the exception.
+    pub last_timestamp: u64,
 }
 
 impl From<(LogEntrySourceKind, carbon_parsing::log::LogEntry<'_>)> for LogEntry {
@@ -45,15 +110,17 @@ impl From<(LogEntrySourceKind, carbon_parsing::log::LogEntry<'_>)> for LogEntry
             timestamp,
             thread_name,
             message,
+            throwable,
         } = entry;
 
         Self {
             source_kind,
-            logger: logger.to_owned(),
+            logger: logger.into_owned(),
             timestamp,
-            thread: thread_name.to_owned(),
+            thread: thread_name.into_owned(),
             level: level.into(),
-            message: message.to_owned(),
+            message: message.into_owned(),
+            throwable: throwable.map(Throwable::from),
         }
     }
 }
@@ -68,6 +135,7 @@ impl LogEntry {
             thread: "N/A".into(),
             level: LogEntryLevel::Info,
             message: msg.to_string(),
+            throwable: None,
         }
     }
 
@@ -154,6 +222,45 @@ impl GameLog {
     pub fn len(&self) -> usize {
         self.0.len()
     }
+
+    /// Lists the distinct exceptions in the log, in the order they first occurred.
+    ///
+    /// Exceptions are the same when their class, message and throwing method match.
+    pub fn exceptions(&self) -> Vec<LogException> {
+        let mut exceptions = Vec::<LogException>::new();
+        let mut seen = HashMap::new();
+
+        for (line, entry) in self.0.iter().enumerate() {
+            let Some(throwable) = &entry.throwable else {
+                continue;
+            };
+
+            let key = (
+                &throwable.class,
+                &throwable.message,
+                throwable.frames.first().map(|frame| &frame.method),
+            );
+
+            match seen.entry(key) {
+                hash_map::Entry::Occupied(idx) => {
+                    let exception = &mut exceptions[*idx.get()];
+                    exception.count += 1;
+                    exception.last_timestamp = entry.timestamp;
+                }
+                hash_map::Entry::Vacant(idx) => {
+                    idx.insert(exceptions.len());
+                    exceptions.push(LogException {
+                        throwable: throwable.clone(),
+                        count: 1,
+                        first_line: line,
+                        last_timestamp: entry.timestamp,
+                    });
+                }
+            }
+        }
+
+        exceptions
+    }
 }
 
 impl ManagerRef<'_, InstanceManager> {
@@ -199,6 +306,16 @@ impl ManagerRef<'_, InstanceManager> {
         }
     }
 
+    pub async fn get_log_exceptions(
+        self,
+        id: GameLogId,
+    ) -> Result<Vec<LogException>, InvalidGameLogIdError> {
+        match self.game_logs.read().await.get(&id) {
+            Some((_, log)) => Ok(log.borrow().exceptions()),
+            None => Err(InvalidGameLogIdError),
+        }
+    }
+
     pub async fn get_logs(self, instance_id: InstanceId) -> Vec<GameLogEntry> {
         self.game_logs
             .read()
@@ -269,4 +386,49 @@ mod test {
         test_span(&log, 1..2, ["item 2"]);
         test_span(&log, 1..=3, ["item 2", "item 3", "item 4"]);
     }
+
+    #[test]
+    fn exceptions() {
+        let mut log = GameLog::new();
+
+        fn add_exception(log: &mut GameLog, class: &str, message: &str) {
+            let mut entry = LogEntry::system_error(message);
+            entry.throwable = Some(Throwable {
+                class: class.into(),
+                message: Some(message.into()),
+                frames: vec![StackFrame {
+                    method: "a.B.run".into(),
+                    location: Some("B.java:7".into()),
+                }],
+                cause: None,
+            });
+            log.add_entry(entry);
+        }
+
+        add_exception(&mut log, "java.lang.NullPointerException", "null");
+        add_exception(&mut log, "java.io.IOException", "closed");
+        add_exception(&mut log, "java.lang.NullPointerException", "null");
+        log.add_entry(LogEntry::system_message("no exception"));
+        add_exception(&mut log, "java.lang.NullPointerException", "null");
+
+        let exceptions = log
+            .exceptions()
+            .into_iter()
+            .map(|exception| {
+                (
+                    exception.throwable.class,
+                    exception.count,
+                    exception.first_line,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            exceptions,
+            [
+                ("java.lang.NullPointerException".to_string(), 3, 0),
+                ("java.io.IOException".to_string(), 1, 1),
+            ]
+        );
+    }
 }
@@ -50,8 +50,10 @@ use unicode_segmentation::UnicodeSegmentation;
 
 pub mod explore;
 pub mod export;
+mod gdlpack;
 pub mod importer;
 pub mod installer;
+pub mod journal;
 pub mod log;
 pub mod modpack;
 mod mods;
@@ -70,6 +72,8 @@ pub struct InstanceManager {
     export_manager: InstanceExportManager,
     game_logs: RwLock<HashMap<GameLogId, (InstanceId, watch::Receiver<GameLog>)>>,
     modpack_info_semaphore: Mutex<()>,
+    // interrupted installs downloading their journaled files
+    resuming_installs: Mutex<HashSet<InstanceId>>,
     pub any_instance_running: Arc<watch::Sender<bool>>,
     instance_running_tracker: Arc<LivenessTracker>,
 }
@@ -94,6 +98,7 @@ impl InstanceManager {
             export_manager: InstanceExportManager::new(),
             game_logs: RwLock::new(HashMap::new()),
             modpack_info_semaphore: Mutex::new(()),
+            resuming_installs: Mutex::new(HashSet::new()),
             any_instance_running: any_instance_running.clone(),
             instance_running_tracker: LivenessTracker::new(move |count| {
                 drop(any_instance_running.send_replace(count != 0))
@@ -152,8 +157,14 @@ fn path_length(path: &Path) -> usize {
 
 impl<'s> ManagerRef<'s, InstanceManager> {
     pub async fn launch_background_tasks(self) {
+        let started_at = std::time::SystemTime::now();
+
         let _ = self.scan_instances().await;
         self.import_manager().launch_background_tasks();
+
+        if let Err(e) = self.clean_orphaned_part_files(started_at).await {
+            tracing::error!({ error = ?e }, "Failed to clean up orphaned part files");
+        }
     }
 
     pub async fn scan_instances(self) -> anyhow::Result<()> {
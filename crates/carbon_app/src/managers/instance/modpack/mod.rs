@@ -33,6 +33,10 @@ impl ManagerRef<'_, InstanceManager> {
         self,
         instance_id: InstanceId,
     ) -> anyhow::Result<()> {
+        if carbon_net::is_offline() {
+            return Ok(());
+        }
+
         let instances = self.instances.read().await;
         let instance = instances
             .get(&instance_id)
@@ -87,6 +91,10 @@ impl ManagerRef<'_, InstanceManager> {
         self,
         instance_id: InstanceId,
     ) -> anyhow::Result<()> {
+        if carbon_net::is_offline() {
+            return Ok(());
+        }
+
         let instances = self.instances.read().await;
         let instance = instances
             .get(&instance_id)
@@ -213,14 +213,16 @@ impl ManagerRef<'_, InstanceManager> {
             None => bail!("Instance has no associated game version and cannot be launched"),
         };
 
-        let task = VisualTask::new(match &launch_account {
+        let task_title = match &launch_account {
             Some(_) => Translation::InstanceTaskLaunch {
                 name: config.name.clone(),
             },
             None => Translation::InstanceTaskPrepare {
                 name: config.name.clone(),
             },
-        });
+        };
+
+        let task = VisualTask::new(task_title.clone());
 
         let id = self.app.task_manager().spawn_task(&task).await;
 
@@ -613,6 +615,8 @@ impl ManagerRef<'_, InstanceManager> {
 
                     let (progress_watch_tx, mut progress_watch_rx) =
                         tokio::sync::watch::channel(carbon_net::Progress::new());
+                    let (served_by_tx, mut served_by_rx) =
+                        tokio::sync::mpsc::unbounded_channel::<carbon_net::ServedBy>();
 
                     t_download_modpack_files.start_opaque();
 
@@ -628,33 +632,51 @@ impl ManagerRef<'_, InstanceManager> {
                                 );
                             }
 
+                            while let Ok(served_by) = served_by_rx.try_recv() {
+                                t_download_modpack_files.add_download_host(&served_by.url);
+                            }
+
                             tokio::time::sleep(Duration::from_millis(200)).await;
                         }
 
+                        while let Ok(served_by) = served_by_rx.try_recv() {
+                            t_download_modpack_files.add_download_host(&served_by.url);
+                        }
+
                         t_download_modpack_files.complete_opaque();
                     });
 
-                    let concurrency = app
-                        .settings_manager()
-                        .get_settings()
-                        .await?
-                        .concurrent_downloads;
+                    let settings = app.settings_manager().get_settings().await?;
+                    let concurrency = settings.concurrent_downloads;
+
+                    let store = app.download_manager().content_store();
 
-                    carbon_net::download_multiple(
+                    instance_manager
+                        .journal_downloads(instance_id, &task_title, &modpack_downloads, Some(&store))
+                        .await?;
+
+                    let downloaded = carbon_net::download_multiple(
                         &modpack_downloads[..],
                         DownloadOptions::builder().concurrency(concurrency as usize)
                         .progress_sender(
                             progress_watch_tx
                         )
+                        .served_by_sender(served_by_tx)
                         .deep_check(deep_check)
+                        .store(store)
                         .build(),
                     )
-                    .await
-                    .with_context(|| {
+                    .await;
+
+                    // a failed install is not an interrupted one, so the journal goes either way
+                    let cleared = instance_manager.clear_download_journal(instance_id).await;
+
+                    downloaded.with_context(|| {
                         format!(
                             "Failed to download modpack instance files for instance {instance_id}"
                         )
                     })?;
+                    cleared?;
 
                     completion.await?;
 
@@ -1219,11 +1241,8 @@ impl ManagerRef<'_, InstanceManager> {
 
                 t_request_minecraft_files.complete_opaque();
 
-                let concurrency = app
-                    .settings_manager()
-                    .get_settings()
-                    .await?
-                    .concurrent_downloads;
+                let settings = app.settings_manager().get_settings().await?;
+                let concurrency = settings.concurrent_downloads;
 
                 let (progress_watch_tx, mut progress_watch_rx) =
                 tokio::sync::watch::channel(carbon_net::Progress::new());
@@ -1275,6 +1294,8 @@ impl ManagerRef<'_, InstanceManager> {
 
                     let (progress_watch_tx, mut progress_watch_rx) =
                         tokio::sync::watch::channel(carbon_net::Progress::new());
+                    let (served_by_tx, mut served_by_rx) =
+                        tokio::sync::mpsc::unbounded_channel::<carbon_net::ServedBy>();
 
                     t_download_files.start_opaque();
                     let completion = tokio::spawn(async move {
@@ -1288,27 +1309,45 @@ impl ManagerRef<'_, InstanceManager> {
                                 );
                             }
 
+                            while let Ok(served_by) = served_by_rx.try_recv() {
+                                t_download_files.add_download_host(&served_by.url);
+                            }
+
                             tokio::time::sleep(Duration::from_millis(200)).await;
                         }
 
+                        while let Ok(served_by) = served_by_rx.try_recv() {
+                            t_download_files.add_download_host(&served_by.url);
+                        }
+
                         t_download_files.complete_opaque();
                     });
 
-                    carbon_net::download_multiple(
+                    instance_manager
+                        .journal_downloads(instance_id, &task_title, &downloads, None)
+                        .await?;
+
+                    let downloaded = carbon_net::download_multiple(
                         &downloads[..],
                         DownloadOptions::builder().concurrency(concurrency as usize)
                         .deep_check(deep_check)
                         .progress_sender(
                             progress_watch_tx
                         )
+                        .served_by_sender(served_by_tx)
                         .build(),
                     )
-                    .await
-                    .with_context(|| {
+                    .await;
+
+                    // a failed install is not an interrupted one, so the journal goes either way
+                    let cleared = instance_manager.clear_download_journal(instance_id).await;
+
+                    downloaded.with_context(|| {
                         format!(
                             "Failed to download instance files for instance {instance_id}"
                         )
                     })?;
+                    cleared?;
 
                     completion.await?;
                 }
@@ -1972,8 +2011,8 @@ async fn read_logs(
 
 /// Performs a single poll for data from the given pipe.
 ///
-/// Returns `true` when a line was fully received, at which point it is
-/// safe to flush to the log.
+/// Returns `true` when at least one entry was fully received, at which
+/// point it is safe to flush to the log.
 ///
 /// This function will modify the [`GameLog`], but not notify watchers.
 /// It is the responsibility of the caller to ensure notification happens
@@ -1992,35 +2031,28 @@ async fn read_pipe(
 
             line_buf.push_str(&utf8);
 
-            match carbon_parsing::log::parse_log_entry(line_buf) {
-                Ok((rest, entry)) => {
-                    log.send_if_modified(|log| {
-                        log.add_entry((kind, entry).into());
-
-                        false
-                    });
-
-                    let rest = rest.to_owned();
-                    *line_buf = rest;
-
-                    true
-                }
-                // do nothing, wait for more bytes
-                Err(nom::Err::Incomplete(_)) => false,
-                Err(err) => {
-                    tracing::error!("failed to parse log entry:\n{err:#?}");
+            add_log_entries(log, kind, line_buf, false)
+        }
+        // the pipe was closed, flush the last entry even without a `\n`
+        Ok(_) if !line_buf.trim().is_empty() => {
+            let mut modified = add_log_entries(log, kind, line_buf, true);
+
+            // an unterminated xml or json event
+            if !line_buf.trim().is_empty() {
+                log.send_if_modified(|log| {
+                    log.add_entry(LogEntry::system_error(format!(
+                        "incomplete log entry from {kind:?}: {line_buf}"
+                    )));
+
+                    false
+                });
 
-                    log.send_if_modified(|log| {
-                        log.add_entry(LogEntry::system_error(
-                            "failed to parse log entry from {kind:?}",
-                        ));
+                modified = true;
+            }
 
-                        false
-                    });
+            line_buf.clear();
 
-                    true
-                }
-            }
+            modified
         }
         Ok(_) => false,
         Err(err) => {
@@ -2042,3 +2074,60 @@ async fn read_pipe(
         }
     }
 }
+
+/// Adds every complete entry in `line_buf` to the log, leaving the
+/// incomplete tail in the buffer.
+///
+/// The last entry is held back until the next line arrives, as it may
+/// still be followed by a stack trace, unless `eof` is set.
+///
+/// Returns `true` if any entry was added.
+fn add_log_entries(
+    log: &watch::Sender<GameLog>,
+    kind: LogEntrySourceKind,
+    line_buf: &mut String,
+    eof: bool,
+) -> bool {
+    let mut modified = false;
+    let mut input = line_buf.as_str();
+
+    loop {
+        let entry = match eof {
+            true => carbon_parsing::log::parse_last_log_entry(input),
+            false => carbon_parsing::log::parse_log_entry(input),
+        };
+
+        match entry {
+            Ok((rest, entry)) => {
+                log.send_if_modified(|log| {
+                    log.add_entry((kind, entry).into());
+
+                    false
+                });
+
+                input = rest;
+                modified = true;
+            }
+            // do nothing, wait for more bytes
+            Err(nom::Err::Incomplete(_)) => break,
+            Err(err) => {
+                tracing::error!("failed to parse log entry:\n{err:#?}");
+
+                log.send_if_modified(|log| {
+                    log.add_entry(LogEntry::system_error(format!(
+                        "failed to parse log entry from {kind:?}: {input}"
+                    )));
+
+                    false
+                });
+
+                input = "";
+                modified = true;
+            }
+        }
+    }
+
+    *line_buf = input.to_owned();
+
+    modified
+}
@@ -575,6 +575,11 @@ fn cache_modplatform<C: ModplatformCacher>(
                     };
 
                     let do_caching = async {
+                        if carbon_net::is_offline() {
+                            debug!("Skipping {} mod caching for instance {instance_id} while offline", C::NAME);
+                            return Ok(());
+                        }
+
                         debug!({ is_priority, is_override }, "Beginning {} mod caching for instance {instance_id}", C::NAME);
 
                         // true could be optimized to "if there is a callback" if this is a bottleneck
@@ -224,14 +224,16 @@ pub async fn prepare_modpack_from_zip(
                 })
                 .flatten();
 
-            let downloadable = Downloadable::new(
-                mod_file
-                    .download_url
-                    .as_ref()
-                    .ok_or(anyhow::anyhow!("Failed to get download url for mod"))?,
-                instance_path.join(&mod_file.file_name),
-            )
-            .with_size(mod_file.file_length as u64);
+            let download_url = mod_file
+                .download_url
+                .as_ref()
+                .ok_or(anyhow::anyhow!("Failed to get download url for mod"))?;
+
+            let downloadable =
+                Downloadable::new(download_url, instance_path.join(&mod_file.file_name))
+                    .with_mirrors(curseforge::cdn_mirrors(download_url))
+                    .with_checksums(mod_file.checksums())
+                    .with_size(mod_file.file_length as u64);
 
             downloadables.push((downloadable, existing_path));
         }
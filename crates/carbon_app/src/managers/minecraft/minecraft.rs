@@ -16,6 +16,7 @@ use crate::{
     },
 };
 use anyhow::Context;
+use carbon_net::proxy::{ProxyConfig, ProxyScheme};
 use daedalus::minecraft::{
     Argument, ArgumentType, ArgumentValue, Library, LibraryGroup, Os, Version, VersionInfo,
     VersionManifest,
@@ -409,6 +410,73 @@ fn wraps_in_quotes_if_necessary(arg: impl AsRef<str>) -> String {
     arg.to_string()
 }
 
+/// JVM system properties that make the game use the launcher proxy.
+fn proxy_jvm_args(proxy: &ProxyConfig) -> Vec<String> {
+    let mut args = Vec::new();
+
+    let host_and_port = |scheme: ProxyScheme| {
+        let endpoint = proxy.endpoint(scheme)?;
+        let host = endpoint.host_str()?.to_string();
+        let port = endpoint.port_or_known_default().unwrap_or(match scheme {
+            ProxyScheme::Socks5 => 1080,
+            _ => 8080,
+        });
+
+        Some((host, port))
+    };
+
+    for (scheme, prefix) in [
+        (ProxyScheme::Http, "http."),
+        (ProxyScheme::Https, "https."),
+        (ProxyScheme::Socks5, "socks"),
+    ] {
+        if let Some((host, port)) = host_and_port(scheme) {
+            args.push(format!("-D{prefix}proxyHost={host}"));
+            args.push(format!("-D{prefix}proxyPort={port}"));
+        }
+    }
+
+    if args.is_empty() {
+        return args;
+    }
+
+    // java expects `|` separated wildcard patterns, shared by http and https
+    let non_proxy_hosts = proxy
+        .no_proxy
+        .iter()
+        .map(|host| host.trim())
+        .filter(|host| !host.is_empty())
+        .map(|host| match host.strip_prefix('.') {
+            Some(domain) => format!("*.{domain}"),
+            None => host.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("|");
+
+    if !non_proxy_hosts.is_empty() {
+        args.push(format!("-Dhttp.nonProxyHosts={non_proxy_hosts}"));
+    }
+
+    // credentials are left out: the JDK ignores the http ones without an `Authenticator`, and
+    // anything on the command line can be read from the process list by other users
+
+    args
+}
+
+/// Joins the command for logging, hiding the values of password system properties.
+fn redact_command(command: &[String]) -> String {
+    command
+        .iter()
+        .map(|arg| match arg.split_once('=') {
+            Some((key, _)) if key.starts_with("-D") && key.to_lowercase().contains("password") => {
+                format!("{key}=***")
+            }
+            _ => arg.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn generate_startup_command(
     java_component: JavaComponent,
@@ -624,6 +692,11 @@ pub async fn generate_startup_command(
         substitute_arguments(&mut command, jvm_arguments);
     }
 
+    // pushed before the user arguments so they can still be overridden
+    if let Some(proxy) = carbon_net::proxy::proxy() {
+        command.extend(proxy_jvm_args(&proxy));
+    }
+
     for cap in extra_args_regex.captures_iter(extra_java_args) {
         let ((Some(arg), _) | (_, Some(arg))) = (cap.name("quoted"), cap.name("raw")) else {
             continue;
@@ -703,7 +776,7 @@ pub async fn launch_minecraft(
     info!(
         "Starting Minecraft with command: {} {}",
         main_command,
-        startup_command.join(" ")
+        redact_command(&startup_command)
     );
 
     let mut command_exec = tokio::process::Command::new(main_command);
@@ -958,4 +1031,48 @@ mod tests {
             .await
             .unwrap();
     }
+
+    #[test]
+    fn test_proxy_jvm_args() {
+        let proxy = ProxyConfig {
+            http: Some("proxy.corp:3128".to_string()),
+            https: Some("https://secure.corp".to_string()),
+            socks5: None,
+            username: Some("user".to_string()),
+            password: Some("secret".to_string()),
+            no_proxy: vec!["localhost".to_string(), ".internal.corp".to_string()],
+            ca_bundle: None,
+        };
+
+        assert_eq!(
+            proxy_jvm_args(&proxy),
+            vec![
+                "-Dhttp.proxyHost=proxy.corp",
+                "-Dhttp.proxyPort=3128",
+                "-Dhttps.proxyHost=secure.corp",
+                "-Dhttps.proxyPort=443",
+                "-Dhttp.nonProxyHosts=localhost|*.internal.corp",
+            ]
+        );
+
+        assert!(proxy_jvm_args(&ProxyConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_redact_command() {
+        let command = [
+            "-Xmx4096m",
+            "-Djava.net.socks.password=secret",
+            "-Dhttps.proxyPassword=secret=too",
+            "-Dhttp.proxyHost=proxy.corp",
+            "--password=kept",
+        ]
+        .map(String::from);
+
+        assert_eq!(
+            redact_command(&command),
+            "-Xmx4096m -Djava.net.socks.password=*** -Dhttps.proxyPassword=*** \
+             -Dhttp.proxyHost=proxy.corp --password=kept"
+        );
+    }
 }
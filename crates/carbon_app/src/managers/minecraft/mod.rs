@@ -13,7 +13,7 @@ use crate::domain::{
     java::JavaArch,
     minecraft::minecraft::{
         assets_index_into_vec_downloadable, libraries_into_vec_downloadable,
-        version_download_into_downloadable,
+        version_download_into_downloadable, with_mojang_mirror,
     },
 };
 
@@ -152,6 +152,19 @@ impl ManagerRef<'_, MinecraftManager> {
         all_files.extend(libraries);
         all_files.extend(assets);
 
+        if let Some(mirror) = self
+            .app
+            .settings_manager()
+            .get_settings()
+            .await?
+            .mojang_mirror
+        {
+            all_files = all_files
+                .into_iter()
+                .map(|file| with_mojang_mirror(file, &mirror))
+                .collect();
+        }
+
         Ok(all_files)
     }
 }
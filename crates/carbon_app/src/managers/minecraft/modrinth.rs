@@ -3,7 +3,7 @@ use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::Context;
-use carbon_net::{DownloadOptions, Downloadable, Progress};
+use carbon_net::{Checksum, DownloadOptions, Downloadable, Progress};
 use tokio::task::spawn_blocking;
 
 use crate::domain::runtime_path::InstancePath;
@@ -227,6 +227,8 @@ pub async fn prepare_modpack_from_mrpack(
 
                 let data_path = instance_path.get_data_path();
 
+                let checksum = Checksum::Sha512(file.hashes.sha512.clone());
+
                 let existing_path = packinfo
                     .map(|packinfo| {
                         let mut sha512 = [0u8; 64];
@@ -243,14 +245,16 @@ pub async fn prepare_modpack_from_mrpack(
 
                 let target_path = secure_path_join(&data_path, &file.path)?;
 
-                let downloadable = Downloadable::new(
-                    file.downloads
-                        .first()
-                        .ok_or(anyhow::anyhow!("Failed to get download url for mod"))?
-                        .to_string(),
-                    target_path,
-                )
-                .with_size(file.file_size as u64);
+                // the first url is the primary, the rest are mirrors of the same file
+                let (url, mirrors) = file
+                    .downloads
+                    .split_first()
+                    .ok_or(anyhow::anyhow!("Failed to get download url for mod"))?;
+
+                let downloadable = Downloadable::new(url.to_string(), target_path)
+                    .with_mirrors(mirrors.iter().map(ToString::to_string))
+                    .with_checksum(Some(checksum))
+                    .with_size(file.file_size as u64);
 
                 progress_percentage_sender
                     .send(ProgressState::AcquiringPackMetadata(i as u64, files_len))?;
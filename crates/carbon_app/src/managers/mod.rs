@@ -16,14 +16,18 @@ use crate::db::PrismaClient;
 use crate::managers::settings::SettingsManager;
 
 use self::account::AccountManager;
+use self::connectivity::ConnectivityManager;
 use self::download::DownloadManager;
+use self::http_cache::HttpCacheManager;
 use self::instance::InstanceManager;
 use self::minecraft::MinecraftManager;
 use self::rich_presence::RichPresenceManager;
 use self::vtask::VisualTaskManager;
 
 pub mod account;
+pub mod connectivity;
 pub mod download;
+pub mod http_cache;
 pub mod instance;
 pub mod java;
 mod metadata;
@@ -66,6 +70,8 @@ mod app {
         account_manager: AccountManager,
         pub(crate) invalidation_channel: broadcast::Sender<InvalidationEvent>,
         download_manager: DownloadManager,
+        connectivity_manager: ConnectivityManager,
+        http_cache_manager: HttpCacheManager,
         pub(crate) instance_manager: InstanceManager,
         meta_cache_manager: MetaCacheManager,
         pub(crate) modplatforms_manager: ModplatformsManager,
@@ -104,6 +110,18 @@ mod app {
                     }
                 };
 
+            // the proxy CA bundle is only read when the http clients are built
+            match db_client
+                .app_configuration()
+                .find_unique(crate::db::app_configuration::id::equals(0))
+                .exec()
+                .await
+            {
+                Ok(Some(settings)) => super::settings::apply_proxy_settings(&settings),
+                Ok(None) => {}
+                Err(e) => error!("Error getting proxy settings: {e}"),
+            }
+
             let app = Arc::new(UnsafeCell::new(MaybeUninit::<AppInner>::uninit()));
             let unsaferef = UnsafeAppRef(Arc::downgrade(&app));
 
@@ -124,6 +142,8 @@ mod app {
                     account_manager: AccountManager::new(http_client.clone(), gdl_base_api.clone()),
                     modplatforms_manager: ModplatformsManager::new(unsaferef, gdl_base_api.clone()),
                     download_manager: DownloadManager::new(),
+                    connectivity_manager: ConnectivityManager::new(),
+                    http_cache_manager: HttpCacheManager::new(),
                     instance_manager: InstanceManager::new(),
                     meta_cache_manager: MetaCacheManager::new(),
                     invalidation_channel,
@@ -150,6 +170,19 @@ mod app {
                 _app.meta_cache_manager().launch_background_tasks().await;
             });
 
+            app.http_cache_manager().launch_background_tasks().await;
+
+            // tests talk to local servers and must not be switched to offline mode
+            #[cfg(not(test))]
+            app.connectivity_manager().launch_background_tasks().await;
+
+            let _app = app.clone();
+            tokio::spawn(async move {
+                if let Err(e) = _app.download_manager().gc_store().await {
+                    tracing::error!({ error = ?e }, "Failed to clean up the download store");
+                }
+            });
+
             let _app = app.clone();
             tokio::spawn(async move {
                 let settings = _app.settings_manager().get_settings().await;
@@ -159,6 +192,14 @@ mod app {
                         let show_app_close_warning = settings.show_app_close_warning;
                         println!("_SHOW_APP_CLOSE_WARNING_:{}", show_app_close_warning);
                         println!("_POTATO_PC_MODE_:{}", settings.reduced_motion);
+                        super::settings::apply_download_bandwidth_limit(
+                            settings.download_bandwidth_limit,
+                        );
+                        super::settings::apply_download_concurrency_per_host(
+                            settings.concurrent_downloads_per_host,
+                        );
+                        _app.connectivity_manager()
+                            .set_forced_offline(settings.offline_mode);
                     }
                     Err(e) => {
                         error!("Error getting settings: {e}");
@@ -189,6 +230,8 @@ mod app {
         manager_getter!(minecraft_manager: MinecraftManager);
         manager_getter!(account_manager: AccountManager);
         manager_getter!(download_manager: DownloadManager);
+        manager_getter!(connectivity_manager: ConnectivityManager);
+        manager_getter!(http_cache_manager: HttpCacheManager);
         manager_getter!(task_manager: VisualTaskManager);
         manager_getter!(instance_manager: InstanceManager);
         manager_getter!(meta_cache_manager: MetaCacheManager);
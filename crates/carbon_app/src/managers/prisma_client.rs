@@ -90,6 +90,34 @@ pub(super) async fn load_and_migrate(
             env!("CARGO_MANIFEST_DIR"),
             "/prisma/migrations/20241007094036_gdl_accounts/migration.sql"
         ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/prisma/migrations/20241018120000_download_limits/migration.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/prisma/migrations/20241019090000_download_journal/migration.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/prisma/migrations/20241020090000_proxy_settings/migration.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/prisma/migrations/20241020120000_http_cache_revalidation/migration.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/prisma/migrations/20241021090000_http_cache_eviction/migration.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/prisma/migrations/20241022090000_offline_mode/migration.sql"
+        ))),
+        M::up(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/prisma/migrations/20241024090000_mojang_mirror/migration.sql"
+        ))),
     ];
 
     let migrations = Migrations::new(migrations);
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{num::NonZeroU32, path::PathBuf};
 
 use anyhow::{anyhow, Context};
 use chrono::Utc;
@@ -15,6 +15,7 @@ use super::ManagerRef;
 
 use self::terms_and_privacy::TermsAndPrivacy;
 
+mod secrets;
 pub mod terms_and_privacy;
 
 pub(crate) struct SettingsManager {
@@ -35,6 +36,58 @@ impl SettingsManager {
     }
 }
 
+/// Applies the `downloadBandwidthLimit` setting to every download in the process.
+pub fn apply_download_bandwidth_limit(bytes_per_second: Option<i32>) {
+    carbon_net::set_bandwidth_limit(
+        bytes_per_second.and_then(|limit| NonZeroU32::new(limit.try_into().ok()?)),
+    );
+}
+
+/// Applies the `concurrentDownloadsPerHost` setting to every download in the process.
+pub fn apply_download_concurrency_per_host(concurrency_per_host: Option<i32>) {
+    carbon_net::set_concurrency_per_host(
+        concurrency_per_host.and_then(|limit| usize::try_from(limit).ok()),
+    );
+}
+
+/// Applies the proxy settings to every HTTP client in the process and to launched games.
+///
+/// Without a configured proxy the system environment variables are used instead.
+pub fn apply_proxy_settings(settings: &app_configuration::Data) {
+    let non_empty = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(ToString::to_string)
+    };
+
+    let config = carbon_net::ProxyConfig {
+        http: non_empty(&settings.proxy_http),
+        https: non_empty(&settings.proxy_https),
+        socks5: non_empty(&settings.proxy_socks),
+        username: non_empty(&settings.proxy_username),
+        password: settings.proxy_password.as_deref().and_then(|password| {
+            secrets::decrypt(&settings.secret, password)
+                .inspect_err(
+                    |e| tracing::error!({ error = ?e }, "Failed to read the proxy password"),
+                )
+                .ok()
+        }),
+        no_proxy: settings
+            .proxy_no_proxy
+            .split(',')
+            .map(str::trim)
+            .filter(|host| !host.is_empty())
+            .map(ToString::to_string)
+            .collect(),
+        ca_bundle: non_empty(&settings.proxy_ca_bundle).map(PathBuf::from),
+    };
+
+    let configured = config.http.is_some() || config.https.is_some() || config.socks5.is_some();
+    carbon_net::set_proxy(configured.then_some(config));
+}
+
 impl ManagerRef<'_, SettingsManager> {
     pub async fn get_settings(self) -> anyhow::Result<crate::db::app_configuration::Data> {
         self.app
@@ -139,6 +192,49 @@ impl ManagerRef<'_, SettingsManager> {
             ));
         }
 
+        if let Some(concurrent_downloads_per_host) =
+            incoming_settings.concurrent_downloads_per_host.clone()
+        {
+            queries.push(self.app.prisma_client.app_configuration().update(
+                app_configuration::id::equals(0),
+                vec![app_configuration::concurrent_downloads_per_host::set(
+                    concurrent_downloads_per_host.inner(),
+                )],
+            ));
+        }
+
+        if let Some(download_bandwidth_limit) = incoming_settings.download_bandwidth_limit.clone() {
+            queries.push(self.app.prisma_client.app_configuration().update(
+                app_configuration::id::equals(0),
+                vec![app_configuration::download_bandwidth_limit::set(
+                    download_bandwidth_limit.inner(),
+                )],
+            ));
+        }
+
+        if let Some(http_cache_size_limit) = incoming_settings.http_cache_size_limit.clone() {
+            queries.push(self.app.prisma_client.app_configuration().update(
+                app_configuration::id::equals(0),
+                vec![app_configuration::http_cache_size_limit::set(
+                    http_cache_size_limit.inner(),
+                )],
+            ));
+        }
+
+        if let Some(offline_mode) = incoming_settings.offline_mode.clone() {
+            queries.push(self.app.prisma_client.app_configuration().update(
+                app_configuration::id::equals(0),
+                vec![app_configuration::offline_mode::set(offline_mode.inner())],
+            ));
+        }
+
+        if let Some(mojang_mirror) = incoming_settings.mojang_mirror.clone() {
+            queries.push(self.app.prisma_client.app_configuration().update(
+                app_configuration::id::equals(0),
+                vec![app_configuration::mojang_mirror::set(mojang_mirror.inner())],
+            ));
+        }
+
         if let Some(download_dependencies) = incoming_settings.download_dependencies {
             queries.push(self.app.prisma_client.app_configuration().update(
                 app_configuration::id::equals(0),
@@ -323,6 +419,36 @@ impl ManagerRef<'_, SettingsManager> {
             ));
         }
 
+        let proxy_updated = incoming_settings.proxy.is_some();
+        if let Some(proxy) = incoming_settings.proxy {
+            let proxy = proxy.inner();
+
+            let mut updates = vec![
+                app_configuration::proxy_http::set(proxy.http),
+                app_configuration::proxy_https::set(proxy.https),
+                app_configuration::proxy_socks::set(proxy.socks5),
+                app_configuration::proxy_username::set(proxy.username),
+                app_configuration::proxy_no_proxy::set(proxy.no_proxy.join(",")),
+                app_configuration::proxy_ca_bundle::set(proxy.ca_bundle),
+            ];
+
+            if let Some(password) = proxy.password {
+                let password = password
+                    .inner()
+                    .map(|password| secrets::encrypt(&secret, &password))
+                    .transpose()?;
+
+                updates.push(app_configuration::proxy_password::set(password));
+            }
+
+            queries.push(
+                self.app
+                    .prisma_client
+                    .app_configuration()
+                    .update(app_configuration::id::equals(0), updates),
+            );
+        }
+
         if let Some(terms_and_privacy_accepted) = incoming_settings.terms_and_privacy_accepted {
             let terms_and_privacy_accepted = terms_and_privacy_accepted.inner();
             queries.push(self.app.prisma_client.app_configuration().update(
@@ -392,6 +518,30 @@ impl ManagerRef<'_, SettingsManager> {
                     show_app_close_warning.inner()
                 );
             }
+
+            if let Some(download_bandwidth_limit) = incoming_settings.download_bandwidth_limit {
+                apply_download_bandwidth_limit(download_bandwidth_limit.inner());
+            }
+
+            if let Some(concurrent_downloads_per_host) =
+                incoming_settings.concurrent_downloads_per_host
+            {
+                apply_download_concurrency_per_host(concurrent_downloads_per_host.inner());
+            }
+
+            if let Some(offline_mode) = incoming_settings.offline_mode {
+                self.app
+                    .connectivity_manager()
+                    .set_forced_offline(offline_mode.inner());
+            }
+
+            if incoming_settings.http_cache_size_limit.is_some() {
+                self.app.http_cache_manager().evict().await?;
+            }
+
+            if proxy_updated {
+                apply_proxy_settings(&self.get_settings().await?);
+            }
         }
 
         if let Some(auto_manage_java_system_profiles) =
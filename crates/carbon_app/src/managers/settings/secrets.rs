@@ -0,0 +1,71 @@
+//! Encryption of settings that must not be stored in plain text, like the proxy password.
+//!
+//! The key is derived from the random secret generated with the app configuration, so the
+//! values can only be read back by this installation.
+
+use anyhow::anyhow;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::digest::{digest, SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+
+fn key(secret: &[u8]) -> anyhow::Result<LessSafeKey> {
+    let key = digest(&SHA256, secret);
+    let key = UnboundKey::new(&CHACHA20_POLY1305, key.as_ref())
+        .map_err(|_| anyhow!("Invalid settings encryption key"))?;
+
+    Ok(LessSafeKey::new(key))
+}
+
+/// Encrypts `value`, the random nonce is stored in front of the ciphertext.
+pub fn encrypt(secret: &[u8], value: &str) -> anyhow::Result<Vec<u8>> {
+    let mut nonce = [0; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| anyhow!("Failed to generate a nonce"))?;
+
+    let mut data = value.as_bytes().to_vec();
+    key(secret)?
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+        .map_err(|_| anyhow!("Failed to encrypt the setting"))?;
+
+    Ok([nonce.as_slice(), &data].concat())
+}
+
+pub fn decrypt(secret: &[u8], data: &[u8]) -> anyhow::Result<String> {
+    if data.len() < NONCE_LEN {
+        return Err(anyhow!("Encrypted setting is too short"));
+    }
+
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce)
+        .map_err(|_| anyhow!("Invalid encrypted setting nonce"))?;
+
+    let mut ciphertext = ciphertext.to_vec();
+    let value = key(secret)?
+        .open_in_place(nonce, Aad::empty(), &mut ciphertext)
+        .map_err(|_| anyhow!("Failed to decrypt the setting"))?;
+
+    Ok(String::from_utf8(value.to_vec())?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decrypt, encrypt};
+
+    #[test]
+    fn test_roundtrip() {
+        let secret = [7; 256];
+
+        let encrypted = encrypt(&secret, "hunter2").unwrap();
+        assert!(!encrypted
+            .windows("hunter2".len())
+            .any(|window| window == b"hunter2"));
+        assert_eq!(decrypt(&secret, &encrypted).unwrap(), "hunter2");
+
+        // every value gets its own nonce
+        assert_ne!(encrypt(&secret, "hunter2").unwrap(), encrypted);
+
+        assert!(decrypt(&[8; 256], &encrypted).is_err());
+        assert!(decrypt(&secret, &encrypted[..4]).is_err());
+    }
+}
@@ -12,6 +12,7 @@ use anyhow::anyhow;
 use thiserror::Error;
 use tokio::sync::{watch, RwLock};
 use tracing::error;
+use url::Url;
 
 use super::ManagerRef;
 
@@ -221,6 +222,7 @@ impl VisualTask {
             weight: 1.0,
             started: false,
             progress: Progress::Opaque(false),
+            download_hosts: Vec::new(),
             checked_progress: Mutex::new(0.0),
         });
 
@@ -351,6 +353,7 @@ impl VisualTask {
                 .map(|t| domain::Subtask {
                     name: t.name.clone().into(),
                     progress: t.progress.into(),
+                    download_hosts: t.download_hosts.clone(),
                 })
                 .collect(),
         }
@@ -429,6 +432,19 @@ impl Subtask {
     pub fn set_weight(&self, weight: f32) {
         self.update(|data| data.weight = weight);
     }
+
+    pub fn add_download_host(&self, url: &str) {
+        let Some(host) = Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(ToString::to_string))
+        else {
+            return;
+        };
+
+        if !self.data.borrow().download_hosts.contains(&host) {
+            self.update(|data| data.download_hosts.push(host));
+        }
+    }
 }
 
 pub struct TaskData {
@@ -461,6 +477,8 @@ pub struct SubtaskData {
     /// Started tasks show in the task list if they are not also complete.
     pub started: bool,
     pub progress: Progress,
+    /// Hosts the subtask's files were downloaded from, in the order they were first used.
+    pub download_hosts: Vec<String>,
     /// Progress that has been checked into the main progress bar
     checked_progress: Mutex<f32>,
 }
@@ -545,6 +563,7 @@ mod test {
             active_subtasks: vec![domain::Subtask {
                 name: Translation::Test,
                 progress: domain::SubtaskProgress::Opaque,
+                download_hosts: Vec::new(),
             }],
         }];
 
@@ -567,6 +586,15 @@ mod test {
         };
         assert_eq!(tasks, app.task_manager().get_tasks().await);
 
+        subtask.add_download_host("https://mirror.example.com/a.jar");
+        subtask.add_download_host("https://cdn.example.com/b.jar");
+        subtask.add_download_host("https://mirror.example.com/c.jar");
+        tasks[0].active_subtasks[0].download_hosts = vec![
+            "mirror.example.com".to_string(),
+            "cdn.example.com".to_string(),
+        ];
+        assert_eq!(tasks, app.task_manager().get_tasks().await);
+
         drop(task);
         tasks.clear();
         // give the queue time to poll
@@ -1,3 +1,13 @@
+use std::path::{Component, Path};
+
 mod nwalkdir;
 
 pub use nwalkdir::NormalizedWalkdir;
+
+/// Checks that `path` is a non empty relative path that cannot escape the directory it is joined onto.
+pub fn is_safe_relative_path(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+}
@@ -0,0 +1,705 @@
+//! Archive creation, the counterpart of [`crate::decompress`].
+
+use std::fs::{File, Metadata};
+use std::io::{self, BufWriter, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use tracing::trace;
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+
+use crate::CompressionError;
+
+/// 1980-01-01, the earliest timestamp a zip archive can hold.
+pub const REPRODUCIBLE_MTIME: u64 = 315_532_800;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+    TarZst,
+}
+
+impl ArchiveFormat {
+    /// Guesses the format from the extension of `path`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar.zst") || name.ends_with(".tzst") {
+            Some(Self::TarZst)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CompressOptions {
+    pub format: ArchiveFormat,
+    /// Glob patterns matched against paths relative to the source directory, using `/` as the
+    /// separator. `*` stays within one path component, `**` crosses them. When empty every file
+    /// is included, otherwise only matching entries are written.
+    pub include: Vec<String>,
+    /// Glob patterns for entries to leave out, an excluded directory is skipped with all of its
+    /// contents.
+    pub exclude: Vec<String>,
+    /// Unix timestamp written for every entry, `None` keeps each file's modification time.
+    /// Setting it also normalizes ownership and permissions, so identical trees produce
+    /// identical archives.
+    pub mtime: Option<u64>,
+    /// Directory inside the archive the contents of the source directory are written to, empty
+    /// for the root. `include` and `exclude` still match paths relative to the source directory.
+    pub prefix: String,
+    /// Generated files written before the contents of the source directory, in order.
+    pub files: Vec<ArchiveFile>,
+}
+
+impl CompressOptions {
+    pub fn new(format: ArchiveFormat) -> Self {
+        Self {
+            format,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            mtime: None,
+            prefix: String::new(),
+            files: Vec::new(),
+        }
+    }
+
+    /// Options that produce byte for byte identical archives from identical trees.
+    pub fn reproducible(format: ArchiveFormat) -> Self {
+        Self {
+            mtime: Some(REPRODUCIBLE_MTIME),
+            ..Self::new(format)
+        }
+    }
+}
+
+/// A file whose contents are generated in memory instead of read from the source directory.
+#[derive(Debug, Clone)]
+pub struct ArchiveFile {
+    /// `/` separated path inside the archive
+    pub name: String,
+    pub contents: Vec<u8>,
+    pub executable: bool,
+}
+
+impl ArchiveFile {
+    pub fn new(name: impl Into<String>, contents: impl Into<Vec<u8>>) -> Self {
+        Self {
+            name: name.into(),
+            contents: contents.into(),
+            executable: false,
+        }
+    }
+
+    pub fn executable(self) -> Self {
+        Self {
+            executable: true,
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompressProgress {
+    pub entries_done: u64,
+    pub entries_total: u64,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+enum EntrySource<'a> {
+    Path { path: PathBuf, metadata: Metadata },
+    Generated(&'a ArchiveFile),
+}
+
+struct Entry<'a> {
+    /// path inside the archive, always `/` separated
+    name: String,
+    source: EntrySource<'a>,
+}
+
+impl Entry<'_> {
+    fn size(&self) -> u64 {
+        match &self.source {
+            EntrySource::Path { metadata, .. } if metadata.is_dir() => 0,
+            EntrySource::Path { metadata, .. } => metadata.len(),
+            EntrySource::Generated(file) => file.contents.len() as u64,
+        }
+    }
+}
+
+/// Escapes `literal` so it can be used as an `include` or `exclude` pattern matching only itself.
+pub fn escape_pattern(literal: &str) -> String {
+    globset::escape(literal)
+}
+
+pub(crate) fn glob_set(patterns: &[String]) -> Result<GlobSet, CompressionError> {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        builder.add(GlobBuilder::new(pattern).literal_separator(true).build()?);
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Lists the generated files followed by a walk of `source` in file name order, so the same
+/// tree always yields the same entries.
+fn collect_entries<'a>(
+    source: &Path,
+    dest: &Path,
+    options: &'a CompressOptions,
+) -> Result<Vec<Entry<'a>>, CompressionError> {
+    let include = glob_set(&options.include)?;
+    let exclude = glob_set(&options.exclude)?;
+
+    let mut entries = options
+        .files
+        .iter()
+        .map(|file| Entry {
+            name: file.name.clone(),
+            source: EntrySource::Generated(file),
+        })
+        .collect::<Vec<_>>();
+
+    let mut walker = WalkDir::new(source)
+        .min_depth(1)
+        .follow_links(true)
+        .sort_by_file_name()
+        .into_iter();
+
+    while let Some(entry) = walker.next() {
+        let entry = entry.map_err(io::Error::from)?;
+        let is_dir = entry.file_type().is_dir();
+
+        let Ok(relative) = entry.path().strip_prefix(source) else {
+            continue;
+        };
+
+        let name = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        // the archive itself may be written inside the tree it is built from
+        if entry.path() == dest || exclude.is_match(&name) {
+            if is_dir {
+                walker.skip_current_dir();
+            }
+            continue;
+        }
+
+        if !include.is_empty() && !include.is_match(&name) {
+            continue;
+        }
+
+        entries.push(Entry {
+            name: match options.prefix.as_str() {
+                "" => name,
+                prefix => format!("{}/{name}", prefix.trim_end_matches('/')),
+            },
+            source: EntrySource::Path {
+                path: entry.path().to_path_buf(),
+                metadata: entry.metadata().map_err(io::Error::from)?,
+            },
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Generated files are treated as modified when the archive is written.
+fn modified_secs(source: &EntrySource) -> Option<u64> {
+    let modified = match source {
+        EntrySource::Path { metadata, .. } => metadata.modified().ok()?,
+        EntrySource::Generated(_) => SystemTime::now(),
+    };
+
+    modified
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_secs())
+}
+
+/// Converts a unix timestamp to a zip timestamp, falling back to 1980-01-01 when out of range.
+fn zip_datetime(secs: u64) -> zip::DateTime {
+    // days to civil date, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = (secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let time = secs % 86_400;
+
+    zip::DateTime::from_date_and_time(
+        u16::try_from(year).unwrap_or(0),
+        month as u8,
+        day as u8,
+        (time / 3_600) as u8,
+        (time % 3_600 / 60) as u8,
+        (time % 60) as u8,
+    )
+    .unwrap_or_default()
+}
+
+fn zip_permissions(source: &EntrySource, normalize: bool) -> Option<u32> {
+    let metadata = match source {
+        EntrySource::Path { metadata, .. } => metadata,
+        EntrySource::Generated(file) if file.executable => return Some(0o755),
+        EntrySource::Generated(_) => return Some(0o644),
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = metadata.permissions().mode();
+
+        match normalize {
+            true if metadata.is_dir() || mode & 0o100 != 0 => Some(0o755),
+            true => Some(0o644),
+            false => Some(mode & 0o7777),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        match normalize {
+            true if metadata.is_dir() => Some(0o755),
+            true => Some(0o644),
+            false => None,
+        }
+    }
+}
+
+fn write_zip<W: Write + Seek>(
+    writer: W,
+    entries: &[Entry],
+    mtime: Option<u64>,
+    report: &mut impl FnMut(&Entry),
+) -> Result<W, CompressionError> {
+    let mut zip = zip::ZipWriter::new(writer);
+
+    for entry in entries {
+        let mut options = SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .last_modified_time(
+                mtime
+                    .or_else(|| modified_secs(&entry.source))
+                    .map(zip_datetime)
+                    .unwrap_or_default(),
+            )
+            .large_file(entry.size() >= u32::MAX as u64);
+
+        if let Some(mode) = zip_permissions(&entry.source, mtime.is_some()) {
+            options = options.unix_permissions(mode);
+        }
+
+        match &entry.source {
+            EntrySource::Path { metadata, .. } if metadata.is_dir() => {
+                zip.add_directory(entry.name.as_str(), options)?;
+            }
+            EntrySource::Path { path, .. } => {
+                zip.start_file(entry.name.as_str(), options)?;
+                io::copy(&mut File::open(path)?, &mut zip)?;
+            }
+            EntrySource::Generated(file) => {
+                zip.start_file(entry.name.as_str(), options)?;
+                zip.write_all(&file.contents)?;
+            }
+        }
+
+        report(entry);
+    }
+
+    Ok(zip.finish()?)
+}
+
+fn write_tar<W: Write>(
+    writer: W,
+    entries: &[Entry],
+    mtime: Option<u64>,
+    report: &mut impl FnMut(&Entry),
+) -> Result<W, CompressionError> {
+    let mut builder = tar::Builder::new(writer);
+
+    for entry in entries {
+        let mut header = tar::Header::new_gnu();
+        match &entry.source {
+            EntrySource::Path { metadata, .. } => header.set_metadata_in_mode(
+                metadata,
+                match mtime {
+                    Some(_) => tar::HeaderMode::Deterministic,
+                    None => tar::HeaderMode::Complete,
+                },
+            ),
+            EntrySource::Generated(file) => {
+                header.set_entry_type(tar::EntryType::Regular);
+                header.set_mode(if file.executable { 0o755 } else { 0o644 });
+                header.set_mtime(modified_secs(&entry.source).unwrap_or_default());
+            }
+        }
+        header.set_size(entry.size());
+        if let Some(mtime) = mtime {
+            header.set_mtime(mtime);
+        }
+
+        match &entry.source {
+            EntrySource::Path { metadata, .. } if metadata.is_dir() => {
+                builder.append_data(&mut header, &entry.name, io::empty())?;
+            }
+            EntrySource::Path { path, .. } => {
+                builder.append_data(&mut header, &entry.name, File::open(path)?)?;
+            }
+            EntrySource::Generated(file) => {
+                builder.append_data(&mut header, &entry.name, file.contents.as_slice())?;
+            }
+        }
+
+        report(entry);
+    }
+
+    Ok(builder.into_inner()?)
+}
+
+fn compress_blocking(
+    source: &Path,
+    dest: &Path,
+    options: &CompressOptions,
+    progress: &mut impl FnMut(CompressProgress),
+) -> Result<(), CompressionError> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let file = File::create(dest)?;
+    let entries = collect_entries(&source.canonicalize()?, &dest.canonicalize()?, options)?;
+
+    let mut state = CompressProgress {
+        entries_total: entries.len() as u64,
+        bytes_total: entries.iter().map(Entry::size).sum(),
+        ..Default::default()
+    };
+    progress(state);
+
+    let mut report = |entry: &Entry| {
+        trace!("Compressed {} ({} bytes)", entry.name, entry.size());
+
+        state.entries_done += 1;
+        state.bytes_done += entry.size();
+        progress(state);
+    };
+
+    let writer = BufWriter::new(file);
+
+    let mut writer = match options.format {
+        ArchiveFormat::Zip => write_zip(writer, &entries, options.mtime, &mut report)?,
+        ArchiveFormat::Tar => write_tar(writer, &entries, options.mtime, &mut report)?,
+        ArchiveFormat::TarGz => {
+            // the gzip header carries no timestamp unless one is set explicitly
+            let encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+            write_tar(encoder, &entries, options.mtime, &mut report)?.finish()?
+        }
+        ArchiveFormat::TarZst => {
+            let encoder = zstd::Encoder::new(writer, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+            write_tar(encoder, &entries, options.mtime, &mut report)?.finish()?
+        }
+    };
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Archives the contents of the `source` directory into `dest`.
+///
+/// Entries are written after `options.files` in file name order, with paths relative to `source`
+/// under `options.prefix`. `progress` is called
+/// once before the first entry and after every entry. A partially written archive is removed
+/// when compression fails.
+pub async fn compress<S, D>(
+    source: S,
+    dest: D,
+    options: CompressOptions,
+    mut progress: impl FnMut(CompressProgress) + Send + 'static,
+) -> Result<(), CompressionError>
+where
+    S: AsRef<Path> + Send + Sync,
+    D: AsRef<Path> + Send + Sync,
+{
+    let source = source.as_ref().to_path_buf();
+    let dest = dest.as_ref().to_path_buf();
+
+    let task_handler = tokio::task::spawn_blocking(move || {
+        trace!(
+            "Starting {:?} compression of {source:?} to {dest:?}",
+            options.format
+        );
+
+        let result = compress_blocking(&source, &dest, &options, &mut progress);
+
+        if result.is_err() {
+            let _ = std::fs::remove_file(&dest);
+        }
+
+        result
+    });
+
+    task_handler.await??;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    fn create_tree(root: &Path) {
+        for (path, contents) in [
+            ("options.txt", "fov:70"),
+            ("config/mod.toml", "enabled = true"),
+            ("mods/a.jar", "jar a"),
+            ("mods/b.jar", "jar b"),
+            ("logs/latest.log", "log"),
+        ] {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+    }
+
+    fn tar_entries(reader: impl Read) -> Vec<String> {
+        tar::Archive::new(reader)
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("backup.TAR.GZ")),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("logs.tar.zst")),
+            Some(ArchiveFormat::TarZst)
+        );
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("pack.zip")),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(ArchiveFormat::from_path(Path::new("notes.txt")), None);
+    }
+
+    #[test]
+    fn test_zip_datetime() {
+        assert_eq!(zip_datetime(REPRODUCIBLE_MTIME), zip::DateTime::default());
+
+        // 2023-11-14 22:13:20 UTC
+        let datetime = zip_datetime(1_700_000_000);
+        assert_eq!(
+            (
+                datetime.year(),
+                datetime.month(),
+                datetime.day(),
+                datetime.hour(),
+                datetime.minute(),
+                datetime.second()
+            ),
+            (2023, 11, 14, 22, 13, 20)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compress_roundtrip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source = temp_dir.path().join("instance");
+        create_tree(&source);
+
+        for (format, name) in [
+            (ArchiveFormat::Zip, "instance.zip"),
+            (ArchiveFormat::Tar, "instance.tar"),
+            (ArchiveFormat::TarGz, "instance.tar.gz"),
+        ] {
+            let archive = temp_dir.path().join(name);
+            compress(&source, &archive, CompressOptions::new(format), |_| {})
+                .await
+                .unwrap();
+
+            let dest = temp_dir.path().join(format!("{name}_extracted"));
+            crate::decompress(&archive, &dest).await.unwrap();
+
+            assert_eq!(
+                std::fs::read_to_string(dest.join("mods/b.jar")).unwrap(),
+                "jar b"
+            );
+            assert_eq!(
+                std::fs::read_to_string(dest.join("config/mod.toml")).unwrap(),
+                "enabled = true"
+            );
+        }
+
+        let archive = temp_dir.path().join("instance.tar.zst");
+        compress(
+            &source,
+            &archive,
+            CompressOptions::new(ArchiveFormat::TarZst),
+            |_| {},
+        )
+        .await
+        .unwrap();
+
+        let entries = tar_entries(zstd::Decoder::new(File::open(&archive).unwrap()).unwrap());
+        assert!(entries.contains(&"options.txt".to_string()));
+        assert!(entries.contains(&"mods/a.jar".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_compress_filters_and_progress() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        create_tree(temp_dir.path());
+
+        // written inside the tree it archives
+        let archive = temp_dir.path().join("export.tar");
+        let updates = Arc::new(Mutex::new(Vec::new()));
+
+        let options = CompressOptions {
+            include: vec!["**/*.jar".to_string(), "*.txt".to_string()],
+            exclude: vec!["mods/b.jar".to_string(), "logs".to_string()],
+            ..CompressOptions::new(ArchiveFormat::Tar)
+        };
+
+        let progress_updates = updates.clone();
+        compress(temp_dir.path(), &archive, options, move |progress| {
+            progress_updates.lock().unwrap().push(progress)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            tar_entries(File::open(&archive).unwrap()),
+            vec!["mods/a.jar", "options.txt"]
+        );
+
+        let updates = updates.lock().unwrap();
+        assert_eq!(updates.len(), 3);
+        assert_eq!(
+            updates.last(),
+            Some(&CompressProgress {
+                entries_done: 2,
+                entries_total: 2,
+                bytes_done: 11,
+                bytes_total: 11,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compress_is_reproducible() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let first = temp_dir.path().join("first");
+        let second = temp_dir.path().join("second");
+        create_tree(&first);
+        create_tree(&second);
+
+        for (format, name) in [
+            (ArchiveFormat::Zip, "instance.zip"),
+            (ArchiveFormat::TarGz, "instance.tar.gz"),
+            (ArchiveFormat::TarZst, "instance.tar.zst"),
+        ] {
+            let mut archives = Vec::new();
+
+            for source in [&first, &second] {
+                let archive = temp_dir.path().join(format!(
+                    "{}_{name}",
+                    source.file_name().unwrap().to_string_lossy()
+                ));
+                compress(
+                    source,
+                    &archive,
+                    CompressOptions::reproducible(format),
+                    |_| {},
+                )
+                .await
+                .unwrap();
+                archives.push(std::fs::read(archive).unwrap());
+            }
+
+            assert_eq!(archives[0], archives[1], "{name} differs");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compress_prefix_and_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source = temp_dir.path().join("instance");
+        create_tree(&source);
+        std::fs::write(source.join("mods/[1.20] c.jar"), "jar c").unwrap();
+
+        let archive = temp_dir.path().join("instance.zip");
+        let options = CompressOptions {
+            include: vec![format!("mods/{}", escape_pattern("[1.20] c.jar"))],
+            prefix: String::from(".minecraft"),
+            files: vec![
+                ArchiveFile::new("instance.cfg", "[General]"),
+                ArchiveFile::new("start.sh", "#!/bin/sh").executable(),
+            ],
+            ..CompressOptions::reproducible(ArchiveFormat::Zip)
+        };
+
+        compress(&source, &archive, options, |_| {}).await.unwrap();
+
+        let mut zip = zip::ZipArchive::new(File::open(&archive).unwrap()).unwrap();
+        assert_eq!(
+            zip.file_names().collect::<Vec<_>>(),
+            vec!["instance.cfg", "start.sh", ".minecraft/mods/[1.20] c.jar"]
+        );
+        assert_eq!(zip.by_name("start.sh").unwrap().unix_mode(), Some(0o100755));
+
+        let mut contents = String::new();
+        zip.by_name(".minecraft/mods/[1.20] c.jar")
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "jar c");
+    }
+
+    #[tokio::test]
+    async fn test_compress_invalid_pattern() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        create_tree(temp_dir.path());
+
+        let archive = temp_dir.path().join("out").join("broken.zip");
+        let options = CompressOptions {
+            exclude: vec!["mods/[".to_string()],
+            ..CompressOptions::new(ArchiveFormat::Zip)
+        };
+
+        let result = compress(temp_dir.path(), &archive, options, |_| {}).await;
+
+        assert!(matches!(result, Err(CompressionError::PatternError(_))));
+        assert!(!archive.exists());
+    }
+}
@@ -0,0 +1,837 @@
+//! Archive extraction with entry filters, size limits and progress reporting.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use globset::GlobSet;
+use tracing::trace;
+
+use crate::compress::glob_set;
+use crate::{
+    detect_compression_format, format_from_header, read_header, CompressionError, CompressionFormat,
+};
+
+// progress is reported at least this often while a large entry is written
+const PROGRESS_INTERVAL: u64 = 1024 * 1024;
+
+// symlinks stored in zip archives keep their target as the entry contents
+const MAX_LINK_LEN: u64 = 4096;
+
+/// Decides whether the entry with the given `/` separated path is extracted.
+pub type EntryFilter = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub struct ExtractOptions {
+    /// Glob patterns for the entries to extract, matched like
+    /// [`CompressOptions::include`](crate::CompressOptions::include). When empty every entry is
+    /// extracted.
+    pub include: Vec<String>,
+    /// Glob patterns for entries to leave out.
+    pub exclude: Vec<String>,
+    /// Called for every entry that passed the patterns.
+    pub filter: Option<EntryFilter>,
+    /// Fails the extraction once more than this many bytes were written.
+    pub max_bytes: Option<u64>,
+    /// Fails the extraction once more than this many entries were extracted.
+    pub max_entries: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExtractProgress {
+    pub entries_done: u64,
+    /// uncompressed bytes written to the destination
+    pub bytes_written: u64,
+    /// how far into the archive file extraction got, the only total every format knows upfront
+    pub archive_read: u64,
+    pub archive_size: u64,
+}
+
+/// Tracks how far into the archive file the decoders have read.
+struct PositionReader<R> {
+    inner: R,
+    position: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for PositionReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.position.fetch_add(read as u64, Ordering::Relaxed);
+        Ok(read)
+    }
+}
+
+impl<R: Seek> Seek for PositionReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = self.inner.seek(pos)?;
+        self.position.store(position, Ordering::Relaxed);
+        Ok(position)
+    }
+}
+
+/// Normalizes an entry name to a relative path, `None` when it would leave the destination.
+fn enclosed_name(name: &str) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+
+    for component in Path::new(&name.replace('\\', "/")).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    Some(path)
+}
+
+/// Resolves the target of a symlink placed in the canonical directory `parent`.
+///
+/// Symlinks already on disk are followed. `..` is only allowed while every component so far is
+/// a real directory, those can't be swapped for symlinks by later entries, so the resolved path
+/// stays valid for the rest of the extraction. `None` for absolute targets and `..` after a
+/// symlink or a path that doesn't exist yet.
+fn resolve_link(parent: &Path, link: &str) -> Option<PathBuf> {
+    let mut path = parent.to_path_buf();
+    let mut real_dirs = true;
+
+    for component in Path::new(&link.replace('\\', "/")).components() {
+        match component {
+            Component::Normal(part) => {
+                path.push(part);
+
+                if !path
+                    .symlink_metadata()
+                    .is_ok_and(|metadata| metadata.is_dir())
+                {
+                    real_dirs = false;
+                }
+
+                if let Ok(canonical) = path.canonicalize() {
+                    path = canonical;
+                }
+            }
+            Component::CurDir => {}
+            Component::ParentDir if real_dirs => {
+                path.pop();
+            }
+            _ => return None,
+        }
+    }
+
+    Some(path)
+}
+
+struct Target {
+    /// normalized `/` separated entry path, what filters are matched against
+    name: String,
+    path: PathBuf,
+}
+
+struct Extractor<'a> {
+    /// canonical, so resolved parents can be compared against it
+    dest: PathBuf,
+    include: GlobSet,
+    exclude: GlobSet,
+    filter: Option<EntryFilter>,
+    max_bytes: Option<u64>,
+    max_entries: Option<u64>,
+    archive_position: Arc<AtomicU64>,
+    state: ExtractProgress,
+    reported_bytes: u64,
+    progress: &'a mut dyn FnMut(ExtractProgress),
+}
+
+impl Extractor<'_> {
+    /// Resolves where an entry goes, `None` when it is filtered out.
+    fn target(&self, name: &str) -> Result<Option<Target>, CompressionError> {
+        let relative =
+            enclosed_name(name).ok_or_else(|| CompressionError::UnsafePath(name.to_string()))?;
+
+        let name = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        // the archive root itself, as in `./`
+        if name.is_empty() {
+            return Ok(None);
+        }
+
+        let included = (self.include.is_empty() || self.include.is_match(&name))
+            && !self.exclude.is_match(&name)
+            && match &self.filter {
+                Some(filter) => filter(&name),
+                None => true,
+            };
+
+        Ok(included.then(|| Target {
+            path: self.dest.join(&relative),
+            name,
+        }))
+    }
+
+    fn report(&mut self) {
+        self.state.archive_read = self
+            .archive_position
+            .load(Ordering::Relaxed)
+            .min(self.state.archive_size);
+        self.reported_bytes = self.state.bytes_written;
+
+        (self.progress)(self.state);
+    }
+
+    fn begin_entry(&mut self, target: &Target) -> Result<(), CompressionError> {
+        if let Some(max_entries) = self.max_entries {
+            if self.state.entries_done >= max_entries {
+                return Err(CompressionError::EntryLimitExceeded(max_entries));
+            }
+        }
+
+        let parent = target.path.parent().unwrap_or(&self.dest);
+        std::fs::create_dir_all(parent)?;
+
+        // catches parents that are symlinks placed by earlier entries
+        if !parent.canonicalize()?.starts_with(&self.dest) {
+            return Err(CompressionError::UnsafePath(target.name.clone()));
+        }
+
+        match target.path.symlink_metadata() {
+            Ok(metadata) if !metadata.is_dir() => std::fs::remove_file(&target.path)?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn finish_entry(&mut self, target: &Target) {
+        trace!("Extracted {} to {}", target.name, target.path.display());
+
+        self.state.entries_done += 1;
+        self.report();
+    }
+
+    fn create_dir(&mut self, target: &Target) -> Result<(), CompressionError> {
+        self.begin_entry(target)?;
+        std::fs::create_dir_all(&target.path)?;
+        self.finish_entry(target);
+
+        Ok(())
+    }
+
+    fn write_file(
+        &mut self,
+        target: &Target,
+        reader: &mut dyn Read,
+        mode: Option<u32>,
+    ) -> Result<(), CompressionError> {
+        self.begin_entry(target)?;
+
+        let mut file = File::create(&target.path)?;
+        let mut buf = vec![0; 64 * 1024];
+
+        loop {
+            let read = match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+
+            self.state.bytes_written += read as u64;
+
+            if let Some(max_bytes) = self.max_bytes {
+                if self.state.bytes_written > max_bytes {
+                    drop(file);
+                    let _ = std::fs::remove_file(&target.path);
+                    return Err(CompressionError::SizeLimitExceeded(max_bytes));
+                }
+            }
+
+            file.write_all(&buf[..read])?;
+
+            if self.state.bytes_written - self.reported_bytes >= PROGRESS_INTERVAL {
+                self.report();
+            }
+        }
+
+        #[cfg(unix)]
+        if let Some(mode) = mode {
+            use std::os::unix::fs::PermissionsExt;
+
+            file.set_permissions(std::fs::Permissions::from_mode(mode & 0o777))?;
+        }
+
+        #[cfg(not(unix))]
+        let _ = mode;
+
+        self.finish_entry(target);
+
+        Ok(())
+    }
+
+    fn create_symlink(&mut self, target: &Target, link: &str) -> Result<(), CompressionError> {
+        self.begin_entry(target)?;
+
+        // relative targets are resolved from the directory the link really ends up in, which
+        // may itself be behind a symlink placed by an earlier entry
+        let parent = target.path.parent().unwrap_or(&self.dest).canonicalize()?;
+        if !resolve_link(&parent, link).is_some_and(|resolved| resolved.starts_with(&self.dest)) {
+            return Err(CompressionError::UnsafePath(target.name.clone()));
+        }
+
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(link, &target.path)?;
+
+        // creating symlinks needs extra privileges on windows
+        #[cfg(not(unix))]
+        trace!("Skipping symlink {} -> {link}", target.name);
+
+        self.finish_entry(target);
+
+        Ok(())
+    }
+
+    fn create_hard_link(&mut self, target: &Target, link: &str) -> Result<(), CompressionError> {
+        let source = enclosed_name(link)
+            .map(|relative| self.dest.join(relative))
+            .ok_or_else(|| CompressionError::UnsafePath(target.name.clone()))?;
+
+        // the source path may go through symlinks placed by earlier entries
+        let source = source.canonicalize()?;
+        if !source.starts_with(&self.dest) {
+            return Err(CompressionError::UnsafePath(target.name.clone()));
+        }
+
+        self.begin_entry(target)?;
+        std::fs::hard_link(source, &target.path)?;
+        self.finish_entry(target);
+
+        Ok(())
+    }
+}
+
+fn extract_zip<R>(reader: R, extractor: &mut Extractor) -> Result<(), CompressionError>
+where
+    R: Read + Seek,
+{
+    let mut archive = zip::ZipArchive::new(reader)?;
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let Some(target) = extractor.target(file.name())? else {
+            continue;
+        };
+
+        if file.is_dir() {
+            extractor.create_dir(&target)?;
+        } else if file.is_symlink() {
+            let mut link = String::new();
+            file.by_ref().take(MAX_LINK_LEN).read_to_string(&mut link)?;
+            extractor.create_symlink(&target, &link)?;
+        } else {
+            let mode = file.unix_mode();
+            extractor.write_file(&target, &mut file, mode)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn extract_tar<R>(reader: R, extractor: &mut Extractor) -> Result<(), CompressionError>
+where
+    R: Read,
+{
+    let mut archive = tar::Archive::new(reader);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+
+        let name = String::from_utf8_lossy(&entry.path_bytes()).to_string();
+        let Some(target) = extractor.target(&name)? else {
+            continue;
+        };
+
+        let entry_type = entry.header().entry_type();
+        let link = entry
+            .link_name_bytes()
+            .map(|link| String::from_utf8_lossy(&link).to_string())
+            .unwrap_or_default();
+
+        if entry_type.is_dir() {
+            extractor.create_dir(&target)?;
+        } else if entry_type.is_symlink() {
+            extractor.create_symlink(&target, &link)?;
+        } else if entry_type.is_hard_link() {
+            extractor.create_hard_link(&target, &link)?;
+        } else if entry_type.is_file() || entry_type.is_contiguous() {
+            let mode = entry.header().mode().ok();
+            extractor.write_file(&target, &mut entry, mode)?;
+        } else {
+            trace!("Skipping tar entry {name} of type {entry_type:?}");
+        }
+    }
+
+    Ok(())
+}
+
+fn extract_7z_entry(
+    extractor: &mut Extractor,
+    entry: &sevenz_rust::SevenZArchiveEntry,
+    reader: &mut dyn Read,
+) -> Result<(), CompressionError> {
+    let target = match entry.is_anti_item() {
+        true => None,
+        false => extractor.target(entry.name())?,
+    };
+
+    match target {
+        Some(target) if entry.is_directory() => extractor.create_dir(&target),
+        Some(target) => extractor.write_file(&target, reader, None),
+        // entries of a solid block share one stream, so skipped ones still have to be read
+        None => {
+            io::copy(reader, &mut io::sink())?;
+            Ok(())
+        }
+    }
+}
+
+fn extract_7z<R>(reader: R, extractor: &mut Extractor) -> Result<(), CompressionError>
+where
+    R: Read + Seek,
+{
+    let dest = extractor.dest.clone();
+    let mut error = None;
+
+    sevenz_rust::decompress_with_extract_fn(
+        reader,
+        dest,
+        |entry, reader, _| match extract_7z_entry(extractor, entry, reader) {
+            Ok(()) => Ok(true),
+            Err(e) => {
+                error = Some(e);
+                Ok(false)
+            }
+        },
+    )?;
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+fn stream_decoder<'a>(
+    format: &CompressionFormat,
+    reader: Box<dyn Read + 'a>,
+) -> Result<Box<dyn Read + 'a>, CompressionError> {
+    Ok(match format {
+        CompressionFormat::Gzip => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+        CompressionFormat::Zstd => Box::new(zstd::Decoder::new(reader)?),
+        CompressionFormat::Xz => Box::new(xz2::read::XzDecoder::new_multi_decoder(reader)),
+        CompressionFormat::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(reader)),
+        _ => return Err(CompressionError::UnknownFormat),
+    })
+}
+
+/// Decodes a compressed stream, unpacking it when it holds a tar archive and decoding it again
+/// when it holds another compressed stream. Anything else is extracted as a single file named
+/// after the archive without its extension.
+fn extract_stream(
+    format: &CompressionFormat,
+    reader: Box<dyn Read + '_>,
+    file_name: &str,
+    extractor: &mut Extractor,
+) -> Result<(), CompressionError> {
+    let mut decoder = stream_decoder(format, reader)?;
+
+    let header = read_header(&mut decoder)?;
+    let inner_format = format_from_header(&header);
+    let mut reader: Box<dyn Read> = Box::new(io::Cursor::new(header).chain(decoder));
+
+    let file_name = Path::new(file_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or_else(|| "decompressed".to_string());
+
+    trace!("Decoded {format:?} stream contains {inner_format:?}");
+
+    match inner_format {
+        Ok(CompressionFormat::Tar) => extract_tar(reader, extractor),
+        Ok(inner_format) if inner_format.is_stream() => {
+            extract_stream(&inner_format, reader, &file_name, extractor)
+        }
+        _ => match extractor.target(&file_name)? {
+            Some(target) => extractor.write_file(&target, &mut reader, None),
+            None => Ok(()),
+        },
+    }
+}
+
+fn extract_blocking(
+    path: &Path,
+    dest_folder: &Path,
+    options: &ExtractOptions,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<ExtractProgress, CompressionError> {
+    let mut file = File::open(path)?;
+    let format = detect_compression_format(&mut file)?;
+    file.seek(SeekFrom::Start(0))?;
+
+    trace!("Starting extraction of {path:?} to {dest_folder:?}");
+    trace!("Detected compression format: {format:?}");
+
+    std::fs::create_dir_all(dest_folder)?;
+
+    let archive_position = Arc::new(AtomicU64::new(0));
+    let mut extractor = Extractor {
+        dest: dest_folder.canonicalize()?,
+        include: glob_set(&options.include)?,
+        exclude: glob_set(&options.exclude)?,
+        filter: options.filter.clone(),
+        max_bytes: options.max_bytes,
+        max_entries: options.max_entries,
+        archive_position: archive_position.clone(),
+        state: ExtractProgress {
+            archive_size: file.metadata()?.len(),
+            ..Default::default()
+        },
+        reported_bytes: 0,
+        progress,
+    };
+
+    let reader = BufReader::new(PositionReader {
+        inner: file,
+        position: archive_position,
+    });
+
+    match format {
+        CompressionFormat::Zip => extract_zip(reader, &mut extractor)?,
+        CompressionFormat::SevenZip => extract_7z(reader, &mut extractor)?,
+        CompressionFormat::Tar => extract_tar(reader, &mut extractor)?,
+        CompressionFormat::Gzip
+        | CompressionFormat::Zstd
+        | CompressionFormat::Xz
+        | CompressionFormat::Bzip2 => {
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            extract_stream(&format, Box::new(reader), &file_name, &mut extractor)?;
+        }
+    }
+
+    extractor.report();
+    extractor.state.archive_read = extractor.state.archive_size;
+    (extractor.progress)(extractor.state);
+
+    Ok(extractor.state)
+}
+
+/// Extracts the entries of the archive at `path` selected by `options` into `dest_folder`.
+///
+/// Entries that would end up outside of `dest_folder`, through `..`, absolute paths or
+/// symlinks, fail the extraction. `progress` is called after every entry and regularly while
+/// large entries are written, the last call always reports the whole archive as read.
+pub async fn extract<T>(
+    path: T,
+    dest_folder: &Path,
+    options: ExtractOptions,
+    mut progress: impl FnMut(ExtractProgress) + Send + 'static,
+) -> Result<ExtractProgress, CompressionError>
+where
+    T: AsRef<Path> + Send + Sync,
+{
+    let path = path.as_ref().to_path_buf();
+    let dest_folder = dest_folder.to_path_buf();
+
+    let task_handler = tokio::task::spawn_blocking(move || {
+        extract_blocking(&path, &dest_folder, &options, &mut progress)
+    });
+
+    task_handler.await?
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{compress, ArchiveFormat, CompressOptions};
+
+    async fn create_archive(root: &Path, format: ArchiveFormat, name: &str) -> PathBuf {
+        let source = root.join("source");
+        for (path, contents) in [
+            ("options.txt", "fov:70"),
+            ("mods/a.jar", "jar a"),
+            ("mods/b.jar", "jar b"),
+            ("config/mod.toml", "enabled = true"),
+        ] {
+            let path = source.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+
+        let archive = root.join(name);
+        compress(&source, &archive, CompressOptions::new(format), |_| {})
+            .await
+            .unwrap();
+
+        archive
+    }
+
+    /// A tar with a single entry whose name is written as is, bypassing the builder's checks.
+    fn raw_tar(path: &Path, name: &str, entry_type: tar::EntryType, link: Option<&str>) {
+        let mut header = tar::Header::new_gnu();
+        header.as_gnu_mut().unwrap().name[..name.len()].copy_from_slice(name.as_bytes());
+        header.set_entry_type(entry_type);
+        header.set_mode(0o644);
+
+        let data = b"evil";
+        match link {
+            Some(link) => {
+                header.set_link_name(link).unwrap();
+                header.set_size(0);
+            }
+            None => header.set_size(data.len() as u64),
+        }
+        header.set_cksum();
+
+        let mut builder = tar::Builder::new(File::create(path).unwrap());
+        match link {
+            Some(_) => builder.append(&header, io::empty()).unwrap(),
+            None => builder.append(&header, &data[..]).unwrap(),
+        }
+        builder.finish().unwrap();
+    }
+
+    #[test]
+    fn test_enclosed_name() {
+        assert_eq!(
+            enclosed_name("mods/./a.jar"),
+            Some(PathBuf::from("mods").join("a.jar"))
+        );
+        assert_eq!(
+            enclosed_name("mods\\a.jar"),
+            Some(PathBuf::from("mods").join("a.jar"))
+        );
+        assert_eq!(enclosed_name("../escape.txt"), None);
+        assert_eq!(enclosed_name("/etc/passwd"), None);
+    }
+
+    #[test]
+    fn test_resolve_link() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let lib = root.join("lib");
+        std::fs::create_dir(&lib).unwrap();
+
+        assert_eq!(
+            resolve_link(&lib, "../bin/java"),
+            Some(root.join("bin").join("java"))
+        );
+        assert_eq!(
+            resolve_link(&lib, "jdk/bin"),
+            Some(lib.join("jdk").join("bin"))
+        );
+        assert!(!resolve_link(&lib, "../../etc").unwrap().starts_with(&root));
+        assert_eq!(resolve_link(&lib, "/etc"), None);
+
+        // `..` can't be used after a path that may still change
+        assert_eq!(resolve_link(&lib, "jdk/../bin"), None);
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink("..", lib.join("up")).unwrap();
+            assert_eq!(resolve_link(&lib, "up"), Some(root.clone()));
+            assert_eq!(resolve_link(&lib, "up/.."), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extract_filters_and_progress() {
+        let temp_dir = tempdir().unwrap();
+
+        for (format, name) in [
+            (ArchiveFormat::Zip, "pack.zip"),
+            (ArchiveFormat::TarZst, "pack.tar.zst"),
+        ] {
+            let archive = create_archive(temp_dir.path(), format, name).await;
+            let dest = temp_dir.path().join(format!("{name}_extracted"));
+
+            let updates = Arc::new(Mutex::new(Vec::new()));
+            let progress_updates = updates.clone();
+
+            let options = ExtractOptions {
+                include: vec!["mods/**".to_string(), "*.txt".to_string()],
+                filter: Some(Arc::new(|name| name != "mods/b.jar")),
+                ..Default::default()
+            };
+
+            let summary = extract(&archive, &dest, options, move |progress| {
+                progress_updates.lock().unwrap().push(progress)
+            })
+            .await
+            .unwrap();
+
+            assert_eq!(
+                std::fs::read_to_string(dest.join("mods/a.jar")).unwrap(),
+                "jar a"
+            );
+            assert!(dest.join("options.txt").exists());
+            assert!(!dest.join("mods/b.jar").exists());
+            assert!(!dest.join("config").exists());
+
+            assert_eq!(summary.entries_done, 2);
+            assert_eq!(summary.bytes_written, 11);
+            assert_eq!(summary.archive_read, summary.archive_size);
+            assert_eq!(updates.lock().unwrap().last(), Some(&summary));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extract_limits() {
+        let temp_dir = tempdir().unwrap();
+        let archive = create_archive(temp_dir.path(), ArchiveFormat::Zip, "pack.zip").await;
+
+        let options = ExtractOptions {
+            max_entries: Some(2),
+            ..Default::default()
+        };
+        let result = extract(&archive, &temp_dir.path().join("entries"), options, |_| {}).await;
+        assert!(matches!(
+            result,
+            Err(CompressionError::EntryLimitExceeded(2))
+        ));
+
+        let options = ExtractOptions {
+            max_bytes: Some(10),
+            ..Default::default()
+        };
+        let result = extract(&archive, &temp_dir.path().join("bytes"), options, |_| {}).await;
+        assert!(matches!(
+            result,
+            Err(CompressionError::SizeLimitExceeded(10))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_extract_rejects_traversal() {
+        let temp_dir = tempdir().unwrap();
+        let dest = temp_dir.path().join("dest");
+
+        let tar_path = temp_dir.path().join("evil.tar");
+        raw_tar(&tar_path, "../evil.txt", tar::EntryType::Regular, None);
+
+        let zip_path = temp_dir.path().join("evil.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        zip.start_file("../evil.txt", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"evil").unwrap();
+        zip.finish().unwrap();
+
+        for archive in [tar_path, zip_path] {
+            let result = extract(&archive, &dest, ExtractOptions::default(), |_| {}).await;
+
+            assert!(
+                matches!(result, Err(CompressionError::UnsafePath(_))),
+                "{archive:?}"
+            );
+            assert!(!temp_dir.path().join("evil.txt").exists());
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_extract_symlinks() {
+        let temp_dir = tempdir().unwrap();
+        let dest = temp_dir.path().join("dest");
+
+        let escaping = temp_dir.path().join("escaping.tar");
+        raw_tar(&escaping, "lib", tar::EntryType::Symlink, Some("../.."));
+
+        let result = extract(&escaping, &dest, ExtractOptions::default(), |_| {}).await;
+        assert!(matches!(result, Err(CompressionError::UnsafePath(_))));
+
+        let enclosed = temp_dir.path().join("enclosed.tar");
+        let mut builder = tar::Builder::new(File::create(&enclosed).unwrap());
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(4);
+        header.set_mode(0o755);
+        builder
+            .append_data(&mut header, "jdk/bin/java", &b"java"[..])
+            .unwrap();
+
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        builder.append_link(&mut header, "bin", "jdk/bin").unwrap();
+        builder.finish().unwrap();
+        drop(builder);
+
+        extract(&enclosed, &dest, ExtractOptions::default(), |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dest.join("bin/java")).unwrap(),
+            "java"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_extract_rejects_chained_symlinks() {
+        let temp_dir = tempdir().unwrap();
+        let dest = temp_dir.path().join("extract").join("dest");
+        std::fs::write(temp_dir.path().join("secret.txt"), "secret").unwrap();
+
+        let chained = temp_dir.path().join("chained.tar");
+        let mut builder = tar::Builder::new(File::create(&chained).unwrap());
+
+        for (name, link, entry_type) in [
+            ("a/b", "..", tar::EntryType::Symlink),
+            ("a/b/c", "../..", tar::EntryType::Symlink),
+            ("stolen", "c/secret.txt", tar::EntryType::Link),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(entry_type);
+            header.set_size(0);
+            builder.append_link(&mut header, name, link).unwrap();
+        }
+
+        builder.finish().unwrap();
+        drop(builder);
+
+        let result = extract(&chained, &dest, ExtractOptions::default(), |_| {}).await;
+
+        assert!(matches!(result, Err(CompressionError::UnsafePath(_))));
+        assert!(dest.join("c").symlink_metadata().is_err());
+        assert!(!dest.join("stolen").exists());
+
+        // the hard link alone can't reach through a symlink out of the destination either
+        let dest = temp_dir.path().join("extract").join("linked");
+        std::fs::create_dir_all(&dest).unwrap();
+        std::os::unix::fs::symlink(temp_dir.path(), dest.join("c")).unwrap();
+
+        let hard_link = temp_dir.path().join("hard_link.tar");
+        raw_tar(
+            &hard_link,
+            "stolen",
+            tar::EntryType::Link,
+            Some("c/secret.txt"),
+        );
+
+        let result = extract(&hard_link, &dest, ExtractOptions::default(), |_| {}).await;
+
+        assert!(matches!(result, Err(CompressionError::UnsafePath(_))));
+        assert!(!dest.join("stolen").exists());
+    }
+}
@@ -1,9 +1,17 @@
 use std::{
-    io::{Read, Seek},
+    io::{self, Read},
     path::Path,
 };
 use thiserror::Error;
-use tracing::trace;
+
+mod compress;
+mod extract;
+
+pub use compress::{
+    compress, escape_pattern, ArchiveFile, ArchiveFormat, CompressOptions, CompressProgress,
+    REPRODUCIBLE_MTIME,
+};
+pub use extract::{extract, EntryFilter, ExtractOptions, ExtractProgress};
 
 #[derive(Error, Debug)]
 pub enum CompressionError {
@@ -15,155 +23,76 @@ pub enum CompressionError {
     GenericDecompressionError(#[from] tokio::task::JoinError),
     #[error("Failed to process zip file: {0}")]
     ZipError(#[from] zip::result::ZipError),
+    #[error("Failed to process 7z file: {0}")]
+    SevenZipError(#[from] sevenz_rust::Error),
+    #[error("Invalid file pattern: {0}")]
+    PatternError(#[from] globset::Error),
+    #[error("Archive entry {0} would be extracted outside of the destination")]
+    UnsafePath(String),
+    #[error("Archive extracts to more than {0} bytes")]
+    SizeLimitExceeded(u64),
+    #[error("Archive has more than {0} entries")]
+    EntryLimitExceeded(u64),
 }
 
 #[derive(Debug, PartialEq)]
 enum CompressionFormat {
     Zip,
+    SevenZip,
     Tar,
     Gzip,
+    Zstd,
+    Xz,
+    Bzip2,
 }
 
 impl CompressionFormat {
     fn from_bytes(bytes: &[u8]) -> Result<Self, CompressionError> {
         match bytes {
             [0x50, 0x4B, 0x03, 0x04, ..] => Ok(Self::Zip),
+            [0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C, ..] => Ok(Self::SevenZip),
             [0x1F, 0x8B, ..] => Ok(Self::Gzip),
+            [0x28, 0xB5, 0x2F, 0xFD, ..] => Ok(Self::Zstd),
+            [0xFD, 0x37, 0x7A, 0x58, 0x5A, 0x00, ..] => Ok(Self::Xz),
+            [0x42, 0x5A, 0x68, b'1'..=b'9', ..] => Ok(Self::Bzip2),
             [.., 0x75, 0x73, 0x74, 0x61, 0x72] => Ok(Self::Tar),
             _ => Err(CompressionError::UnknownFormat),
         }
     }
-}
 
-fn detect_compression_format(file: &mut impl Read) -> Result<CompressionFormat, CompressionError> {
-    let mut header = [0; 262]; // Magic number to hold the max possible offset (tar) of 257 bytes + 5 relevant bytes
-    let _bytes = file.read(&mut header)?;
-    if let Ok(format) = CompressionFormat::from_bytes(&header) {
-        return Ok(format);
+    /// Formats that compress a single stream of data, usually a tar archive.
+    fn is_stream(&self) -> bool {
+        matches!(self, Self::Gzip | Self::Zstd | Self::Xz | Self::Bzip2)
     }
-
-    Err(CompressionError::UnknownFormat)
 }
 
-fn decompress_zip<R>(archive: &mut zip::ZipArchive<R>, dest: &Path) -> Result<(), CompressionError>
-where
-    R: Read + Seek,
-{
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i)?;
-        let outpath = match file.enclosed_name() {
-            Some(path) => dest.join(path),
-            None => continue,
-        };
-
-        {
-            let comment = file.comment();
-            if !comment.is_empty() {
-                trace!("File {i} comment: {comment}");
-            }
-        }
-
-        if (*file.name()).ends_with('/') {
-            trace!("File {} extracted to \"{}\"", i, outpath.display());
-            std::fs::create_dir_all(&outpath)?;
-        } else {
-            trace!(
-                "File {} extracted to \"{}\" ({} bytes)",
-                i,
-                outpath.display(),
-                file.size()
-            );
-            if let Some(p) = outpath.parent() {
-                if !p.exists() {
-                    std::fs::create_dir_all(p)?;
-                }
-            }
-            let mut outfile = std::fs::File::create(&outpath)?;
-            std::io::copy(&mut file, &mut outfile)?;
-        }
-
-        // Get and Set permissions
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-
-            if let Some(mode) = file.unix_mode() {
-                std::fs::set_permissions(&outpath, std::fs::Permissions::from_mode(mode))?;
-            }
-        }
-    }
-
-    Ok(())
-}
+// Magic number to hold the max possible offset (tar) of 257 bytes + 5 relevant bytes
+const HEADER_LEN: usize = 262;
 
-fn decompress_tar<R>(
-    archive: &mut tar::Archive<R>,
-    dest_folder: &Path,
-) -> Result<(), CompressionError>
-where
-    R: Read,
-{
-    archive.unpack(dest_folder)?;
-    Ok(())
+/// Reads up to [`HEADER_LEN`] bytes, decoders may hand them out over several reads.
+fn read_header(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    reader.take(HEADER_LEN as u64).read_to_end(&mut header)?;
+    Ok(header)
 }
 
-fn decompress_gzip(archive_path: &Path, dest_folder: &Path) -> Result<(), CompressionError> {
-    let archive_file = std::fs::File::open(&archive_path)?;
-    let mut archive = flate2::read::GzDecoder::new(archive_file);
-
-    let format = detect_compression_format(&mut archive);
-    // Redeclare because it's been consumed by detect_compression_format
-    let archive_file = std::fs::File::open(archive_path)?;
-    let archive = flate2::read::GzDecoder::new(archive_file);
-
-    if let Ok(format) = format {
-        if CompressionFormat::Tar == format {
-            let mut archive = tar::Archive::new(archive);
-            decompress_tar(&mut archive, dest_folder)?;
-            return Ok(());
-        } else {
-            unimplemented!("{format:?} is not supported yet")
-        }
-    }
+fn format_from_header(header: &[u8]) -> Result<CompressionFormat, CompressionError> {
+    let mut padded = [0; HEADER_LEN];
+    padded[..header.len()].copy_from_slice(header);
 
-    unimplemented!("Gzip is not supported yet");
+    CompressionFormat::from_bytes(&padded)
 }
 
-// TODO: Ideally decompression would recursively look for another compressed file until it can't find any more.
+fn detect_compression_format(file: &mut impl Read) -> Result<CompressionFormat, CompressionError> {
+    format_from_header(&read_header(file)?)
+}
 
-// accept both paths and strings
+/// Extracts the whole archive at `path` into `dest_folder`, see [`extract`] for more control.
 pub async fn decompress<T>(path: T, dest_folder: &Path) -> Result<(), CompressionError>
 where
     T: AsRef<Path> + Send + Sync,
 {
-    let path_clone = path.as_ref().to_path_buf();
-    let dest_folder_clone = dest_folder.to_path_buf();
-
-    let task_handler = tokio::task::spawn_blocking(move || {
-        let mut file = std::fs::File::open(&path_clone)?;
-        let file_clone = std::fs::File::open(&path_clone)?;
-        let format = detect_compression_format(&mut file)?;
-        trace!("Starting compression for file: {path_clone:?} to {dest_folder_clone:?}");
-        trace!("Detected compression format: {format:?}");
-
-        match format {
-            CompressionFormat::Zip => {
-                let mut archive = zip::ZipArchive::new(file_clone)?;
-                decompress_zip(&mut archive, &dest_folder_clone)?;
-            }
-            CompressionFormat::Tar => {
-                let mut archive = tar::Archive::new(file_clone);
-                decompress_tar(&mut archive, &dest_folder_clone)?;
-            }
-            CompressionFormat::Gzip => {
-                decompress_gzip(&path_clone, &dest_folder_clone)?;
-            }
-        }
-
-        Ok::<(), CompressionError>(())
-    });
-
-    task_handler.await??;
+    extract(path, dest_folder, ExtractOptions::default(), |_| {}).await?;
 
     Ok(())
 }
@@ -189,6 +118,21 @@ mod tests {
         let mut file = std::fs::File::open("fixtures/compressed.gz").unwrap();
         let format = detect_compression_format(&mut file).unwrap();
         assert_eq!(CompressionFormat::Gzip, format);
+
+        for (fixture, expected) in [
+            ("compressed.tar.zst", CompressionFormat::Zstd),
+            ("compressed.tar.xz", CompressionFormat::Xz),
+            ("compressed.xz", CompressionFormat::Xz),
+            ("compressed.tar.bz2", CompressionFormat::Bzip2),
+            ("compressed.7z", CompressionFormat::SevenZip),
+        ] {
+            let mut file = std::fs::File::open(Path::new("fixtures").join(fixture)).unwrap();
+            let format = detect_compression_format(&mut file).unwrap();
+            assert_eq!(expected, format, "{fixture}");
+        }
+
+        let mut file = std::fs::File::open("fixtures/compressed.txt").unwrap();
+        assert!(detect_compression_format(&mut file).is_err());
     }
 
     #[tokio::test]
@@ -212,12 +156,81 @@ mod tests {
         decompress(&file_path, &dest_folder).await.unwrap();
     }
 
-    // Not supported yet.. :(
+    #[tokio::test]
+    async fn test_decompress_gzip() {
+        let file_path = Path::new("fixtures/compressed.gz");
+        let dest_folder = Path::new("tests_decompressed/gzip");
+        decompress(file_path, dest_folder).await.unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dest_folder.join("compressed")).unwrap(),
+            "Some compressed file"
+        );
+    }
 
-    // #[tokio::test]
-    // async fn test_decompress_gzip() {
-    //     let file_path = PathBuf::from("fixtures/compressed.gz");
-    //     let dest_folder = PathBuf::from("tests_decompressed/gzip");
-    //     decompress(&file_path, &dest_folder).await.unwrap();
-    // }
+    #[tokio::test]
+    async fn test_decompress_xz() {
+        let file_path = Path::new("fixtures/compressed.xz");
+        let dest_folder = Path::new("tests_decompressed/xz");
+        decompress(file_path, dest_folder).await.unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dest_folder.join("compressed")).unwrap(),
+            "Some compressed file"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decompress_compressed_tars() {
+        for (fixture, folder) in [
+            ("compressed.tar.zst", "tar_zstd"),
+            ("compressed.tar.xz", "tar_xz"),
+            ("compressed.tar.bz2", "tar_bzip2"),
+        ] {
+            let file_path = Path::new("fixtures").join(fixture);
+            let dest_folder = Path::new("tests_decompressed").join(folder);
+            decompress(&file_path, &dest_folder).await.unwrap();
+
+            assert_eq!(
+                std::fs::read_to_string(dest_folder.join("compressed.txt")).unwrap(),
+                "Some compressed file",
+                "{fixture}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decompress_7z() {
+        let file_path = Path::new("fixtures/compressed.7z");
+        let dest_folder = Path::new("tests_decompressed/7z");
+        decompress(file_path, dest_folder).await.unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dest_folder.join("compressed.txt")).unwrap(),
+            "Some compressed file"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decompress_nested_streams() {
+        // a zstd compressed tar compressed again with xz
+        let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+        std::io::copy(
+            &mut std::fs::File::open("fixtures/compressed.tar.zst").unwrap(),
+            &mut encoder,
+        )
+        .unwrap();
+
+        std::fs::create_dir_all("tests_decompressed").unwrap();
+        let file_path = Path::new("tests_decompressed/compressed.tar.zst.xz");
+        std::fs::write(file_path, encoder.finish().unwrap()).unwrap();
+
+        let dest_folder = Path::new("tests_decompressed/nested");
+        decompress(file_path, dest_folder).await.unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dest_folder.join("compressed.txt")).unwrap(),
+            "Some compressed file"
+        );
+    }
 }
@@ -1,28 +1,165 @@
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
 use md5::Digest as Md5Digest;
 use md5::Md5;
+use murmurhash32::Murmur2Digest;
 use reqwest::Client;
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use sha1::Sha1;
-use sha2::Sha256;
+use sha2::{Sha256, Sha512};
 use std::collections::HashMap;
 use std::ffi::OsString;
 use std::fmt::Display;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::io::SeekFrom;
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{LazyLock, PoisonError};
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
 use thiserror::Error;
 use tokio::fs::File;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::watch::Sender;
-use tokio::sync::{Mutex, Semaphore};
+use tokio::sync::{Mutex, OwnedMutexGuard, OwnedSemaphorePermit, Semaphore};
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info, instrument, warn};
+use tracing::{error, info, instrument, trace, warn};
+
+pub mod proxy;
+pub mod store;
+
+pub use proxy::{set_proxy, ProxyConfig};
+pub use store::{ContentStore, StoreUsage};
 
 const PART_POSTFIX: &str = ".__gdl_part~";
 
+const DEFAULT_SEGMENTS: usize = 4;
+const DEFAULT_SEGMENT_THRESHOLD: u64 = 8 * 1024 * 1024;
+
+/// Shared by every download in the process so the cap holds across concurrent `download_multiple` calls.
+static BANDWIDTH_LIMITER: std::sync::RwLock<Option<Arc<BandwidthLimiter>>> =
+    std::sync::RwLock::new(None);
+
+struct BandwidthLimiter {
+    bytes_per_second: NonZeroU32,
+    limiter: DefaultDirectRateLimiter,
+}
+
+impl BandwidthLimiter {
+    fn new(bytes_per_second: NonZeroU32) -> Self {
+        Self {
+            bytes_per_second,
+            limiter: RateLimiter::direct(Quota::per_second(bytes_per_second)),
+        }
+    }
+
+    async fn until_ready(&self, bytes: usize) {
+        let mut remaining = bytes;
+
+        // the limiter can't hand out more than one second worth of bytes at once
+        while remaining > 0 {
+            let n = remaining.min(self.bytes_per_second.get() as usize);
+            remaining -= n;
+
+            let n = NonZeroU32::new(n as u32).expect("n is never zero");
+            let _ = self.limiter.until_n_ready(n).await;
+        }
+    }
+}
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// While offline, files that are not already on disk fail with [`DownloadError::Offline`]
+/// instead of being requested.
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Caps the combined speed of all downloads in the process, `None` removes the cap.
+pub fn set_bandwidth_limit(bytes_per_second: Option<NonZeroU32>) {
+    let limiter = bytes_per_second.map(|limit| Arc::new(BandwidthLimiter::new(limit)));
+    *BANDWIDTH_LIMITER
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = limiter;
+}
+
+async fn throttle(bytes: usize) {
+    let limiter = BANDWIDTH_LIMITER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+
+    if let Some(limiter) = limiter {
+        limiter.until_ready(bytes).await;
+    }
+}
+
+/// Shared by every download in the process so the per host cap holds across concurrent
+/// `download_multiple` calls.
+static HOST_LIMITS: std::sync::RwLock<Option<Arc<HostLimits>>> = std::sync::RwLock::new(None);
+
+/// Limits how many files are downloaded from the same host at once.
+struct HostLimits {
+    per_host: usize,
+    semaphores: std::sync::Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl HostLimits {
+    fn new(per_host: usize) -> Self {
+        Self {
+            per_host,
+            semaphores: Default::default(),
+        }
+    }
+
+    async fn acquire(&self, url: &str) -> Result<Option<OwnedSemaphorePermit>, DownloadError> {
+        let Some(host) = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+        else {
+            return Ok(None);
+        };
+
+        let semaphore = self
+            .semaphores
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(host)
+            .or_insert_with(|| Arc::new(Semaphore::new(self.per_host)))
+            .clone();
+
+        Ok(Some(semaphore.acquire_owned().await?))
+    }
+}
+
+/// Caps how many files are downloaded from the same host at once, `None` removes the cap.
+///
+/// Downloads already holding a permit keep it until they finish.
+pub fn set_concurrency_per_host(concurrency_per_host: Option<usize>) {
+    let limits = concurrency_per_host
+        .filter(|&limit| limit > 0)
+        .map(|limit| Arc::new(HostLimits::new(limit)));
+    *HOST_LIMITS.write().unwrap_or_else(PoisonError::into_inner) = limits;
+}
+
+async fn acquire_host_permit(url: &str) -> Result<Option<OwnedSemaphorePermit>, DownloadError> {
+    let limits = HOST_LIMITS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+
+    match limits {
+        Some(limits) => limits.acquire(url).await,
+        None => Ok(None),
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum DownloadError {
     #[error("Failed to download {0}")]
@@ -58,43 +195,66 @@ pub enum DownloadError {
     CannotCreateDirectory(String),
     #[error("Failed to atomically move file: {0}")]
     CannotMoveFile(std::io::Error, String, String),
+    #[error("Cannot download {0} while offline")]
+    Offline(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Checksum {
     Sha1(String),
     Sha256(String),
+    Sha512(String),
     Md5(String),
+    /// CurseForge fingerprint: murmur2 with seed 1 over the file with whitespace bytes removed
+    Murmur2(u32),
+}
+
+impl Checksum {
+    fn expected(&self) -> String {
+        match self {
+            Checksum::Sha1(hash)
+            | Checksum::Sha256(hash)
+            | Checksum::Sha512(hash)
+            | Checksum::Md5(hash) => hash.to_lowercase(),
+            Checksum::Murmur2(hash) => hash.to_string(),
+        }
+    }
 }
 
 enum HashDigest {
     Sha256(Sha256),
+    Sha512(Sha512),
     Sha1(sha1::Sha1),
     Md5(md5::Md5),
+    // murmur2 is seeded with the normalized length, so the streaming pass can only count it
+    Murmur2Len(u64),
+}
+
+enum HashOutput {
+    Digest(Vec<u8>),
+    Murmur2Len(u64),
 }
 
 impl HashDigest {
     fn update(&mut self, data: &[u8]) {
         match self {
             HashDigest::Sha256(h) => h.update(data),
+            HashDigest::Sha512(h) => h.update(data),
             HashDigest::Sha1(h) => h.update(data),
             HashDigest::Md5(h) => h.update(data),
+            HashDigest::Murmur2Len(len) => {
+                *len += data.iter().filter(|&&b| !is_murmur2_whitespace(b)).count() as u64
+            }
         }
     }
 
-    fn finalize(self) -> Vec<u8> {
-        match self {
-            HashDigest::Sha256(h) => h.finalize().to_vec(),
-            HashDigest::Sha1(h) => h.finalize().to_vec(),
-            HashDigest::Md5(h) => h.finalize().to_vec(),
-        }
-    }
-
-    fn finalize_reset(&mut self) -> Vec<u8> {
+    fn finalize_reset(&mut self) -> HashOutput {
         match self {
-            HashDigest::Sha256(h) => h.finalize_reset().to_vec(),
-            HashDigest::Sha1(h) => h.finalize_reset().to_vec(),
-            HashDigest::Md5(h) => h.finalize_reset().to_vec(),
+            HashDigest::Sha256(h) => HashOutput::Digest(h.finalize_reset().to_vec()),
+            HashDigest::Sha512(h) => HashOutput::Digest(h.finalize_reset().to_vec()),
+            HashDigest::Sha1(h) => HashOutput::Digest(h.finalize_reset().to_vec()),
+            HashDigest::Md5(h) => HashOutput::Digest(h.finalize_reset().to_vec()),
+            HashDigest::Murmur2Len(len) => HashOutput::Murmur2Len(std::mem::take(len)),
         }
     }
 }
@@ -103,12 +263,90 @@ impl From<&Checksum> for HashDigest {
     fn from(value: &Checksum) -> Self {
         match value {
             Checksum::Sha256(_) => HashDigest::Sha256(Sha256::new()),
+            Checksum::Sha512(_) => HashDigest::Sha512(Sha512::new()),
             Checksum::Sha1(_) => HashDigest::Sha1(Sha1::new()),
             Checksum::Md5(_) => HashDigest::Md5(Md5::new()),
+            Checksum::Murmur2(_) => HashDigest::Murmur2Len(0),
         }
     }
 }
 
+/// Computes every checksum of a [`Downloadable`] in a single pass over the data.
+struct Hasher(Vec<HashDigest>);
+
+impl Hasher {
+    fn new(checksums: &[Checksum]) -> Self {
+        Self(checksums.iter().map(HashDigest::from).collect())
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for digest in &mut self.0 {
+            digest.update(data);
+        }
+    }
+
+    fn finalize_reset(&mut self) -> Vec<HashOutput> {
+        self.0.iter_mut().map(HashDigest::finalize_reset).collect()
+    }
+}
+
+fn is_murmur2_whitespace(b: u8) -> bool {
+    matches!(b, 9 | 10 | 13 | 32)
+}
+
+/// Hashes `path` with CurseForge's murmur2 variant, `normalized_len` being the non whitespace byte count.
+async fn murmur2_file(path: &Path, normalized_len: u64) -> Result<u32, DownloadError> {
+    let mut file = File::open(path).await?;
+    let mut buffer = vec![0; 8192];
+    let mut normalized = Vec::with_capacity(buffer.len());
+    let mut digest = Murmur2Digest::new(normalized_len as u32);
+
+    loop {
+        let bytes_read = file.read(&mut buffer).await?;
+        if bytes_read == 0 {
+            break;
+        }
+
+        normalized.clear();
+        normalized.extend(
+            buffer[..bytes_read]
+                .iter()
+                .filter(|&&b| !is_murmur2_whitespace(b)),
+        );
+        digest.update(&normalized);
+    }
+
+    Ok(digest.finalize())
+}
+
+/// Compares the hasher outputs for the file at `path` against every expected checksum.
+async fn verify_checksums(
+    path: &Path,
+    url: &str,
+    checksums: &[Checksum],
+    outputs: Vec<HashOutput>,
+) -> Result<(), DownloadError> {
+    for (checksum, output) in checksums.iter().zip(outputs) {
+        let actual = match output {
+            HashOutput::Digest(digest) => hex::encode(digest),
+            HashOutput::Murmur2Len(len) => murmur2_file(path, len).await?.to_string(),
+        };
+
+        let expected = checksum.expected();
+        if actual != expected {
+            error!("Checksum mismatch: expected {}, got {}", expected, actual);
+            return Err(DownloadError::ChecksumMismatch {
+                expected,
+                actual,
+                url: url.to_string(),
+                path: path.to_string_lossy().to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
 pub trait IntoVecDownloadable {
     fn into_vec_downloadable(self, base_path: &Path) -> Vec<Downloadable>;
 }
@@ -120,8 +358,11 @@ pub trait IntoDownloadable {
 #[derive(Debug, Clone)]
 pub struct Downloadable {
     pub url: String,
+    /// tried in order after `url` fails
+    pub mirrors: Vec<String>,
     pub path: PathBuf,
-    pub checksum: Option<Checksum>,
+    /// all of these have to match, they are computed in the same pass
+    pub checksums: Vec<Checksum>,
     pub size: Option<u64>,
 }
 
@@ -135,14 +376,20 @@ impl Downloadable {
     pub fn new(url: impl Into<String>, path: impl AsRef<Path>) -> Self {
         Self {
             url: url.into(),
+            mirrors: Vec::new(),
             path: path.as_ref().into(),
-            checksum: None,
+            checksums: Vec::new(),
             size: None,
         }
     }
 
     pub fn with_checksum(mut self, checksum: Option<Checksum>) -> Self {
-        self.checksum = checksum;
+        self.checksums.extend(checksum);
+        self
+    }
+
+    pub fn with_checksums(mut self, checksums: impl IntoIterator<Item = Checksum>) -> Self {
+        self.checksums.extend(checksums);
         self
     }
 
@@ -150,6 +397,16 @@ impl Downloadable {
         self.size = Some(size);
         self
     }
+
+    pub fn with_mirrors(mut self, mirrors: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.mirrors.extend(mirrors.into_iter().map(Into::into));
+        self
+    }
+
+    /// The primary url followed by the mirrors.
+    pub fn urls(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.url.as_str()).chain(self.mirrors.iter().map(String::as_str))
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -160,12 +417,24 @@ pub struct Progress {
     pub current_size: u64,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServedBy {
+    pub path: PathBuf,
+    /// the url or mirror the file was downloaded from
+    pub url: String,
+}
+
 impl Progress {
     pub fn new() -> Self {
         Self::default()
     }
 }
 
+/// Shared by every download in the process, so the same path is never written by two
+/// `download_multiple` calls at once. This matters most for store entries, which are shared
+/// by every instance.
+static FILE_PATH_LOCK: LazyLock<FilePathLock> = LazyLock::new(FilePathLock::new);
+
 struct FilePathLock {
     locks: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>,
 }
@@ -177,12 +446,19 @@ impl FilePathLock {
         }
     }
 
-    async fn lock(&self, path: &PathBuf) -> Arc<Mutex<()>> {
-        let mut locks = self.locks.lock().await;
-        locks
-            .entry(path.clone())
-            .or_insert_with(|| Arc::new(Mutex::new(())))
-            .clone()
+    async fn lock(&self, path: &Path) -> OwnedMutexGuard<()> {
+        // This only locks for the acquisition of the file lock
+        let file_lock = {
+            let mut locks = self.locks.lock().await;
+            // locks nobody holds or waits for anymore are dropped so the map doesn't grow forever
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            locks
+                .entry(path.to_path_buf())
+                .or_insert_with(|| Arc::new(Mutex::new(())))
+                .clone()
+        };
+
+        file_lock.lock_owned().await
     }
 }
 
@@ -194,6 +470,10 @@ pub struct DownloadOptions {
     deep_check: bool,
     max_retries: u32,
     progress_sender: Option<Sender<Progress>>,
+    served_by_sender: Option<UnboundedSender<ServedBy>>,
+    store: Option<ContentStore>,
+    segments: usize,
+    segment_threshold: u64,
 }
 
 impl Default for DownloadOptions {
@@ -205,6 +485,10 @@ impl Default for DownloadOptions {
             deep_check: true,
             max_retries: 3,
             progress_sender: None,
+            served_by_sender: None,
+            store: None,
+            segments: DEFAULT_SEGMENTS,
+            segment_threshold: DEFAULT_SEGMENT_THRESHOLD,
         }
     }
 }
@@ -223,6 +507,10 @@ pub struct DownloadOptionsBuilder {
     deep_check: Option<bool>,
     max_retries: Option<u32>,
     progress_sender: Option<Sender<Progress>>,
+    served_by_sender: Option<UnboundedSender<ServedBy>>,
+    store: Option<ContentStore>,
+    segments: Option<usize>,
+    segment_threshold: Option<u64>,
 }
 
 impl DownloadOptionsBuilder {
@@ -256,6 +544,31 @@ impl DownloadOptionsBuilder {
         self
     }
 
+    /// Receives the url every downloaded file was served by, files that were already valid
+    /// are not reported
+    pub fn served_by_sender(mut self, served_by_sender: UnboundedSender<ServedBy>) -> Self {
+        self.served_by_sender = Some(served_by_sender);
+        self
+    }
+
+    /// Files with a SHA-1 or SHA-512 checksum are fetched into the store and linked into place
+    pub fn store(mut self, store: ContentStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Number of concurrent ranged requests used for large files, `1` disables segmenting
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = Some(segments);
+        self
+    }
+
+    /// Files with a known size below this many bytes are always downloaded over one connection
+    pub fn segment_threshold(mut self, segment_threshold: u64) -> Self {
+        self.segment_threshold = Some(segment_threshold);
+        self
+    }
+
     pub fn build(self) -> DownloadOptions {
         DownloadOptions {
             concurrency: self.concurrency.unwrap_or(4),
@@ -264,24 +577,32 @@ impl DownloadOptionsBuilder {
             deep_check: self.deep_check.unwrap_or(false),
             max_retries: self.max_retries.unwrap_or(3),
             progress_sender: self.progress_sender,
+            served_by_sender: self.served_by_sender,
+            store: self.store,
+            segments: self.segments.unwrap_or(DEFAULT_SEGMENTS).max(1),
+            segment_threshold: self.segment_threshold.unwrap_or(DEFAULT_SEGMENT_THRESHOLD),
         }
     }
 }
 
+/// State shared by every file of a single `download_multiple` call.
+struct DownloadContext {
+    options: DownloadOptions,
+    client: ClientWithMiddleware,
+    semaphore: Semaphore,
+    total_downloaded_size: AtomicU64,
+    current_files_count: AtomicU64,
+    total_files_size: u64,
+    total_files_count: u64,
+}
+
 #[instrument(skip(files, options))]
 pub async fn download_multiple(
     files: &[Downloadable],
     options: DownloadOptions,
 ) -> Result<bool, DownloadError> {
-    let client = create_client(&options);
-    let semaphore = Arc::new(Semaphore::new(options.concurrency));
-    // only one file with the same path can be downloaded concurrently to avoid race conditions
-    let file_path_lock = Arc::new(FilePathLock::new());
-
     let total_files_size: u64 = files.iter().filter_map(|f| f.size).sum();
-    let total_downloaded_size = Arc::new(AtomicU64::new(0));
     let total_files_count = files.len() as u64;
-    let current_files_count = Arc::new(AtomicU64::new(0));
 
     info!(
         "Starting processing of {} files - {} MB",
@@ -298,22 +619,31 @@ pub async fn download_multiple(
         });
     }
 
-    let tasks = create_download_tasks(
-        files,
-        &options,
-        &client,
-        &semaphore,
-        &total_downloaded_size,
-        &current_files_count,
+    let only_validate = options.only_validate;
+    let progress_sender = options.progress_sender.clone();
+
+    let ctx = Arc::new(DownloadContext {
+        client: create_client(&options),
+        semaphore: Semaphore::new(options.concurrency),
+        total_downloaded_size: AtomicU64::new(0),
+        current_files_count: AtomicU64::new(0),
         total_files_size,
         total_files_count,
-        &file_path_lock,
-    );
+        options,
+    });
+
+    // keeps the store from being garbage collected before the downloaded entries are linked
+    let _store_in_use = match &ctx.options.store {
+        Some(store) => Some(store.in_use().read_owned().await),
+        None => None,
+    };
+
+    let tasks = create_download_tasks(files, &ctx);
 
     process_download_tasks(
         tasks,
-        options.only_validate,
-        &options.progress_sender,
+        only_validate,
+        &progress_sender,
         total_files_count,
         total_files_size,
     )
@@ -322,297 +652,374 @@ pub async fn download_multiple(
 
 fn create_client(options: &DownloadOptions) -> ClientWithMiddleware {
     let retry_policy = ExponentialBackoff::builder().build_with_max_retries(options.max_retries);
-    ClientBuilder::new(Client::new())
+    let client = proxy::client_builder().build().unwrap_or_else(|e| {
+        error!("Failed to build the download client with the proxy settings: {e}");
+        Client::new()
+    });
+
+    ClientBuilder::new(client)
         .with(RetryTransientMiddleware::new_with_policy(retry_policy))
         .build()
 }
 
 fn create_download_tasks(
     files: &[Downloadable],
-    options: &DownloadOptions,
-    client: &ClientWithMiddleware,
-    semaphore: &Arc<Semaphore>,
-    total_downloaded_size: &Arc<AtomicU64>,
-    current_files_count: &Arc<AtomicU64>,
-    total_files_size: u64,
-    total_files_count: u64,
-    file_path_lock: &Arc<FilePathLock>,
+    ctx: &Arc<DownloadContext>,
 ) -> Vec<tokio::task::JoinHandle<Result<(), DownloadError>>> {
     files
         .iter()
         .map(|file| {
-            let semaphore = Arc::clone(semaphore);
-            let client = client.clone();
-            let options = options.clone();
+            let ctx = Arc::clone(ctx);
             let file = file.clone();
-            let total_downloaded_size = Arc::clone(total_downloaded_size);
-            let current_files_count = Arc::clone(current_files_count);
-            let file_path_lock = Arc::clone(file_path_lock);
-
-            tokio::spawn(async move {
-                let _permit = semaphore.acquire().await?;
-                process_file(
-                    file,
-                    options,
-                    client,
-                    &total_downloaded_size,
-                    &current_files_count,
-                    total_files_size,
-                    total_files_count,
-                    &file_path_lock,
-                )
-                .await
-            })
+
+            tokio::spawn(async move { process_file(file, &ctx).await })
         })
         .collect()
 }
 
-#[instrument(skip(
-    options,
-    client,
-    total_downloaded_size,
-    current_files_count,
-    file_path_lock
-))]
+#[instrument(skip(ctx))]
 async fn process_file(
     downloadable: Downloadable,
-    options: DownloadOptions,
-    client: ClientWithMiddleware,
-    total_downloaded_size: &AtomicU64,
-    current_files_count: &AtomicU64,
-    total_files_size: u64,
-    total_files_count: u64,
-    file_path_lock: &FilePathLock,
+    ctx: &DownloadContext,
 ) -> Result<(), DownloadError> {
+    let permit = ctx.semaphore.acquire().await?;
+
     let validation = validate_file(
         &downloadable.path,
         downloadable.size,
-        &downloadable.checksum,
-        options.deep_check,
+        &downloadable.checksums,
+        ctx.options.deep_check,
     )
     .await;
 
     match validation {
-        Err(err) if options.only_validate => {
+        Err(err) if ctx.options.only_validate => {
             return Err(err);
         }
+        Err(_) => {}
+        Ok(_) => {
+            report_existing_file(&downloadable, ctx);
+            return Ok(());
+        }
+    }
+
+    let store_path = ctx
+        .options
+        .store
+        .as_ref()
+        .and_then(|store| store.path_for(&downloadable.checksums));
+
+    // path locks are always taken before download slots, waiting on one while holding a slot
+    // could starve the download holding the lock
+    drop(permit);
+
+    let Some(store_path) = store_path else {
+        // only one file with the same path can be downloaded concurrently to avoid race conditions
+        let _guard = FILE_PATH_LOCK.lock(&downloadable.path).await;
+        return download_file(downloadable, ctx).await;
+    };
+
+    // held until the entry is linked, so concurrent installs of the same file download it once
+    let _guard = FILE_PATH_LOCK.lock(&store_path).await;
+
+    let permit = ctx.semaphore.acquire().await?;
+    let store_validation = validate_file(
+        &store_path,
+        downloadable.size,
+        &downloadable.checksums,
+        ctx.options.deep_check,
+    )
+    .await;
+    // the download takes a slot again once its host is free, see `download_file`
+    drop(permit);
+
+    match store_validation {
+        Ok(_) => report_existing_file(&downloadable, ctx),
         Err(_) => {
             download_file(
-                downloadable,
-                options,
-                client,
-                total_downloaded_size,
-                current_files_count,
-                total_files_size,
-                total_files_count,
-                file_path_lock,
+                Downloadable {
+                    path: store_path.clone(),
+                    ..downloadable.clone()
+                },
+                ctx,
             )
-            .await
+            .await?
         }
-        Ok(_) => {
-            if let Some(size) = downloadable.size {
-                total_downloaded_size.fetch_add(size, Ordering::SeqCst);
-            }
-            current_files_count.fetch_add(1, Ordering::SeqCst);
-            if let Some(sender) = &options.progress_sender {
-                let progress = Progress {
-                    total_count: total_files_count,
-                    current_count: current_files_count.load(Ordering::SeqCst),
-                    total_size: total_files_size,
-                    current_size: total_downloaded_size.load(Ordering::SeqCst),
-                };
+    }
 
-                let _ = sender.send(progress);
-            }
-            return Ok(());
-        }
+    let target = downloadable.path.clone();
+    let link_kind =
+        tokio::task::spawn_blocking(move || store::link_file(&store_path, &target)).await??;
+
+    trace!(
+        "Linked {} from the store ({:?})",
+        downloadable.path.to_string_lossy(),
+        link_kind
+    );
+
+    Ok(())
+}
+
+fn report_existing_file(downloadable: &Downloadable, ctx: &DownloadContext) {
+    if let Some(size) = downloadable.size {
+        ctx.total_downloaded_size.fetch_add(size, Ordering::SeqCst);
+    }
+    ctx.current_files_count.fetch_add(1, Ordering::SeqCst);
+    if let Some(sender) = &ctx.options.progress_sender {
+        let progress = Progress {
+            total_count: ctx.total_files_count,
+            current_count: ctx.current_files_count.load(Ordering::SeqCst),
+            total_size: ctx.total_files_size,
+            current_size: ctx.total_downloaded_size.load(Ordering::SeqCst),
+        };
+
+        let _ = sender.send(progress);
     }
 }
 
-#[instrument(skip(
-    options,
-    client,
-    total_downloaded_size,
-    current_files_count,
-    file_path_lock
-))]
+#[instrument(skip(ctx))]
 async fn download_file(
     downloadable: Downloadable,
-    options: DownloadOptions,
-    client: ClientWithMiddleware,
-    total_downloaded_size: &AtomicU64,
-    current_files_count: &AtomicU64,
-    total_files_size: u64,
-    total_files_count: u64,
-    file_path_lock: &FilePathLock,
+    ctx: &DownloadContext,
 ) -> Result<(), DownloadError> {
-    // This only locks for the acquisition of the file lock
-    let file_lock = file_path_lock.lock(&downloadable.path).await;
-    // acquire the lock for the file so no other file with the same path can be downloaded concurrently
-    let _guard = file_lock.lock().await;
+    if is_offline() {
+        return Err(DownloadError::Offline(downloadable.url));
+    }
 
     let file_processed_bytes = AtomicU64::new(0);
 
+    let progress = ctx.options.progress_sender.as_ref();
+
+    // a partial file can only be continued from another mirror if the result can be verified
+    let can_resume_across_mirrors =
+        downloadable.size.is_some() || !downloadable.checksums.is_empty();
+
+    let mut last_error = None;
+
+    for (mirror_index, url) in downloadable.urls().enumerate() {
+        if mirror_index > 0 {
+            warn!(
+                "Falling back to mirror {} for {}",
+                url,
+                downloadable.path.to_string_lossy()
+            );
+        }
+
+        // the host permit comes first so files queued on a busy host don't hold download slots
+        let host_permit = acquire_host_permit(url).await?;
+        let permit = ctx.semaphore.acquire().await?;
+
+        let outcome = download_from_url(
+            url,
+            mirror_index > 0 && !can_resume_across_mirrors,
+            &downloadable,
+            ctx,
+            &file_processed_bytes,
+        )
+        .await;
+
+        drop(permit);
+        drop(host_permit);
+
+        match outcome {
+            Ok(part_file_path) => {
+                tokio::fs::rename(&part_file_path, &downloadable.path)
+                    .await
+                    .map_err(|e| {
+                        DownloadError::CannotMoveFile(
+                            e,
+                            part_file_path.to_string_lossy().to_string(),
+                            downloadable.path.to_string_lossy().to_string(),
+                        )
+                    })?;
+
+                info!(
+                    "Downloaded {} from {}",
+                    downloadable.path.to_string_lossy(),
+                    url
+                );
+
+                ctx.current_files_count.fetch_add(1, Ordering::SeqCst);
+                if let Some(sender) = &ctx.options.served_by_sender {
+                    let _ = sender.send(ServedBy {
+                        path: downloadable.path.clone(),
+                        url: url.to_string(),
+                    });
+                }
+                if let Some(sender) = progress {
+                    let _ = sender.send(Progress {
+                        total_count: ctx.total_files_count,
+                        current_count: ctx.current_files_count.load(Ordering::SeqCst),
+                        total_size: ctx.total_files_size,
+                        current_size: ctx.total_downloaded_size.load(Ordering::SeqCst),
+                    });
+                }
+
+                return Ok(());
+            }
+            Err(e) => {
+                error!(
+                    "Error downloading file {} from {}: {:?}",
+                    downloadable.path.to_string_lossy().to_string(),
+                    url,
+                    e
+                );
+
+                // the bytes are counted again if the next mirror resumes the partial file
+                ctx.total_downloaded_size.fetch_sub(
+                    file_processed_bytes.swap(0, Ordering::SeqCst),
+                    Ordering::SeqCst,
+                );
+
+                if let Some(sender) = progress {
+                    let _ = sender.send(Progress {
+                        total_count: ctx.total_files_count,
+                        current_count: ctx.current_files_count.load(Ordering::SeqCst),
+                        total_size: ctx.total_files_size,
+                        current_size: ctx.total_downloaded_size.load(Ordering::SeqCst),
+                    });
+                }
+
+                let cancelled = matches!(e, DownloadError::Cancelled);
+                last_error = Some(e);
+                if cancelled {
+                    break;
+                }
+            }
+        }
+    }
+
+    let part_file_path = part_file_path(&downloadable.path);
+    if part_file_path.exists() {
+        remove_file(&part_file_path).await?;
+    }
+
+    Err(last_error.expect("a downloadable always has at least one url"))
+}
+
+/// Downloads the file from a single url into its part file, returning the part file path.
+///
+/// A resumed download that fails verification is restarted from scratch once.
+async fn download_from_url(
+    url: &str,
+    force_overwrite: bool,
+    downloadable: &Downloadable,
+    ctx: &DownloadContext,
+    file_processed_bytes: &AtomicU64,
+) -> Result<PathBuf, DownloadError> {
+    let options = &ctx.options;
+
+    let segmented_size = downloadable
+        .size
+        .filter(|&size| options.segments > 1 && size >= options.segment_threshold);
+
+    if let Some(size) = segmented_size {
+        let part_file_path = part_file_path(&downloadable.path);
+
+        // an existing part file is resumed over a single connection instead
+        if force_overwrite || !part_file_path.exists() {
+            let outcome = download_segmented(
+                url,
+                size,
+                &part_file_path,
+                downloadable,
+                ctx,
+                file_processed_bytes,
+            )
+            .await;
+
+            // segments leave holes in the part file, so it cannot be resumed
+            if outcome.is_err() && part_file_path.exists() {
+                remove_file(&part_file_path).await?;
+            }
+
+            return outcome.map(|_| part_file_path);
+        }
+    }
+
     let progress = options.progress_sender.as_ref();
 
     let (part_file_path, mut file, headers, mut hasher, was_resumed) = prepare_download(
-        &downloadable,
-        false,
-        total_downloaded_size,
-        &file_processed_bytes,
-        current_files_count,
-        total_files_size,
-        total_files_count,
+        downloadable,
+        force_overwrite,
+        &ctx.total_downloaded_size,
+        file_processed_bytes,
+        &ctx.current_files_count,
+        ctx.total_files_size,
+        ctx.total_files_count,
         progress,
     )
     .await?;
 
     let outcome = _download_file(
-        &downloadable.url,
+        url,
         headers,
         &part_file_path,
         &mut file,
         &mut hasher,
-        &downloadable,
+        downloadable,
         options.clone(),
-        client.clone(),
-        total_downloaded_size,
-        &file_processed_bytes,
-        current_files_count,
-        total_files_size,
-        total_files_count,
+        ctx.client.clone(),
+        &ctx.total_downloaded_size,
+        file_processed_bytes,
+        &ctx.current_files_count,
+        ctx.total_files_size,
+        ctx.total_files_count,
     )
     .await;
 
-    if let Some(sender) = progress {
-        let _ = sender.send(Progress {
-            total_count: total_files_count,
-            current_count: current_files_count.load(Ordering::SeqCst),
-            total_size: total_files_size,
-            current_size: total_downloaded_size.load(Ordering::SeqCst),
-        });
-    }
-
     match outcome {
-        Ok(()) => {
-            tokio::fs::rename(&part_file_path, &downloadable.path)
-                .await
-                .map_err(|e| {
-                    DownloadError::CannotMoveFile(
-                        e,
-                        part_file_path.to_string_lossy().to_string(),
-                        downloadable.path.to_string_lossy().to_string(),
-                    )
-                })?;
-
-            current_files_count.fetch_add(1, Ordering::SeqCst);
-            if let Some(sender) = progress {
-                let _ = sender.send(Progress {
-                    total_count: total_files_count,
-                    current_count: current_files_count.load(Ordering::SeqCst),
-                    total_size: total_files_size,
-                    current_size: total_downloaded_size.load(Ordering::SeqCst),
-                });
-            }
-
-            Ok(())
-        }
         Err(DownloadError::ChecksumMismatch { .. }) | Err(DownloadError::SizeMismatch { .. })
             if was_resumed =>
         {
             tracing::warn!("Download was resumed, but checksum or size mismatched");
 
-            total_downloaded_size.fetch_sub(
-                file_processed_bytes.load(Ordering::SeqCst),
+            ctx.total_downloaded_size.fetch_sub(
+                file_processed_bytes.swap(0, Ordering::SeqCst),
                 Ordering::SeqCst,
             );
 
-            file_processed_bytes.store(0, Ordering::SeqCst);
-
             if let Some(sender) = progress {
                 let _ = sender.send(Progress {
-                    total_count: total_files_count,
-                    current_count: current_files_count.load(Ordering::SeqCst),
-                    total_size: total_files_size,
-                    current_size: total_downloaded_size.load(Ordering::SeqCst),
+                    total_count: ctx.total_files_count,
+                    current_count: ctx.current_files_count.load(Ordering::SeqCst),
+                    total_size: ctx.total_files_size,
+                    current_size: ctx.total_downloaded_size.load(Ordering::SeqCst),
                 });
             }
 
             let (part_file_path, mut file, headers, mut hasher, _) = prepare_download(
-                &downloadable,
+                downloadable,
                 true,
-                total_downloaded_size,
-                &file_processed_bytes,
-                current_files_count,
-                total_files_size,
-                total_files_count,
+                &ctx.total_downloaded_size,
+                file_processed_bytes,
+                &ctx.current_files_count,
+                ctx.total_files_size,
+                ctx.total_files_count,
                 progress,
             )
             .await?;
 
-            let outcome = _download_file(
-                &downloadable.url,
+            _download_file(
+                url,
                 headers,
                 &part_file_path,
                 &mut file,
                 &mut hasher,
-                &downloadable,
+                downloadable,
                 options.clone(),
-                client,
-                total_downloaded_size,
-                &file_processed_bytes,
-                current_files_count,
-                total_files_size,
-                total_files_count,
+                ctx.client.clone(),
+                &ctx.total_downloaded_size,
+                file_processed_bytes,
+                &ctx.current_files_count,
+                ctx.total_files_size,
+                ctx.total_files_count,
             )
-            .await;
-
-            match outcome {
-                Ok(()) => {
-                    tokio::fs::rename(&part_file_path, &downloadable.path)
-                        .await
-                        .map_err(|e| {
-                            DownloadError::CannotMoveFile(
-                                e,
-                                part_file_path.to_string_lossy().to_string(),
-                                downloadable.path.to_string_lossy().to_string(),
-                            )
-                        })?;
-
-                    current_files_count.fetch_add(1, Ordering::SeqCst);
-                    if let Some(sender) = progress {
-                        let _ = sender.send(Progress {
-                            total_count: total_files_count,
-                            current_count: current_files_count.load(Ordering::SeqCst),
-                            total_size: total_files_size,
-                            current_size: total_downloaded_size.load(Ordering::SeqCst),
-                        });
-                    }
+            .await?;
 
-                    Ok(())
-                }
-                Err(e) => {
-                    error!(
-                        "Error downloading file {} - {}: {:?}",
-                        part_file_path.to_string_lossy().to_string(),
-                        downloadable.path.to_string_lossy().to_string(),
-                        e
-                    );
-                    remove_file(&part_file_path).await?;
-                    Err(e)
-                }
-            }
-        }
-        Err(e) => {
-            error!(
-                "Error downloading file {} - {}: {:?}",
-                part_file_path.to_string_lossy().to_string(),
-                downloadable.path.to_string_lossy().to_string(),
-                e
-            );
-            remove_file(&part_file_path).await?;
-            Err(e)
+            Ok(part_file_path)
         }
+        outcome => outcome.map(|_| part_file_path),
     }
 }
 
@@ -622,7 +1029,7 @@ async fn _download_file(
     headers: reqwest::header::HeaderMap,
     part_file_path: &PathBuf,
     file: &mut File,
-    hasher: &mut Option<HashDigest>,
+    hasher: &mut Hasher,
     downloadable: &Downloadable,
     options: DownloadOptions,
     client: ClientWithMiddleware,
@@ -634,7 +1041,13 @@ async fn _download_file(
 ) -> Result<(), DownloadError> {
     let mut response = client.get(url).headers(headers).send().await?;
 
-    check_response_status(&response, &downloadable)?;
+    check_response_status(
+        &response,
+        &Downloadable {
+            url: url.to_string(),
+            ..downloadable.clone()
+        },
+    )?;
 
     download_content(
         &mut response,
@@ -651,6 +1064,179 @@ async fn _download_file(
 
     file.flush().await?;
 
+    verify_download(
+        part_file_path,
+        url,
+        downloadable,
+        file_processed_bytes,
+        hasher.finalize_reset(),
+    )
+    .await
+}
+
+/// Downloads a file of known `size` as concurrent ranged segments written in place.
+///
+/// The request for the first segment doubles as the probe: a server that ignores `Range`
+/// answers it with the whole body, which is then written out like a regular download.
+/// All segments share the per host permit of the file.
+async fn download_segmented(
+    url: &str,
+    size: u64,
+    part_file_path: &Path,
+    downloadable: &Downloadable,
+    ctx: &DownloadContext,
+    file_processed_bytes: &AtomicU64,
+) -> Result<(), DownloadError> {
+    let options = &ctx.options;
+    let client = &ctx.client;
+    let total_downloaded_size = &ctx.total_downloaded_size;
+    let current_files_count = &ctx.current_files_count;
+    let total_files_size = ctx.total_files_size;
+    let total_files_count = ctx.total_files_count;
+
+    let parent_dir = part_file_path
+        .parent()
+        .ok_or(DownloadError::CannotCreateDirectory(
+            part_file_path.to_string_lossy().to_string(),
+        ))?;
+
+    tokio::fs::create_dir_all(parent_dir).await?;
+
+    let mut file = File::create(part_file_path).await?;
+
+    let segment_len = size.div_ceil(options.segments as u64);
+    let segments = (0..size)
+        .step_by(segment_len as usize)
+        .map(|start| (start, (start + segment_len).min(size) - 1))
+        .collect::<Vec<_>>();
+
+    let request_segment = |start: u64, end: u64| {
+        client
+            .get(url)
+            .header(reqwest::header::RANGE, format!("bytes={}-{}", start, end))
+            .send()
+    };
+
+    let failed_response = || Downloadable {
+        url: url.to_string(),
+        ..downloadable.clone()
+    };
+
+    let (first_start, first_end) = segments[0];
+    let mut first_response = request_segment(first_start, first_end).await?;
+    check_response_status(&first_response, &failed_response())?;
+
+    if first_response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        trace!("{} does not support ranged requests", url);
+
+        let mut hasher = Hasher::new(&downloadable.checksums);
+
+        download_content(
+            &mut first_response,
+            &mut file,
+            &mut hasher,
+            options,
+            total_downloaded_size,
+            file_processed_bytes,
+            total_files_size,
+            current_files_count,
+            total_files_count,
+        )
+        .await?;
+
+        file.flush().await?;
+
+        return verify_download(
+            part_file_path,
+            url,
+            downloadable,
+            file_processed_bytes,
+            hasher.finalize_reset(),
+        )
+        .await;
+    }
+
+    file.set_len(size).await?;
+    drop(file);
+
+    let mut first_response = Some(first_response);
+
+    let segment_tasks = segments.iter().map(|&(start, end)| {
+        let response = first_response.take();
+
+        async move {
+            let mut response = match response {
+                Some(response) => response,
+                None => {
+                    let response = request_segment(start, end).await?;
+                    check_response_status(&response, &failed_response())?;
+
+                    if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+                        return Err(DownloadError::GenericDownload(format!(
+                            "{} did not honor the range of segment {}-{}",
+                            url, start, end
+                        )));
+                    }
+
+                    response
+                }
+            };
+
+            let mut file = File::options().write(true).open(part_file_path).await?;
+            file.seek(SeekFrom::Start(start)).await?;
+
+            // the whole file is hashed once every segment is in place
+            let mut hasher = Hasher::new(&[]);
+
+            download_content(
+                &mut response,
+                &mut file,
+                &mut hasher,
+                options,
+                total_downloaded_size,
+                file_processed_bytes,
+                total_files_size,
+                current_files_count,
+                total_files_count,
+            )
+            .await?;
+
+            file.flush().await?;
+
+            let segment_end = file.stream_position().await?;
+            if segment_end != end + 1 {
+                return Err(DownloadError::SizeMismatch {
+                    expected: end + 1 - start,
+                    actual: segment_end.saturating_sub(start),
+                });
+            }
+
+            Ok(())
+        }
+    });
+
+    futures::future::try_join_all(segment_tasks).await?;
+
+    let outputs = hash_file(part_file_path, &downloadable.checksums).await?;
+
+    verify_download(
+        part_file_path,
+        url,
+        downloadable,
+        file_processed_bytes,
+        outputs,
+    )
+    .await
+}
+
+/// Checks the downloaded byte count and the checksums of a finished part file.
+async fn verify_download(
+    part_file_path: &Path,
+    url: &str,
+    downloadable: &Downloadable,
+    file_processed_bytes: &AtomicU64,
+    outputs: Vec<HashOutput>,
+) -> Result<(), DownloadError> {
     if let Some(expected_size) = downloadable.size {
         let processed_bytes = file_processed_bytes.load(Ordering::SeqCst);
         if expected_size != processed_bytes {
@@ -661,29 +1247,24 @@ async fn _download_file(
         }
     }
 
-    if let Some(expected_checksum) = downloadable.checksum.as_ref() {
-        let expected_hash = match expected_checksum {
-            Checksum::Sha256(hash) | Checksum::Sha1(hash) | Checksum::Md5(hash) => hash,
-        };
+    verify_checksums(part_file_path, url, &downloadable.checksums, outputs).await
+}
 
-        let actual_hash = hex::encode(
-            hasher
-                .as_mut()
-                .expect("downloadable checksum is set, but hasher is not set")
-                .finalize_reset(),
-        );
+async fn hash_file(path: &Path, checksums: &[Checksum]) -> Result<Vec<HashOutput>, DownloadError> {
+    let mut file = File::open(path).await?;
+    let mut buffer = vec![0; 8192];
 
-        if actual_hash != *expected_hash {
-            return Err(DownloadError::ChecksumMismatch {
-                expected: expected_hash.clone(),
-                actual: actual_hash,
-                url: downloadable.url.clone(),
-                path: downloadable.path.to_string_lossy().to_string(),
-            });
+    let mut hasher = Hasher::new(checksums);
+
+    loop {
+        let bytes_read = file.read(&mut buffer).await?;
+        if bytes_read == 0 {
+            break;
         }
+        hasher.update(&buffer[..bytes_read]);
     }
 
-    Ok(())
+    Ok(hasher.finalize_reset())
 }
 
 async fn remove_file(path: &Path) -> Result<(), DownloadError> {
@@ -693,30 +1274,9 @@ async fn remove_file(path: &Path) -> Result<(), DownloadError> {
     })
 }
 
-async fn prepare_download(
-    downloadable: &Downloadable,
-    force_overwrite: bool,
-    total_downloaded_size: &AtomicU64,
-    file_processed_bytes: &AtomicU64,
-    current_files_count: &AtomicU64,
-    total_files_size: u64,
-    total_files_count: u64,
-    progress: Option<&Sender<Progress>>,
-) -> Result<
-    (
-        PathBuf,
-        File,
-        reqwest::header::HeaderMap,
-        Option<HashDigest>,
-        bool,
-    ),
-    DownloadError,
-> {
-    let initial_ext = downloadable
-        .path
-        .extension()
-        .unwrap_or_default()
-        .to_os_string();
+/// Where an in-progress download of `path` is written before being moved into place.
+pub fn part_file_path(path: &Path) -> PathBuf {
+    let initial_ext = path.extension().unwrap_or_default().to_os_string();
 
     let format_ext = if initial_ext.is_empty() {
         OsString::from(PART_POSTFIX)
@@ -729,7 +1289,30 @@ async fn prepare_download(
         ext
     };
 
-    let part_file_path = downloadable.path.with_extension(format_ext);
+    path.with_extension(format_ext)
+}
+
+/// Returns the file a part file is being downloaded to, or `None` if `path` is not a part file.
+pub fn part_file_target(path: &Path) -> Option<PathBuf> {
+    let file_name = path.file_name()?.to_str()?;
+    let target_name = file_name.strip_suffix(PART_POSTFIX)?;
+    // files without an extension get an empty one before the postfix
+    let target_name = target_name.strip_suffix('.').unwrap_or(target_name);
+
+    Some(path.with_file_name(target_name))
+}
+
+async fn prepare_download(
+    downloadable: &Downloadable,
+    force_overwrite: bool,
+    total_downloaded_size: &AtomicU64,
+    file_processed_bytes: &AtomicU64,
+    current_files_count: &AtomicU64,
+    total_files_size: u64,
+    total_files_count: u64,
+    progress: Option<&Sender<Progress>>,
+) -> Result<(PathBuf, File, reqwest::header::HeaderMap, Hasher, bool), DownloadError> {
+    let part_file_path = part_file_path(&downloadable.path);
 
     let should_resume = part_file_path.exists() && !force_overwrite;
 
@@ -754,12 +1337,7 @@ async fn prepare_download(
     let mut headers = reqwest::header::HeaderMap::new();
     let mut processed_bytes = 0;
 
-    let mut hasher = match downloadable.checksum {
-        Some(Checksum::Sha256(_)) => Some(HashDigest::Sha256(Sha256::new())),
-        Some(Checksum::Sha1(_)) => Some(HashDigest::Sha1(sha1::Sha1::new())),
-        Some(Checksum::Md5(_)) => Some(HashDigest::Md5(md5::Md5::new())),
-        None => None,
-    };
+    let mut hasher = Hasher::new(&downloadable.checksums);
 
     if should_resume {
         let mut buf = vec![0; 8192];
@@ -768,9 +1346,7 @@ async fn prepare_download(
             if bytes_read == 0 {
                 break;
             }
-            if let Some(hasher) = hasher.as_mut() {
-                hasher.update(&buf[..bytes_read]);
-            }
+            hasher.update(&buf[..bytes_read]);
             processed_bytes += bytes_read as u64;
 
             if let Some(progress) = progress {
@@ -816,7 +1392,7 @@ fn check_response_status(
 async fn download_content(
     response: &mut reqwest::Response,
     file: &mut File,
-    hasher: &mut Option<HashDigest>,
+    hasher: &mut Hasher,
     options: &DownloadOptions,
     total_downloaded_size: &AtomicU64,
     file_processed_bytes: &AtomicU64,
@@ -830,11 +1406,11 @@ async fn download_content(
             return Err(DownloadError::Cancelled);
         }
 
+        throttle(chunk.len()).await;
+
         file.write_all(&chunk).await?;
 
-        if let Some(hasher) = hasher.as_mut() {
-            hasher.update(&chunk);
-        }
+        hasher.update(&chunk);
 
         total_downloaded_size.fetch_add(chunk.len() as u64, Ordering::SeqCst);
         file_processed_bytes.fetch_add(chunk.len() as u64, Ordering::SeqCst);
@@ -895,11 +1471,11 @@ async fn process_download_tasks(
     Ok(false)
 }
 
-#[instrument(skip(expected_checksum))]
+#[instrument(skip(expected_checksums))]
 async fn validate_file(
     path: &Path,
     expected_size: Option<u64>,
-    expected_checksum: &Option<Checksum>,
+    expected_checksums: &[Checksum],
     deep_check: bool,
 ) -> Result<(), DownloadError> {
     if !path.exists() {
@@ -924,41 +1500,11 @@ async fn validate_file(
         }
     }
 
-    if deep_check {
-        if let Some(expected_checksum) = expected_checksum {
-            let mut file = File::open(path).await?;
-            let mut buffer = vec![0; 8192];
-
-            let mut hasher = HashDigest::from(expected_checksum);
-
-            let actual_hash = {
-                loop {
-                    let bytes_read = file.read(&mut buffer).await?;
-                    if bytes_read == 0 {
-                        break;
-                    }
-                    hasher.update(&buffer[..bytes_read]);
-                }
-                Ok::<_, DownloadError>(hex::encode(hasher.finalize()))
-            }?;
-
-            let expected_hash = match expected_checksum {
-                Checksum::Sha256(hash) | Checksum::Sha1(hash) | Checksum::Md5(hash) => hash,
-            };
+    if deep_check && !expected_checksums.is_empty() {
+        let outputs = hash_file(path, expected_checksums).await?;
 
-            if actual_hash != *expected_hash {
-                error!(
-                    "Checksum mismatch: expected {}, got {}",
-                    expected_hash, actual_hash
-                );
-                return Err(DownloadError::ChecksumMismatch {
-                    expected: expected_hash.clone(),
-                    actual: actual_hash,
-                    url: "".to_string(), // We don't have the URL in this context
-                    path: path.to_string_lossy().to_string(),
-                });
-            }
-        }
+        // We don't have the URL in this context
+        verify_checksums(path, "", expected_checksums, outputs).await?;
     }
 
     Ok(())
@@ -1005,19 +1551,21 @@ mod tests {
 
         let downloadable = Downloadable {
             url: format!("{}/test.txt", mock_url),
+            mirrors: vec![],
             path: file_path.clone(),
-            checksum: Some(Checksum::Sha256(
+            checksums: vec![Checksum::Sha256(
                 "dffd6021bb2bd5b0af676290809ec3a53191dd81c7f70a4b28688a362182986f".to_string(),
-            )),
+            )],
             size: Some(13),
         };
 
         let downloadable1 = Downloadable {
             url: format!("{}/test1.txt", mock_url),
+            mirrors: vec![],
             path: file_path1.clone(),
-            checksum: Some(Checksum::Sha256(
+            checksums: vec![Checksum::Sha256(
                 "dffd6021bb2bd5b0af676290809ec3a53191dd81c7f70a4b28688a362182986f".to_string(),
-            )),
+            )],
             size: Some(13),
         };
 
@@ -1053,8 +1601,9 @@ mod tests {
 
         let downloadable = Downloadable {
             url: format!("{}/test.txt", mock_url),
+            mirrors: vec![],
             path: file_path,
-            checksum: None,
+            checksums: vec![],
             size: None,
         };
 
@@ -1093,8 +1642,9 @@ mod tests {
 
         let downloadable = Downloadable {
             url: format!("{}/test.txt", mock_url),
+            mirrors: vec![],
             path: file_path,
-            checksum: None,
+            checksums: vec![],
             size: Some(13),
         };
 
@@ -1139,15 +1689,17 @@ mod tests {
 
         let downloadable1 = Downloadable {
             url: format!("{}/test.txt", mock_url),
+            mirrors: vec![],
             path: file_path.clone(),
-            checksum: None,
+            checksums: vec![],
             size: None,
         };
 
         let downloadable2 = Downloadable {
             url: format!("{}/test.txt", mock_url),
+            mirrors: vec![],
             path: file_path.clone(),
-            checksum: None,
+            checksums: vec![],
             size: None,
         };
 
@@ -1175,7 +1727,7 @@ mod tests {
             "dffd6021bb2bd5b0af676290809ec3a53191dd81c7f70a4b28688a362182986f".to_string(),
         );
 
-        validate_file(&file_path, Some(13), &Some(checksum), true)
+        validate_file(&file_path, Some(13), &[checksum], true)
             .await
             .unwrap()
     }
@@ -1190,7 +1742,7 @@ mod tests {
         file.write_all(content).await.unwrap();
         file.flush().await.unwrap();
 
-        let result = validate_file(&file_path, Some(14), &None, false).await;
+        let result = validate_file(&file_path, Some(14), &[], false).await;
         assert!(matches!(result, Err(DownloadError::SizeMismatch { .. })));
     }
 
@@ -1206,13 +1758,218 @@ mod tests {
 
         let checksum = Checksum::Sha256("incorrect_hash".to_string());
 
-        let result = validate_file(&file_path, Some(13), &Some(checksum), true).await;
+        let result = validate_file(&file_path, Some(13), &[checksum], true).await;
         assert!(matches!(
             result,
             Err(DownloadError::ChecksumMismatch { .. })
         ));
     }
 
+    #[tokio::test]
+    #[traced_test]
+    async fn test_validate_file_multiple_checksums() {
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        let content = b"Hello, World!";
+        let mut file = File::create(&file_path).await.unwrap();
+        file.write_all(content).await.unwrap();
+        file.flush().await.unwrap();
+
+        let sha512 = Checksum::Sha512(
+            "374d794a95cdcfd8b35993185fef9ba368f160d8daf432d08ba9f1ed1e5abe6cc69291e0fa2fe0006a52570ef18c19def4e617c33ce52ef0a6e5fbe318cb0387".to_string(),
+        );
+
+        validate_file(
+            &file_path,
+            Some(13),
+            &[sha512.clone(), Checksum::Murmur2(1961219979)],
+            true,
+        )
+        .await
+        .unwrap();
+
+        let result = validate_file(
+            &file_path,
+            Some(13),
+            &[sha512, Checksum::Murmur2(1234)],
+            true,
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(DownloadError::ChecksumMismatch { expected, .. }) if expected == "1234"
+        ));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_download_multiple_checksums() {
+        let mut server = mockito::Server::new_async().await;
+        let mock_url = server.url();
+
+        let mock = server
+            .mock("GET", "/test.txt")
+            .with_status(200)
+            .with_body("Hello, World!")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+
+        let downloadable = Downloadable::new(format!("{}/test.txt", mock_url), &file_path)
+            .with_checksums([
+                Checksum::Sha1("0a0a9f2a6772942557ab5355d76af442f8f65e01".to_string()),
+                Checksum::Sha512("374d794a95cdcfd8b35993185fef9ba368f160d8daf432d08ba9f1ed1e5abe6cc69291e0fa2fe0006a52570ef18c19def4e617c33ce52ef0a6e5fbe318cb0387".to_string()),
+                Checksum::Murmur2(1961219979),
+            ])
+            .with_size(13);
+
+        download_multiple(&[downloadable], DownloadOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(file_path).unwrap(), "Hello, World!");
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_download_mirror_fallback() {
+        let mut server = mockito::Server::new_async().await;
+        let mock_url = server.url();
+
+        let primary = server
+            .mock("GET", "/primary/test.txt")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mirror = server
+            .mock("GET", "/mirror/test.txt")
+            .with_status(200)
+            .with_body("Hello, World!")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+
+        let downloadable = Downloadable::new(format!("{}/primary/test.txt", mock_url), &file_path)
+            .with_mirrors([format!("{}/mirror/test.txt", mock_url)])
+            .with_size(13);
+
+        let (served_by_tx, mut served_by_rx) = tokio::sync::mpsc::unbounded_channel();
+        let options = DownloadOptions::builder()
+            .served_by_sender(served_by_tx)
+            .build();
+
+        download_multiple(&[downloadable], options).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            "Hello, World!"
+        );
+        assert!(logs_contain(&format!("from {}/mirror/test.txt", mock_url)));
+        assert_eq!(
+            served_by_rx.try_recv().unwrap(),
+            ServedBy {
+                path: file_path,
+                url: format!("{}/mirror/test.txt", mock_url),
+            }
+        );
+        assert!(served_by_rx.try_recv().is_err());
+
+        primary.assert();
+        mirror.assert();
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_download_mirror_resumes_part_file() {
+        let mut server = mockito::Server::new_async().await;
+        let mock_url = server.url();
+
+        // the primary cuts off early, the mirror continues from where it stopped
+        let primary = server
+            .mock("GET", "/primary/test.txt")
+            .with_status(200)
+            .with_body("Hello, ")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mirror = server
+            .mock("GET", "/mirror/test.txt")
+            .match_header("range", "bytes=7-")
+            .with_status(206)
+            .with_body("World!")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+
+        let downloadable = Downloadable::new(format!("{}/primary/test.txt", mock_url), &file_path)
+            .with_mirrors([format!("{}/mirror/test.txt", mock_url)])
+            .with_checksum(Some(Checksum::Sha256(
+                "dffd6021bb2bd5b0af676290809ec3a53191dd81c7f70a4b28688a362182986f".to_string(),
+            )))
+            .with_size(13);
+
+        download_multiple(&[downloadable], DownloadOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(file_path).unwrap(), "Hello, World!");
+
+        primary.assert();
+        mirror.assert();
+    }
+
+    #[tokio::test]
+    async fn test_bandwidth_limiter() {
+        let limiter = BandwidthLimiter::new(NonZeroU32::new(10_000).unwrap());
+
+        let start = std::time::Instant::now();
+        // the first second worth of bytes is available immediately
+        limiter.until_ready(25_000).await;
+        assert!(start.elapsed() >= Duration::from_millis(1400));
+    }
+
+    #[tokio::test]
+    async fn test_host_limits() {
+        let limits = HostLimits::new(1);
+
+        let permit = limits
+            .acquire("https://edge.forgecdn.net/a.jar")
+            .await
+            .unwrap();
+        assert!(permit.is_some());
+
+        let other_host = limits
+            .acquire("https://cdn.modrinth.com/b.jar")
+            .await
+            .unwrap();
+        assert!(other_host.is_some());
+
+        let same_host = tokio::time::timeout(
+            Duration::from_millis(100),
+            limits.acquire("https://edge.forgecdn.net/c.jar"),
+        )
+        .await;
+        assert!(same_host.is_err());
+
+        drop(permit);
+        let same_host = limits
+            .acquire("https://edge.forgecdn.net/c.jar")
+            .await
+            .unwrap();
+        assert!(same_host.is_some());
+    }
+
     #[tokio::test]
     #[traced_test]
     async fn test_download_file_resume() {
@@ -1244,10 +2001,11 @@ mod tests {
 
         let downloadable = Downloadable {
             url: format!("{}/test.txt", mock_url),
+            mirrors: vec![],
             path: file_path.clone(),
-            checksum: Some(Checksum::Sha256(
+            checksums: vec![Checksum::Sha256(
                 "dffd6021bb2bd5b0af676290809ec3a53191dd81c7f70a4b28688a362182986f".to_string(),
-            )),
+            )],
             size: Some(13),
         };
 
@@ -1303,10 +2061,11 @@ mod tests {
 
         let downloadable = Downloadable {
             url: format!("{}/test.txt", mock_url),
+            mirrors: vec![],
             path: file_path.clone(),
-            checksum: Some(Checksum::Sha256(
+            checksums: vec![Checksum::Sha256(
                 "dffd6021bb2bd5b0af676290809ec3a53191dd81c7f70a4b28688a362182986f".to_string(),
-            )),
+            )],
             size: Some(13),
         };
 
@@ -1381,28 +2140,31 @@ mod tests {
 
         let downloadable1 = Downloadable {
             url: format!("{}/test1.txt", mock_url),
+            mirrors: vec![],
             path: file_path1.clone(),
-            checksum: Some(Checksum::Sha1(
+            checksums: vec![Checksum::Sha1(
                 "83cfe479c149983ad66bb9c0e4b47cf2870e33c6".to_string(),
-            )),
+            )],
             size: Some(15),
         };
 
         let downloadable2 = Downloadable {
             url: format!("{}/test2.txt", mock_url),
+            mirrors: vec![],
             path: file_path2.clone(),
-            checksum: Some(Checksum::Sha256(
+            checksums: vec![Checksum::Sha256(
                 "1aefd92d29317a3119827259177ce85329da746818baf6682d1d455ade4263fd".to_string(),
-            )),
+            )],
             size: Some(15),
         };
 
         let downloadable3 = Downloadable {
             url: format!("{}/test3.txt", mock_url),
+            mirrors: vec![],
             path: file_path3.clone(),
-            checksum: Some(Checksum::Md5(
+            checksums: vec![Checksum::Md5(
                 "216a3bcccd4c577dcf40e58f19f31367".to_string(),
-            )),
+            )],
             size: Some(15),
         };
 
@@ -1447,8 +2209,9 @@ mod tests {
 
         let downloadable = Downloadable {
             url: format!("{}/test.txt", mock_url),
+            mirrors: vec![],
             path: file_path,
-            checksum: None,
+            checksums: vec![],
             size: None,
         };
 
@@ -1488,8 +2251,9 @@ mod tests {
 
         let downloadable = Downloadable {
             url: format!("{}/original.txt", mock_url),
+            mirrors: vec![],
             path: file_path.clone(),
-            checksum: None,
+            checksums: vec![],
             size: None,
         };
 
@@ -1529,8 +2293,9 @@ mod tests {
 
         let downloadable = Downloadable {
             url: format!("{}/large_file.bin", mock_url),
+            mirrors: vec![],
             path: file_path.clone(),
-            checksum: None,
+            checksums: vec![],
             size: Some(LARGE_FILE_SIZE as u64),
         };
 
@@ -1573,15 +2338,17 @@ mod tests {
 
         let downloadable1 = Downloadable {
             url: format!("{}/test1.txt", mock_url),
+            mirrors: vec![],
             path: file_path1.clone(),
-            checksum: None,
+            checksums: vec![],
             size: Some(13),
         };
 
         let downloadable2 = Downloadable {
             url: format!("{}/test2.txt", mock_url),
+            mirrors: vec![],
             path: file_path2.clone(),
-            checksum: None,
+            checksums: vec![],
             size: Some(15),
         };
 
@@ -1662,8 +2429,9 @@ mod tests {
 
         let downloadable = Downloadable {
             url: format!("{}/test.txt", mock_url),
+            mirrors: vec![],
             path: file_path.clone(),
-            checksum: None,
+            checksums: vec![],
             size: Some(13),
         };
 
@@ -1719,8 +2487,9 @@ mod tests {
 
         let downloadable = Downloadable {
             url: format!("{}/test.txt", mock_url),
+            mirrors: vec![],
             path: file_path,
-            checksum: None,
+            checksums: vec![],
             size: Some(1_000_000_000),
         };
 
@@ -1765,4 +2534,194 @@ mod tests {
 
         mock.assert();
     }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_download_through_store() {
+        let mut server = mockito::Server::new_async().await;
+        let mock_url = server.url();
+
+        let mock = server
+            .mock("GET", "/test.txt")
+            .with_status(200)
+            .with_body("Hello, World!")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let temp_dir = tempdir().unwrap();
+        let store = ContentStore::new(temp_dir.path().join("store"));
+
+        let downloadable = |name: &str| {
+            Downloadable::new(
+                format!("{}/test.txt", mock_url),
+                temp_dir.path().join(name).join("mods").join("test.txt"),
+            )
+            .with_checksum(Some(Checksum::Sha1(
+                "0a0a9f2a6772942557ab5355d76af442f8f65e01".to_string(),
+            )))
+            .with_size(13)
+        };
+
+        let options = DownloadOptions::builder()
+            .concurrency(1)
+            .store(store.clone())
+            .build();
+
+        download_multiple(&[downloadable("first"), downloadable("second")], options)
+            .await
+            .unwrap();
+
+        // the store already has the file, so no request is made
+        let options = DownloadOptions::builder().store(store.clone()).build();
+        download_multiple(&[downloadable("third")], options)
+            .await
+            .unwrap();
+
+        for name in ["first", "second", "third"] {
+            assert_eq!(
+                std::fs::read_to_string(temp_dir.path().join(name).join("mods").join("test.txt"))
+                    .unwrap(),
+                "Hello, World!"
+            );
+        }
+
+        assert_eq!(
+            store.disk_usage().unwrap(),
+            StoreUsage {
+                files: 1,
+                bytes: 13
+            }
+        );
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_concurrent_downloads_through_store() {
+        let mut server = mockito::Server::new_async().await;
+        let mock_url = server.url();
+
+        let mock = server
+            .mock("GET", "/test.txt")
+            .with_status(200)
+            .with_body("Hello, World!")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let temp_dir = tempdir().unwrap();
+        let store = ContentStore::new(temp_dir.path().join("store"));
+
+        let downloadable = |name: &str| {
+            Downloadable::new(
+                format!("{}/test.txt", mock_url),
+                temp_dir.path().join(name).join("mods").join("test.txt"),
+            )
+            .with_checksum(Some(Checksum::Sha1(
+                "0a0a9f2a6772942557ab5355d76af442f8f65e01".to_string(),
+            )))
+            .with_size(13)
+        };
+
+        let first = [downloadable("first")];
+        let second = [downloadable("second")];
+
+        // separate calls, as two instances installing the same mod would
+        let (first_result, second_result) = tokio::join!(
+            download_multiple(
+                &first,
+                DownloadOptions::builder().store(store.clone()).build()
+            ),
+            download_multiple(
+                &second,
+                DownloadOptions::builder().store(store.clone()).build()
+            ),
+        );
+        first_result.unwrap();
+        second_result.unwrap();
+
+        for name in ["first", "second"] {
+            assert_eq!(
+                std::fs::read_to_string(temp_dir.path().join(name).join("mods").join("test.txt"))
+                    .unwrap(),
+                "Hello, World!"
+            );
+        }
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_download_segmented() {
+        let mut server = mockito::Server::new_async().await;
+        let mock_url = server.url();
+
+        let body = "0123456789abcdefghijABCDEFGHIJ!@#$%^&*()";
+
+        let mut mocks = Vec::new();
+        for start in (0..body.len()).step_by(10) {
+            let end = start + 9;
+            mocks.push(
+                server
+                    .mock("GET", "/segmented.bin")
+                    .match_header("range", &*format!("bytes={}-{}", start, end))
+                    .with_status(206)
+                    .with_header("accept-ranges", "bytes")
+                    .with_header(
+                        "content-range",
+                        &format!("bytes {}-{}/{}", start, end, body.len()),
+                    )
+                    .with_body(&body[start..=end])
+                    .expect(1)
+                    .create_async()
+                    .await,
+            );
+        }
+
+        let temp_dir = tempdir().unwrap();
+        let file_path = temp_dir.path().join("segmented.bin");
+
+        let downloadable = Downloadable::new(format!("{}/segmented.bin", mock_url), &file_path)
+            .with_checksum(Some(Checksum::Sha1(
+                "769382f9bd62850e6185699b257ef6902b8ebd2e".to_string(),
+            )))
+            .with_size(body.len() as u64);
+
+        let (progress_tx, progress_rx) = watch::channel(Progress::new());
+
+        let options = DownloadOptions::builder()
+            .segments(4)
+            .segment_threshold(16)
+            .progress_sender(progress_tx)
+            .build();
+
+        download_multiple(&[downloadable], options).await.unwrap();
+
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), body);
+        assert!(!part_file_path(&file_path).exists());
+
+        let progress = progress_rx.borrow();
+        assert_eq!(progress.current_size, body.len() as u64);
+        assert_eq!(progress.current_count, 1);
+
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    #[test]
+    fn test_part_file_target() {
+        for path in ["/mods/test.jar", "/mods/test", "/mods/test.tar.gz"] {
+            let path = Path::new(path);
+            assert_eq!(
+                part_file_target(&part_file_path(path)).as_deref(),
+                Some(path)
+            );
+        }
+
+        assert_eq!(part_file_target(Path::new("/mods/test.jar")), None);
+    }
 }
@@ -0,0 +1,461 @@
+//! Content-addressed store shared by every instance.
+//!
+//! Files are kept under `<root>/<algorithm>/<first two hex chars>/<hash>` and linked into their
+//! final location, so the same jar is only stored once on disk no matter how many instances use it.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, PoisonError};
+
+use tokio::sync::RwLock;
+
+use tracing::{trace, warn};
+
+use crate::Checksum;
+
+const LINKS_POSTFIX: &str = ".links";
+
+/// Held shared by every download into a store, keyed by its root, so garbage collection never
+/// removes an entry that was downloaded but not linked yet.
+static STORES_IN_USE: std::sync::Mutex<BTreeMap<PathBuf, Arc<RwLock<()>>>> =
+    std::sync::Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone)]
+pub struct ContentStore {
+    root: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreAlgorithm {
+    Sha1,
+    Sha512,
+}
+
+impl StoreAlgorithm {
+    const ALL: [StoreAlgorithm; 2] = [StoreAlgorithm::Sha512, StoreAlgorithm::Sha1];
+
+    fn dir_name(self) -> &'static str {
+        match self {
+            StoreAlgorithm::Sha1 => "sha1",
+            StoreAlgorithm::Sha512 => "sha512",
+        }
+    }
+
+    fn hex_len(self) -> usize {
+        match self {
+            StoreAlgorithm::Sha1 => 40,
+            StoreAlgorithm::Sha512 => 128,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StoreUsage {
+    pub files: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    Reflink,
+    Hardlink,
+    Copy,
+}
+
+impl ContentStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Downloads hold this shared while they use the store, `gc` needs it exclusively.
+    pub(crate) fn in_use(&self) -> Arc<RwLock<()>> {
+        STORES_IN_USE
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(self.root.clone())
+            .or_default()
+            .clone()
+    }
+
+    /// Returns where a file with the given checksums lives in the store, preferring SHA-512.
+    ///
+    /// `None` means the file cannot be stored because it has no usable checksum.
+    pub fn path_for(&self, checksums: &[Checksum]) -> Option<PathBuf> {
+        let sha512 = checksums.iter().find_map(|c| match c {
+            Checksum::Sha512(hash) => Some((StoreAlgorithm::Sha512, hash)),
+            _ => None,
+        });
+        let sha1 = checksums.iter().find_map(|c| match c {
+            Checksum::Sha1(hash) => Some((StoreAlgorithm::Sha1, hash)),
+            _ => None,
+        });
+
+        sha512
+            .into_iter()
+            .chain(sha1)
+            .find_map(|(algorithm, hash)| self.entry_path(algorithm, hash))
+    }
+
+    fn entry_path(&self, algorithm: StoreAlgorithm, hash: &str) -> Option<PathBuf> {
+        // the hash ends up in a path, so anything but plain hex is rejected
+        if hash.len() != algorithm.hex_len() || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let hash = hash.to_lowercase();
+
+        Some(
+            self.root
+                .join(algorithm.dir_name())
+                .join(&hash[..2])
+                .join(hash),
+        )
+    }
+
+    fn entries(&self) -> io::Result<Vec<(StoreAlgorithm, String, PathBuf)>> {
+        let mut entries = Vec::new();
+
+        for algorithm in StoreAlgorithm::ALL {
+            let algorithm_dir = self.root.join(algorithm.dir_name());
+            if !algorithm_dir.is_dir() {
+                continue;
+            }
+
+            for prefix in std::fs::read_dir(&algorithm_dir)? {
+                let prefix = prefix?;
+                if !prefix.file_type()?.is_dir() {
+                    continue;
+                }
+
+                for entry in std::fs::read_dir(prefix.path())? {
+                    let entry = entry?;
+                    if !entry.file_type()?.is_file() {
+                        continue;
+                    }
+
+                    // part files of in-flight downloads and link records are not entries
+                    let hash = entry.file_name().to_string_lossy().to_string();
+                    if hash.len() != algorithm.hex_len() {
+                        continue;
+                    }
+
+                    entries.push((algorithm, hash, entry.path()));
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    pub fn disk_usage(&self) -> io::Result<StoreUsage> {
+        let mut usage = StoreUsage::default();
+
+        for (_, _, path) in self.entries()? {
+            usage.files += 1;
+            usage.bytes += std::fs::metadata(path)?.len();
+        }
+
+        Ok(usage)
+    }
+
+    /// Removes every entry that is neither linked anywhere else nor reported as referenced.
+    ///
+    /// `is_referenced` receives the algorithm and the lowercase hex hash of each entry.
+    /// Returns `None` without removing anything while downloads into the store are in progress.
+    pub fn gc(
+        &self,
+        is_referenced: impl Fn(StoreAlgorithm, &str) -> bool,
+    ) -> io::Result<Option<StoreUsage>> {
+        let in_use = self.in_use();
+        let Ok(_guard) = in_use.try_write() else {
+            return Ok(None);
+        };
+
+        let mut removed = StoreUsage::default();
+
+        for (algorithm, hash, path) in self.entries()? {
+            let metadata = std::fs::metadata(&path)?;
+
+            if has_other_links(&metadata)
+                || has_recorded_links(&path, metadata.len())
+                || is_referenced(algorithm, &hash)
+            {
+                continue;
+            }
+
+            trace!("Removing unreferenced store entry {}", path.display());
+
+            match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    removed.files += 1;
+                    removed.bytes += metadata.len();
+                }
+                Err(e) => warn!("Failed to remove store entry {}: {e}", path.display()),
+            }
+
+            match std::fs::remove_file(links_path(&path)) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => warn!("Failed to remove the links of {}: {e}", path.display()),
+            }
+        }
+
+        Ok(Some(removed))
+    }
+}
+
+#[cfg(unix)]
+fn has_other_links(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    metadata.nlink() > 1
+}
+
+#[cfg(not(unix))]
+fn has_other_links(_metadata: &std::fs::Metadata) -> bool {
+    // the link count is not available here, `has_recorded_links` covers these platforms
+    false
+}
+
+fn links_path(entry: &Path) -> PathBuf {
+    let mut path = entry.as_os_str().to_os_string();
+    path.push(LINKS_POSTFIX);
+    PathBuf::from(path)
+}
+
+/// Remembers that `dst` was hardlinked to the store entry `src`.
+fn record_link(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::io::Write;
+
+    let mut links = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(links_path(src))?;
+
+    writeln!(links, "{}", dst.display())
+}
+
+/// Whether any recorded link of the entry still exists.
+///
+/// Files that were replaced with a different size since don't count anymore.
+fn has_recorded_links(entry: &Path, len: u64) -> bool {
+    let Ok(links) = std::fs::read_to_string(links_path(entry)) else {
+        return false;
+    };
+
+    links.lines().any(|link| {
+        std::fs::metadata(link)
+            .map(|metadata| metadata.is_file() && metadata.len() == len)
+            .unwrap_or(false)
+    })
+}
+
+/// Places `src` at `dst`, sharing the data on disk when the filesystem allows it.
+///
+/// Reflinks are tried first since the copies stay independent, then hardlinks, and a plain copy
+/// is the fallback when the store and the destination live on different filesystems.
+pub fn link_file(src: &Path, dst: &Path) -> io::Result<LinkKind> {
+    if let Some(parent) = dst.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    match std::fs::remove_file(dst) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+
+    if reflink(src, dst).is_ok() {
+        return Ok(LinkKind::Reflink);
+    }
+
+    if std::fs::hard_link(src, dst).is_ok() {
+        // the link count can't be read on every platform, so the store keeps its own record
+        if let Err(e) = record_link(src, dst) {
+            warn!("Failed to record the link of {}: {e}", src.display());
+        }
+
+        return Ok(LinkKind::Hardlink);
+    }
+
+    std::fs::copy(src, dst)?;
+    Ok(LinkKind::Copy)
+}
+
+#[cfg(target_os = "linux")]
+fn reflink(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    // _IOW(0x94, 9, int) from linux/fs.h
+    const FICLONE: libc::c_ulong = 0x40049409;
+
+    let src_file = std::fs::File::open(src)?;
+    let dst_file = std::fs::File::create(dst)?;
+
+    // SAFETY: both descriptors are valid for the duration of the call
+    let result = unsafe { libc::ioctl(dst_file.as_raw_fd(), FICLONE as _, src_file.as_raw_fd()) };
+
+    if result == -1 {
+        let err = io::Error::last_os_error();
+        drop(dst_file);
+        let _ = std::fs::remove_file(dst);
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn reflink(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let src = CString::new(src.as_os_str().as_bytes())?;
+    let dst = CString::new(dst.as_os_str().as_bytes())?;
+
+    // SAFETY: both paths are valid NUL terminated strings
+    let result = unsafe { libc::clonefile(src.as_ptr(), dst.as_ptr(), 0) };
+
+    if result == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn reflink(_src: &Path, _dst: &Path) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const SHA1: &str = "0a0a9f2a6772942557ab5355d76af442f8f65e01";
+
+    #[test]
+    fn test_path_for_prefers_sha512_and_rejects_bad_hashes() {
+        let store = ContentStore::new("/store");
+        let sha512 = "A".repeat(128);
+
+        assert_eq!(
+            store.path_for(&[
+                Checksum::Sha1(SHA1.to_string()),
+                Checksum::Sha512(sha512.clone())
+            ]),
+            Some(PathBuf::from("/store/sha512/aa").join(sha512.to_lowercase()))
+        );
+        assert_eq!(
+            store.path_for(&[Checksum::Sha1(SHA1.to_string())]),
+            Some(PathBuf::from("/store/sha1/0a").join(SHA1))
+        );
+
+        assert_eq!(
+            store.path_for(&[Checksum::Sha1("../../etc".to_string())]),
+            None
+        );
+        assert_eq!(store.path_for(&[Checksum::Sha1("abc".to_string())]), None);
+        assert_eq!(store.path_for(&[Checksum::Murmur2(1)]), None);
+    }
+
+    #[test]
+    fn test_link_and_gc() {
+        let temp_dir = tempdir().unwrap();
+        let store = ContentStore::new(temp_dir.path().join("store"));
+
+        let linked = store.path_for(&[Checksum::Sha1(SHA1.to_string())]).unwrap();
+        let kept = store.path_for(&[Checksum::Sha1("1".repeat(40))]).unwrap();
+        let orphan = store.path_for(&[Checksum::Sha1("2".repeat(40))]).unwrap();
+
+        for path in [&linked, &kept, &orphan] {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "Hello, World!").unwrap();
+        }
+
+        let instance_file = temp_dir.path().join("instance").join("mods").join("a.jar");
+        link_file(&linked, &instance_file).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&instance_file).unwrap(),
+            "Hello, World!"
+        );
+
+        assert_eq!(
+            store.disk_usage().unwrap(),
+            StoreUsage {
+                files: 3,
+                bytes: 39
+            }
+        );
+
+        let keep_hash = "1".repeat(40);
+        let removed = store
+            .gc(|algorithm, hash| {
+                algorithm == StoreAlgorithm::Sha1 && (hash == keep_hash || hash == SHA1)
+            })
+            .unwrap();
+
+        assert_eq!(
+            removed,
+            Some(StoreUsage {
+                files: 1,
+                bytes: 13
+            })
+        );
+        assert!(linked.exists());
+        assert!(kept.exists());
+        assert!(!orphan.exists());
+    }
+
+    #[test]
+    fn test_recorded_links() {
+        let temp_dir = tempdir().unwrap();
+        let store = ContentStore::new(temp_dir.path().join("store"));
+
+        let entry = store.path_for(&[Checksum::Sha1(SHA1.to_string())]).unwrap();
+        std::fs::create_dir_all(entry.parent().unwrap()).unwrap();
+        std::fs::write(&entry, "Hello, World!").unwrap();
+
+        let instance_file = temp_dir.path().join("instance").join("mods").join("a.jar");
+        record_link(&entry, &instance_file).unwrap();
+        assert!(!has_recorded_links(&entry, 13));
+
+        std::fs::create_dir_all(instance_file.parent().unwrap()).unwrap();
+        std::fs::write(&instance_file, "Hello, World!").unwrap();
+        assert!(has_recorded_links(&entry, 13));
+
+        // the file was replaced by another one
+        std::fs::write(&instance_file, "Goodbye").unwrap();
+        assert!(!has_recorded_links(&entry, 13));
+
+        let removed = store.gc(|_, _| false).unwrap();
+        assert_eq!(removed.map(|usage| usage.files), Some(1));
+        assert!(!entry.exists());
+        assert!(!links_path(&entry).exists());
+    }
+
+    #[tokio::test]
+    async fn test_gc_waits_for_downloads() {
+        let temp_dir = tempdir().unwrap();
+        let store = ContentStore::new(temp_dir.path().join("store"));
+
+        let entry = store.path_for(&[Checksum::Sha1(SHA1.to_string())]).unwrap();
+        std::fs::create_dir_all(entry.parent().unwrap()).unwrap();
+        std::fs::write(&entry, "Hello, World!").unwrap();
+
+        let in_use = store.in_use();
+        let download = in_use.read().await;
+        assert_eq!(store.gc(|_, _| false).unwrap(), None);
+        assert!(entry.exists());
+
+        drop(download);
+        assert!(store.gc(|_, _| false).unwrap().is_some());
+        assert!(!entry.exists());
+    }
+}
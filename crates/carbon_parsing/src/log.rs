@@ -1,29 +1,274 @@
-//! This module provides support for parsing log4j messages.
+//! This module provides support for parsing the game output.
+//!
+//! Every entry is detected on its own, so the log4j XML layout, log4j's JSON layout, the plain
+//! `[HH:MM:SS] [thread/LEVEL]: message` text layout and raw lines can all be mixed in the same
+//! stream.
+//!
+//! Java stack traces are attached to the entry they were printed after as a [`Throwable`].
 
+use std::{borrow::Cow, fmt};
+
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeZone};
 use nom::{
     branch::alt,
     bytes::streaming::{tag, take_until},
     character::streaming::{char, multispace0, u64},
-    combinator::{map, value},
+    combinator::{map, opt, value},
     error::ParseError,
     multi::count,
-    sequence::{delimited, preceded, separated_pair, tuple},
-    IResult,
+    sequence::{delimited, separated_pair, tuple},
+    IResult, Needed,
 };
+use serde::Deserialize;
+
+/// Used for the logger and thread of entries whose layout doesn't include them.
+const UNKNOWN: &str = "N/A";
+
+const XML_EVENT_TAG: &str = "<log4j:Event";
 
-/// Represents a parsed log4j message.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+// an unterminated JSON object longer than this is treated as raw text
+const MAX_JSON_EVENT_LEN: usize = 1024 * 1024;
+
+/// Represents a parsed log message.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct LogEntry<'a> {
     /// The name of the logger.
-    pub logger: &'a str,
+    pub logger: Cow<'a, str>,
     /// The log level of the entry.
     pub level: LogEntryLevel,
     /// The time the event was logged.
     pub timestamp: u64,
     /// The name of the thread.
-    pub thread_name: &'a str,
-    /// The log message.
-    pub message: &'a str,
+    pub thread_name: Cow<'a, str>,
+    /// The log message, including the stack trace when there is one.
+    pub message: Cow<'a, str>,
+    /// The exception logged with the entry.
+    pub throwable: Option<Throwable<'a>>,
+}
+
+/// A Java exception, with the chain of exceptions that caused it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Throwable<'a> {
+    /// The fully qualified class name of the exception.
+    pub class: Cow<'a, str>,
+    /// The exception message.
+    pub message: Option<Cow<'a, str>>,
+    /// The stack frames, starting with the one that threw.
+    pub frames: Vec<StackFrame<'a>>,
+    /// The exception that caused this one.
+    pub cause: Option<Box<Throwable<'a>>>,
+}
+
+/// A single `at ...` line of a stack trace.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StackFrame<'a> {
+    /// The method, qualified by its class, i.e. `net.minecraft.client.main.Main.main`.
+    pub method: Cow<'a, str>,
+    /// Where in the source the method was, i.e. `Main.java:42` or `Native Method`.
+    pub location: Option<Cow<'a, str>>,
+}
+
+impl<'a> Throwable<'a> {
+    fn new(class: &'a str, message: Option<&'a str>) -> Self {
+        Self {
+            class: class.into(),
+            message: message.map(Cow::Borrowed),
+            frames: Vec::new(),
+            cause: None,
+        }
+    }
+
+    /// Iterates over this exception and the ones that caused it.
+    pub fn chain(&self) -> impl Iterator<Item = &Throwable<'a>> {
+        std::iter::successors(Some(self), |throwable| throwable.cause.as_deref())
+    }
+
+    /// Converts the exception into one that doesn't borrow from the log.
+    pub fn into_owned(self) -> Throwable<'static> {
+        Throwable {
+            class: self.class.into_owned().into(),
+            message: self.message.map(|message| message.into_owned().into()),
+            frames: self
+                .frames
+                .into_iter()
+                .map(|frame| StackFrame {
+                    method: frame.method.into_owned().into(),
+                    location: frame.location.map(|location| location.into_owned().into()),
+                })
+                .collect(),
+            cause: self.cause.map(|cause| Box::new(cause.into_owned())),
+        }
+    }
+}
+
+/// Prints the exception the way the JVM does.
+impl fmt::Display for Throwable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, throwable) in self.chain().enumerate() {
+            if i > 0 {
+                write!(f, "\nCaused by: ")?;
+            }
+
+            write!(f, "{}", throwable.class)?;
+            if let Some(message) = &throwable.message {
+                write!(f, ": {message}")?;
+            }
+
+            for frame in &throwable.frames {
+                write!(f, "\n\tat {}", frame.method)?;
+                if let Some(location) = &frame.location {
+                    write!(f, "({location})")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a stack trace as printed by `Throwable::printStackTrace`.
+///
+/// Suppressed exceptions and lines the JVM elided with `... N more` are skipped.
+pub fn parse_throwable(text: &str) -> Option<Throwable<'_>> {
+    let mut chain: Vec<Throwable> = Vec::new();
+    let mut suppressed_indent = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        match suppressed_indent {
+            Some(suppressed) if indent > suppressed => continue,
+            _ => suppressed_indent = None,
+        }
+
+        if trimmed.starts_with("Suppressed: ") {
+            suppressed_indent = Some(indent);
+        } else if let Some(cause) = trimmed.strip_prefix("Caused by: ") {
+            if let Some((class, message)) = exception_header(cause) {
+                chain.push(Throwable::new(class, message));
+            }
+        } else if let Some(frame) = trimmed.strip_prefix("at ") {
+            if let Some(throwable) = chain.last_mut() {
+                throwable.frames.push(stack_frame(frame.trim_end()));
+            }
+        } else if chain.is_empty() {
+            if let Some((class, message)) = exception_header(trimmed) {
+                chain.push(Throwable::new(class, message));
+            }
+        }
+    }
+
+    chain.into_iter().rev().reduce(|cause, mut throwable| {
+        throwable.cause = Some(Box::new(cause));
+        throwable
+    })
+}
+
+/// Parses a frame like `net.minecraft.client.main.Main.main(Main.java:42) ~[client.jar:?]`.
+fn stack_frame(frame: &str) -> StackFrame {
+    match frame.split_once('(') {
+        Some((method, rest)) => StackFrame {
+            method: method.into(),
+            location: rest.split_once(')').map(|(location, _)| location.into()),
+        },
+        None => StackFrame {
+            method: frame.into(),
+            location: None,
+        },
+    }
+}
+
+/// Whether `name` looks like the class name of an exception, i.e. `java.lang.RuntimeException`.
+fn is_exception_class(name: &str) -> bool {
+    name.contains('.')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '.' | '_' | '$'))
+        && ["Exception", "Error", "Throwable"]
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+}
+
+/// Splits the first line of a stack trace into the exception class and message.
+fn exception_header(line: &str) -> Option<(&str, Option<&str>)> {
+    let line = line.trim();
+    let line = line
+        .strip_prefix("Exception in thread \"")
+        .and_then(|rest| rest.split_once("\" "))
+        .map_or(line, |(_, rest)| rest);
+
+    let (class, message) = match line.split_once(':') {
+        Some((class, message)) => (class, Some(message.trim()).filter(|m| !m.is_empty())),
+        None => (line, None),
+    };
+
+    is_exception_class(class).then_some((class, message))
+}
+
+/// Whether `line` continues a stack trace, `header` allows it to start one.
+fn is_trace_line(line: &str, header: bool) -> bool {
+    let trimmed = line.trim_start();
+
+    (trimmed.starts_with("at ") && trimmed.len() < line.len())
+        || trimmed.starts_with("Caused by: ")
+        || trimmed.starts_with("Suppressed: ")
+        || (trimmed.starts_with("... ") && trimmed.trim_end().ends_with(" more"))
+        || (header && exception_header(trimmed).is_some())
+}
+
+/// Whether the partially received `line` might still turn into a stack trace line.
+fn may_be_trace_line(line: &str, header: bool) -> bool {
+    let trimmed = line.trim_start();
+
+    let keyword = ["at ", "Caused by: ", "Suppressed: ", "... "]
+        .iter()
+        .any(|keyword| keyword.starts_with(trimmed) || trimmed.starts_with(keyword));
+
+    let class = match trimmed.split_once(':') {
+        Some((class, _)) => is_exception_class(class),
+        None => trimmed
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '.' | '_' | '$')),
+    };
+
+    keyword || (header && class)
+}
+
+/// The length of the stack trace at the start of `input`.
+///
+/// Unless `eof` is set, returns [`nom::Err::Incomplete`] when the trace runs to the end of
+/// `input`, as the next read may continue it, or when the last line hasn't been fully received
+/// and could still belong to the trace.
+fn trace_len(
+    input: &str,
+    header: bool,
+    eof: bool,
+) -> Result<usize, nom::Err<nom::error::Error<&str>>> {
+    let mut len = 0;
+
+    for line in input.split_inclusive('\n') {
+        let header = header && len == 0;
+
+        if !line.ends_with('\n') && !eof {
+            if may_be_trace_line(line, header) {
+                return Err(nom::Err::Incomplete(Needed::Unknown));
+            }
+            break;
+        }
+
+        if !is_trace_line(line, header) {
+            break;
+        }
+
+        len += line.len();
+    }
+
+    if len == input.len() && !eof {
+        return Err(nom::Err::Incomplete(Needed::Unknown));
+    }
+
+    Ok(len)
 }
 
 /// The log level of the log entry.
@@ -51,18 +296,110 @@ where
     delimited(multispace0, inner, multispace0)
 }
 
-/// Parses a log4j event.
+fn now_millis() -> u64 {
+    Local::now().timestamp_millis() as u64
+}
+
+/// Parses the next log entry, whatever its layout.
+///
+/// Returns [`nom::Err::Incomplete`] until a whole entry is available. Text and raw entries may
+/// be followed by a stack trace, so they are only returned once the line after them has started
+/// and turned out not to belong to one. Use [`parse_last_log_entry`] once no more input will
+/// arrive.
 pub fn parse_log_entry(input: &str) -> IResult<&str, LogEntry> {
-    let (o, (attributes, _, message)) = preceded(
-        multispace0,
-        alt((
-            delimited(
-                tag("<log4j:Event"),
-                tuple((attributes, tag(">"), whitespace(message))),
-                tag("</log4j:Event>"),
-            ),
-            plain_text,
+    log_entry(input, false)
+}
+
+/// Like [`parse_log_entry`], for input that has ended, e.g. once the game's output was closed.
+///
+/// The entry at the end of the input is returned even without a following line or newline.
+/// Unterminated XML and JSON events are still [`nom::Err::Incomplete`].
+pub fn parse_last_log_entry(input: &str) -> IResult<&str, LogEntry> {
+    log_entry(input, true)
+}
+
+fn log_entry(input: &str, eof: bool) -> IResult<&str, LogEntry> {
+    // drop blank lines but keep the indentation of the first line with content
+    let blank = input.len() - input.trim_start().len();
+    let input = &input[input[..blank].rfind('\n').map_or(0, |i| i + 1)..];
+    let trimmed = input.trim_start();
+
+    if trimmed.is_empty() {
+        return Err(nom::Err::Incomplete(Needed::Unknown));
+    }
+
+    if trimmed.starts_with(XML_EVENT_TAG) || XML_EVENT_TAG.starts_with(trimmed) {
+        match xml_event(trimmed) {
+            // not a well formed event, keep it as raw text
+            Err(nom::Err::Error(_) | nom::Err::Failure(_)) => {}
+            result => return result,
+        }
+    }
+
+    if let Some(result) = json_event(trimmed) {
+        return result;
+    }
+
+    let end = match line_end(input) {
+        Some(end) => end,
+        None if eof => input.len(),
+        None => return Err(nom::Err::Incomplete(Needed::Unknown)),
+    };
+
+    let line = input[..end].trim_end();
+    let rest = &input[end..];
+    let rest = rest.strip_prefix('\n').unwrap_or(rest);
+
+    if let Some(mut entry) = text_line(line.trim_start()) {
+        // a stack trace printed right after the entry belongs to it
+        let len = trace_len(rest, true, eof)?;
+        let trace = rest[..len].trim_end();
+
+        if !trace.is_empty() {
+            entry.message = format!("{}\n{trace}", entry.message).into();
+            entry.throwable = parse_throwable(trace);
+        }
+
+        return Ok((&rest[len..], entry));
+    }
+
+    // the line itself may start the stack trace
+    let header = exception_header(line).is_some();
+    let len = trace_len(rest, !header, eof)?;
+    let end = input.len() - rest.len() + len;
+    let text = input[..end].trim_end();
+
+    let mut entry = raw_line(text);
+    entry.throwable = parse_throwable(text);
+    if entry.throwable.is_some() {
+        entry.level = LogEntryLevel::Error;
+    }
+
+    Ok((&input[end..], entry))
+}
+
+/// Where the line at the start of `input` ends, either at a newline or where an XML event
+/// starts right after it.
+fn line_end(input: &str) -> Option<usize> {
+    let newline = input.find('\n');
+
+    match input.find(XML_EVENT_TAG).filter(|&start| start > 0) {
+        Some(start) => Some(newline.map_or(start, |newline| newline.min(start))),
+        None => newline,
+    }
+}
+
+/// Parses a log4j XML event.
+fn xml_event(input: &str) -> IResult<&str, LogEntry> {
+    let (o, (attributes, _, message, trace)) = delimited(
+        tag(XML_EVENT_TAG),
+        tuple((
+            attributes,
+            tag(">"),
+            whitespace(message),
+            opt(whitespace(throwable)),
         )),
+        tag("</log4j:Event>"),
     )(input)?;
 
     let Attributes {
@@ -75,11 +412,15 @@ pub fn parse_log_entry(input: &str) -> IResult<&str, LogEntry> {
     Ok((
         o,
         LogEntry {
-            logger,
+            logger: logger.into(),
             level,
             timestamp,
-            thread_name,
-            message,
+            thread_name: thread_name.into(),
+            message: match trace {
+                Some(trace) => format!("{message}\n{}", trace.trim_end()).into(),
+                None => message.into(),
+            },
+            throwable: trace.and_then(parse_throwable),
         },
     ))
 }
@@ -215,20 +556,307 @@ fn message(input: &str) -> IResult<&str, &str> {
     )(input)
 }
 
-fn plain_text(input: &str) -> IResult<&str, (Attributes, &str, &str)> {
-    map(take_until("<"), |text| {
-        (
-            Attributes {
-                logger: "GDLauncher",
-                level: LogEntryLevel::Info,
-                timestamp: chrono::Local::now().timestamp_millis() as u64,
-                thread_name: "N/A",
-            },
-            //  this is just to satisfy the type checker for the `alt` this is used in
-            "",
-            text,
-        )
-    })(input)
+/// Parses the stack trace of the event.
+fn throwable(input: &str) -> IResult<&str, &str> {
+    delimited(
+        tag("<log4j:Throwable>"),
+        whitespace(delimited(tag("<![CDATA["), take_until("]]>"), tag("]]>"))),
+        tag("</log4j:Throwable>"),
+    )(input)
+}
+
+/// Parses a level name, including the ones used by `java.util.logging`.
+fn level_from_name(name: &str) -> Option<LogEntryLevel> {
+    match name.trim().to_ascii_uppercase().as_str() {
+        "TRACE" | "FINER" | "FINEST" => Some(LogEntryLevel::Trace),
+        "DEBUG" | "FINE" => Some(LogEntryLevel::Debug),
+        "INFO" | "CONFIG" => Some(LogEntryLevel::Info),
+        "WARN" | "WARNING" => Some(LogEntryLevel::Warn),
+        "ERROR" | "FATAL" | "SEVERE" => Some(LogEntryLevel::Error),
+        _ => None,
+    }
+}
+
+fn local_timestamp(datetime: NaiveDateTime) -> Option<u64> {
+    Local
+        .from_local_datetime(&datetime)
+        .earliest()
+        .map(|datetime| datetime.timestamp_millis() as u64)
+}
+
+/// Parses the time of a text entry, lines without a date are assumed to be from today.
+fn text_timestamp(time: &str) -> Option<u64> {
+    let datetime = NaiveDateTime::parse_from_str(time, "%d%b%Y %H:%M:%S%.f")
+        .ok()
+        .or_else(|| {
+            NaiveTime::parse_from_str(time, "%H:%M:%S%.f")
+                .ok()
+                .map(|time| Local::now().date_naive().and_time(time))
+        })?;
+
+    local_timestamp(datetime)
+}
+
+/// Parses a line of the `[HH:MM:SS] [thread/LEVEL]: message` layout, the
+/// `[time] [thread/LEVEL] [logger]: message` variant used by forge, or the
+/// `YYYY-MM-DD HH:MM:SS [LEVEL] [logger] message` layout of versions before 1.7.
+fn text_line(line: &str) -> Option<LogEntry> {
+    if let Some(rest) = line.strip_prefix('[') {
+        let (time, rest) = rest.split_once("] [")?;
+        let (source, rest) = rest.split_once(']')?;
+        let (thread_name, level) = source.rsplit_once('/')?;
+
+        let (logger, message) = match rest.strip_prefix(':') {
+            Some(message) => (UNKNOWN, message),
+            None => {
+                let (logger, message) = rest.strip_prefix(" [")?.split_once("]:")?;
+                (logger.trim_end_matches('/'), message)
+            }
+        };
+
+        return Some(LogEntry {
+            logger: logger.into(),
+            level: level_from_name(level)?,
+            timestamp: text_timestamp(time)?,
+            thread_name: thread_name.into(),
+            message: message.strip_prefix(' ').unwrap_or(message).into(),
+            throwable: None,
+        });
+    }
+
+    let datetime = NaiveDateTime::parse_from_str(line.get(..19)?, "%Y-%m-%d %H:%M:%S").ok()?;
+    let (level, rest) = line.get(19..)?.strip_prefix(" [")?.split_once(']')?;
+    let rest = rest.strip_prefix(' ').unwrap_or(rest);
+
+    let (logger, message) = match rest
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once("] "))
+    {
+        Some((logger, message)) => (logger, message),
+        None => (UNKNOWN, rest),
+    };
+
+    Some(LogEntry {
+        logger: logger.into(),
+        level: level_from_name(level)?,
+        timestamp: local_timestamp(datetime)?,
+        thread_name: UNKNOWN.into(),
+        message: message.into(),
+        throwable: None,
+    })
+}
+
+/// Guesses the level of a line printed outside of any logger, like stack traces, JVM crashes
+/// and mods writing to stdout directly.
+fn raw_level(line: &str) -> LogEntryLevel {
+    let trimmed = line.trim_start();
+    let first_word = trimmed.split_whitespace().next().unwrap_or_default();
+
+    let is_error = trimmed.starts_with("Exception in thread")
+        || trimmed.starts_with("Caused by:")
+        || (trimmed.starts_with("at ") && trimmed.len() < line.len())
+        || (trimmed.starts_with("... ") && trimmed.ends_with(" more"))
+        || first_word.ends_with("Exception:")
+        || first_word.ends_with("Error:")
+        || line.starts_with('#')
+        || ["[ERROR]", "[FATAL]", "[SEVERE]"]
+            .iter()
+            .any(|level| line.contains(level));
+
+    if is_error {
+        LogEntryLevel::Error
+    } else if trimmed.starts_with("WARNING:")
+        || ["[WARN]", "[WARNING]"]
+            .iter()
+            .any(|level| line.contains(level))
+    {
+        LogEntryLevel::Warn
+    } else {
+        LogEntryLevel::Info
+    }
+}
+
+fn raw_line(line: &str) -> LogEntry {
+    LogEntry {
+        logger: UNKNOWN.into(),
+        level: raw_level(line),
+        timestamp: now_millis(),
+        thread_name: UNKNOWN.into(),
+        message: line.into(),
+        throwable: None,
+    }
+}
+
+/// A log4j `JsonLayout` event, the ECS style field names of `JsonTemplateLayout` are accepted
+/// as well.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonEvent<'a> {
+    #[serde(borrow, alias = "log.logger")]
+    logger_name: Option<Cow<'a, str>>,
+    #[serde(borrow, alias = "log.level")]
+    level: Option<Cow<'a, str>>,
+    #[serde(borrow, alias = "process.thread.name")]
+    thread: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    message: Option<Cow<'a, str>>,
+    time_millis: Option<u64>,
+    instant: Option<JsonInstant>,
+    #[serde(borrow, rename = "@timestamp")]
+    iso_timestamp: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    thrown: Option<JsonThrown<'a>>,
+    #[serde(borrow, rename = "error.stack_trace")]
+    stack_trace: Option<Cow<'a, str>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonInstant {
+    epoch_second: u64,
+    nano_of_second: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonThrown<'a> {
+    #[serde(borrow)]
+    name: Option<Cow<'a, str>>,
+    #[serde(borrow)]
+    message: Option<Cow<'a, str>>,
+    #[serde(borrow, default, alias = "stackTrace")]
+    extended_stack_trace: Vec<JsonFrame<'a>>,
+    #[serde(borrow)]
+    cause: Option<Box<JsonThrown<'a>>>,
+}
+
+impl<'a> JsonThrown<'a> {
+    fn into_throwable(self) -> Throwable<'a> {
+        Throwable {
+            class: self.name.unwrap_or(Cow::Borrowed("java.lang.Throwable")),
+            message: self.message,
+            frames: self
+                .extended_stack_trace
+                .into_iter()
+                .map(|frame| StackFrame {
+                    method: format!("{}.{}", frame.class, frame.method).into(),
+                    location: Some(match (frame.file, frame.line) {
+                        (Some(file), Some(line)) if line >= 0 => format!("{file}:{line}").into(),
+                        (Some(file), _) => file,
+                        (None, _) => Cow::Borrowed("Unknown Source"),
+                    }),
+                })
+                .collect(),
+            cause: self.cause.map(|cause| Box::new(cause.into_throwable())),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonFrame<'a> {
+    #[serde(borrow)]
+    class: Cow<'a, str>,
+    #[serde(borrow)]
+    method: Cow<'a, str>,
+    #[serde(borrow)]
+    file: Option<Cow<'a, str>>,
+    line: Option<i64>,
+}
+
+impl<'a> JsonEvent<'a> {
+    fn into_entry(self) -> Option<LogEntry<'a>> {
+        let mut message = self.message?;
+
+        let timestamp = self
+            .time_millis
+            .or_else(|| {
+                self.instant
+                    .map(|instant| instant.epoch_second * 1000 + instant.nano_of_second / 1_000_000)
+            })
+            .or_else(|| {
+                DateTime::parse_from_rfc3339(self.iso_timestamp.as_deref()?)
+                    .ok()
+                    .map(|datetime| datetime.timestamp_millis() as u64)
+            })
+            .unwrap_or_else(now_millis);
+
+        let throwable = match (self.thrown, &self.stack_trace) {
+            (Some(thrown), _) => Some(thrown.into_throwable()),
+            (None, Some(stack_trace)) => parse_throwable(stack_trace).map(Throwable::into_owned),
+            (None, None) => None,
+        };
+
+        // stack traces are appended the way log4j prints them in the text layouts
+        if let Some(throwable) = &throwable {
+            message = format!("{message}\n{throwable}").into();
+        } else if let Some(stack_trace) = &self.stack_trace {
+            message = format!("{message}\n{}", stack_trace.trim_end()).into();
+        }
+
+        Some(LogEntry {
+            logger: self.logger_name.unwrap_or(Cow::Borrowed(UNKNOWN)),
+            level: self
+                .level
+                .and_then(|level| level_from_name(&level))
+                .unwrap_or(LogEntryLevel::Info),
+            timestamp,
+            thread_name: self.thread.unwrap_or(Cow::Borrowed(UNKNOWN)),
+            message,
+            throwable,
+        })
+    }
+}
+
+/// The length of the JSON object at the start of `input`, `None` while it isn't closed yet.
+fn json_object_len(input: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, byte) in input.bytes().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Parses a log4j JSON event, `None` when `input` doesn't hold one.
+fn json_event(input: &str) -> Option<IResult<&str, LogEntry>> {
+    let body = input.strip_prefix('{')?.trim_start();
+    if !body.is_empty() && !body.starts_with('"') {
+        return None;
+    }
+
+    let Some(len) = json_object_len(input) else {
+        return (input.len() < MAX_JSON_EVENT_LEN)
+            .then_some(Err(nom::Err::Incomplete(Needed::Unknown)));
+    };
+
+    let entry = serde_json::from_str::<JsonEvent>(&input[..len])
+        .ok()?
+        .into_entry()?;
+
+    Some(Ok((&input[len..], entry)))
 }
 
 #[cfg(test)]
@@ -333,6 +961,7 @@ mod tests {
                 timestamp: 1699556020363,
                 thread_name: "Datafixer Bootstrap".into(),
                 message: "192 Datafixer optimizations took 1128 milliseconds".into(),
+                throwable: None,
             }
         );
     }
@@ -347,4 +976,253 @@ mod tests {
 
         assert_eq!(input, "exit code: 0");
     }
+
+    #[test]
+    fn parse_text_entry() {
+        let (o, entry) =
+            parse_log_entry("[22:13:20] [Render thread/WARN]: Missing sound for event\n[22:13")
+                .unwrap();
+
+        assert_eq!(o, "[22:13");
+        assert_eq!(entry.logger, UNKNOWN);
+        assert_eq!(entry.level, LogEntryLevel::Warn);
+        assert_eq!(entry.thread_name, "Render thread");
+        assert_eq!(entry.message, "Missing sound for event");
+    }
+
+    #[test]
+    fn parse_forge_text_entry() {
+        let (_, entry) = parse_last_log_entry(
+            "[14Nov2023 22:13:20.123] [main/INFO] [cpw.mods.modlauncher.Launcher/MODLAUNCHER]: \
+             ModLauncher running\n",
+        )
+        .unwrap();
+
+        assert_eq!(entry.logger, "cpw.mods.modlauncher.Launcher/MODLAUNCHER");
+        assert_eq!(entry.level, LogEntryLevel::Info);
+        assert_eq!(entry.thread_name, "main");
+        assert_eq!(entry.message, "ModLauncher running");
+    }
+
+    #[test]
+    fn parse_legacy_text_entry() {
+        let (_, entry) = parse_last_log_entry(
+            "2013-07-01 12:00:00 [SEVERE] [Minecraft-Server] Can't keep up!\n",
+        )
+        .unwrap();
+
+        assert_eq!(entry.logger, "Minecraft-Server");
+        assert_eq!(entry.level, LogEntryLevel::Error);
+        assert_eq!(entry.message, "Can't keep up!");
+    }
+
+    #[test]
+    fn parse_json_entry() {
+        let (o, entry) = parse_log_entry(
+            r#"{
+              "instant" : { "epochSecond" : 1699556020, "nanoOfSecond" : 363000000 },
+              "thread" : "main",
+              "level" : "ERROR",
+              "loggerName" : "net.minecraft.client.Minecraft",
+              "message" : "Crashed {\"braces\"}",
+              "thrown" : {
+                "name" : "java.lang.IllegalStateException",
+                "message" : "oops",
+                "extendedStackTrace" : [
+                  { "class" : "a.B", "method" : "run", "file" : "B.java", "line" : 7 }
+                ]
+              }
+            }
+            rest"#,
+        )
+        .unwrap();
+
+        assert_eq!(o.trim_start(), "rest");
+        assert_eq!(
+            entry,
+            LogEntry {
+                logger: "net.minecraft.client.Minecraft".into(),
+                level: LogEntryLevel::Error,
+                timestamp: 1699556020363,
+                thread_name: "main".into(),
+                message: "Crashed {\"braces\"}\njava.lang.IllegalStateException: oops\n\tat \
+                          a.B.run(B.java:7)"
+                    .into(),
+                throwable: Some(Throwable {
+                    class: "java.lang.IllegalStateException".into(),
+                    message: Some("oops".into()),
+                    frames: vec![StackFrame {
+                        method: "a.B.run".into(),
+                        location: Some("B.java:7".into()),
+                    }],
+                    cause: None,
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_incomplete_json_entry() {
+        assert!(matches!(
+            parse_log_entry(r#"{"message" : "half"#),
+            Err(nom::Err::Incomplete(_))
+        ));
+    }
+
+    #[test]
+    fn parse_raw_entries() {
+        let mut input = "Exception in thread \"main\" java.lang.RuntimeException: boom\n\
+                         \tat a.B.main(B.java:3)\n\
+                         Hello from a mod\n\
+                         <log4j:Event";
+        let mut entries = Vec::new();
+
+        while let Ok((o, entry)) = parse_log_entry(input) {
+            entries.push(entry);
+            input = o;
+        }
+
+        assert_eq!(input, "<log4j:Event");
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.level, entry.message.as_ref()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    LogEntryLevel::Error,
+                    "Exception in thread \"main\" java.lang.RuntimeException: boom\n\tat \
+                     a.B.main(B.java:3)"
+                ),
+                (LogEntryLevel::Info, "Hello from a mod"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_stack_trace() {
+        let throwable = parse_throwable(
+            "java.lang.RuntimeException: outer\n\
+             \tat a.B.run(B.java:7) ~[mod.jar:?]\n\
+             \tSuppressed: java.io.IOException: close\n\
+             \t\tat a.C.close(C.java:1)\n\
+             Caused by: java.lang.NullPointerException\n\
+             \tat a.D.get(Native Method)\n\
+             \t... 1 more",
+        )
+        .unwrap();
+
+        assert_eq!(
+            throwable,
+            Throwable {
+                class: "java.lang.RuntimeException".into(),
+                message: Some("outer".into()),
+                frames: vec![StackFrame {
+                    method: "a.B.run".into(),
+                    location: Some("B.java:7".into()),
+                }],
+                cause: Some(Box::new(Throwable {
+                    class: "java.lang.NullPointerException".into(),
+                    message: None,
+                    frames: vec![StackFrame {
+                        method: "a.D.get".into(),
+                        location: Some("Native Method".into()),
+                    }],
+                    cause: None,
+                })),
+            }
+        );
+        assert_eq!(
+            throwable.to_string(),
+            "java.lang.RuntimeException: outer\n\tat a.B.run(B.java:7)\nCaused by: \
+             java.lang.NullPointerException\n\tat a.D.get(Native Method)"
+        );
+    }
+
+    #[test]
+    fn group_stack_trace_with_entry() {
+        let input = "[22:13:20] [main/ERROR]: Failed to load mod\n\
+                     java.lang.IllegalStateException: broken\n\
+                     \tat a.B.load(B.java:3)\n\
+                     [22:13:21] [main/INFO]: Done\n";
+
+        let (o, entry) = parse_log_entry(input).unwrap();
+
+        assert_eq!(o, "[22:13:21] [main/INFO]: Done\n");
+        assert_eq!(
+            entry.message,
+            "Failed to load mod\njava.lang.IllegalStateException: broken\n\tat a.B.load(B.java:3)"
+        );
+        let throwable = entry.throwable.unwrap();
+        assert_eq!(throwable.class, "java.lang.IllegalStateException");
+        assert_eq!(throwable.frames.len(), 1);
+    }
+
+    #[test]
+    fn wait_for_stack_trace() {
+        assert!(matches!(
+            parse_log_entry("[22:13:20] [main/ERROR]: Failed to load mod\njava.lang.Ill"),
+            Err(nom::Err::Incomplete(_))
+        ));
+        assert!(parse_log_entry("[22:13:20] [main/INFO]: Loading\n[22:13").is_ok());
+    }
+
+    #[test]
+    fn stack_trace_in_separate_chunks() {
+        let chunks = [
+            "[22:13:20] [main/ERROR]: Failed to load mod\n",
+            "java.lang.IllegalStateException: broken\n",
+            "\tat a.B.load(B.java:3)\n",
+            "\tat a.B.main(B.java:1)\n",
+            "[22:13:21] [main/INFO]: Done",
+        ];
+
+        let mut buf = String::new();
+        let mut entries = Vec::new();
+
+        for chunk in chunks {
+            buf.push_str(chunk);
+
+            let mut input = buf.as_str();
+            while let Ok((o, entry)) = parse_log_entry(input) {
+                entries.push(entry.message.into_owned());
+                input = o;
+            }
+            buf = input.to_string();
+        }
+
+        // the last entry could still get a trace until the input ends
+        assert_eq!(
+            entries,
+            [
+                "Failed to load mod\njava.lang.IllegalStateException: broken\n\tat \
+              a.B.load(B.java:3)\n\tat a.B.main(B.java:1)"
+            ]
+        );
+
+        let (o, entry) = parse_last_log_entry(&buf).unwrap();
+        assert_eq!(o, "");
+        assert_eq!(entry.message, "Done");
+    }
+
+    #[test]
+    fn parse_xml_throwable() {
+        let (_, entry) = parse_log_entry(
+            r#"<log4j:Event logger="net.minecraft.client.Minecraft" timestamp="1699556020363" level="ERROR" thread="Render thread">
+                <log4j:Message><![CDATA[Crashed]]></log4j:Message>
+                <log4j:Throwable><![CDATA[java.lang.NullPointerException: null
+	at a.B.render(B.java:12)
+]]></log4j:Throwable>
+            </log4j:Event>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            entry.message,
+            "Crashed\njava.lang.NullPointerException: null\n\tat a.B.render(B.java:12)"
+        );
+        let throwable = entry.throwable.unwrap();
+        assert_eq!(throwable.class, "java.lang.NullPointerException");
+        assert_eq!(throwable.message.as_deref(), Some("null"));
+    }
 }
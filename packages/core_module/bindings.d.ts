@@ -21,6 +21,8 @@ export type Procedures = {
         { key: "instance.getImportableEntities", input: never, result: ImportEntityStatus[] } | 
         { key: "instance.getInstanceDetails", input: FEInstanceId | null, result: InstanceDetails | null } | 
         { key: "instance.getInstanceMods", input: FEInstanceId | null, result: Mod[] | null } | 
+        { key: "instance.getInterruptedInstalls", input: never, result: FEInterruptedInstall[] } | 
+        { key: "instance.getLogExceptions", input: GameLogId, result: GameLogException[] } | 
         { key: "instance.getLogs", input: FEInstanceId, result: GameLogEntry[] } | 
         { key: "instance.getModSources", input: FEInstanceId, result: ModSources } | 
         { key: "instance.getModpackInfo", input: FEInstanceId | null, result: FEInstanceModpackInfo | null } | 
@@ -58,9 +60,12 @@ export type Procedures = {
         { key: "modplatforms.modrinth.getVersions", input: MRFEVersionIDs, result: MRFEVersionsResponse } | 
         { key: "modplatforms.modrinth.search", input: MRFEProjectSearchParameters, result: MRFEProjectSearchResponse } | 
         { key: "modplatforms.unifiedSearch", input: FEUnifiedSearchParameters, result: FEUnifiedSearchResponse } | 
+        { key: "settings.getNetworkStatus", input: never, result: FENetworkStatus } | 
         { key: "settings.getPrivacyStatementBody", input: never, result: string } | 
         { key: "settings.getSettings", input: never, result: FESettings } | 
         { key: "settings.getTermsOfServiceBody", input: never, result: string } | 
+        { key: "systeminfo.getDownloadStoreUsage", input: never, result: FEDownloadStoreUsage } | 
+        { key: "systeminfo.getHttpCacheStats", input: never, result: FEHttpCacheStats } | 
         { key: "systeminfo.getTotalRAM", input: never, result: string } | 
         { key: "systeminfo.getUsedRAM", input: never, result: string } | 
         { key: "vtask.getTask", input: FETaskId | null, result: FETask | null } | 
@@ -87,6 +92,7 @@ export type Procedures = {
         { key: "instance.deleteLog", input: GameLogId, result: null } | 
         { key: "instance.deleteMod", input: InstanceMod, result: null } | 
         { key: "instance.disableMod", input: InstanceMod, result: null } | 
+        { key: "instance.dismissInterruptedInstall", input: FEInstanceId, result: null } | 
         { key: "instance.duplicateInstance", input: DuplicateInstance, result: FEInstanceId } | 
         { key: "instance.enableMod", input: InstanceMod, result: null } | 
         { key: "instance.export", input: ExportArgs, result: FETaskId } | 
@@ -100,6 +106,7 @@ export type Procedures = {
         { key: "instance.moveInstance", input: MoveInstance, result: null } | 
         { key: "instance.openInstanceFolder", input: OpenInstanceFolder, result: null } | 
         { key: "instance.prepareInstance", input: FEInstanceId, result: FETaskId } | 
+        { key: "instance.resumeInterruptedInstall", input: FEInstanceId, result: FETaskId } | 
         { key: "instance.setFavorite", input: SetFavorite, result: null } | 
         { key: "instance.setImportScanTarget", input: [ImportEntity, string], result: null } | 
         { key: "instance.updateInstance", input: FEUpdateInstance, result: null } | 
@@ -112,7 +119,10 @@ export type Procedures = {
         { key: "java.updateJavaProfile", input: FEUpdateJavaProfileArgs, result: null } | 
         { key: "java.validateCustomJavaPath", input: string, result: boolean } | 
         { key: "longRunning", input: never, result: boolean } | 
+        { key: "settings.checkConnectivity", input: never, result: boolean } | 
         { key: "settings.setSettings", input: FESettingsUpdate, result: null } | 
+        { key: "systeminfo.cleanDownloadStore", input: never, result: FEDownloadStoreUsage } | 
+        { key: "systeminfo.purgeHttpCache", input: FEHttpCachePurge, result: number } | 
         { key: "vtask.dismissTask", input: FETaskId, result: null },
     subscriptions: never
 };
@@ -121,7 +131,17 @@ export type FERequestNewEmailChangeStatus = { status: "success" } | { status: "f
 
 export type ListGroup = { id: FEGroupId; name: string }
 
-export type FESettingsUpdate = { theme?: Set<string> | null; language?: Set<string> | null; reducedMotion?: Set<boolean> | null; discordIntegration?: Set<boolean> | null; releaseChannel?: Set<FEReleaseChannel> | null; lastAppVersion?: Set<string | null> | null; concurrentDownloads?: Set<number> | null; downloadDependencies?: Set<boolean> | null; instancesSortBy?: Set<InstancesSortBy> | null; instancesSortByAsc?: Set<boolean> | null; instancesGroupBy?: Set<InstancesGroupBy> | null; instancesGroupByAsc?: Set<boolean> | null; instancesTileSize?: Set<number> | null; deletionThroughRecycleBin?: Set<boolean> | null; showNews?: Set<boolean> | null; showFeatured?: Set<boolean> | null; xmx?: Set<number> | null; xms?: Set<number> | null; preLaunchHook?: Set<string | null> | null; wrapperCommand?: Set<string | null> | null; postExitHook?: Set<string | null> | null; isFirstLaunch?: Set<boolean> | null; launcherActionOnGameLaunch?: Set<FELauncherActionOnGameLaunch> | null; showAppCloseWarning?: Set<boolean> | null; gameResolution?: Set<GameResolution | null> | null; javaCustomArgs?: Set<string> | null; autoManageJavaSystemProfiles?: Set<boolean> | null; modSources?: Set<ModSources> | null; termsAndPrivacyAccepted?: Set<boolean> | null; metricsEnabled?: Set<boolean> | null; gdlAccountId?: Set<string | null> | null }
+export type FESettingsUpdate = { theme?: Set<string> | null; language?: Set<string> | null; reducedMotion?: Set<boolean> | null; discordIntegration?: Set<boolean> | null; releaseChannel?: Set<FEReleaseChannel> | null; lastAppVersion?: Set<string | null> | null; concurrentDownloads?: Set<number> | null; concurrentDownloadsPerHost?: Set<number | null> | null; downloadBandwidthLimit?: Set<number | null> | null; mojangMirror?: Set<string | null> | null; httpCacheSizeLimit?: Set<number> | null; offlineMode?: Set<boolean> | null; downloadDependencies?: Set<boolean> | null; instancesSortBy?: Set<InstancesSortBy> | null; instancesSortByAsc?: Set<boolean> | null; instancesGroupBy?: Set<InstancesGroupBy> | null; instancesGroupByAsc?: Set<boolean> | null; instancesTileSize?: Set<number> | null; deletionThroughRecycleBin?: Set<boolean> | null; showNews?: Set<boolean> | null; showFeatured?: Set<boolean> | null; xmx?: Set<number> | null; xms?: Set<number> | null; preLaunchHook?: Set<string | null> | null; wrapperCommand?: Set<string | null> | null; postExitHook?: Set<string | null> | null; isFirstLaunch?: Set<boolean> | null; launcherActionOnGameLaunch?: Set<FELauncherActionOnGameLaunch> | null; showAppCloseWarning?: Set<boolean> | null; gameResolution?: Set<GameResolution | null> | null; javaCustomArgs?: Set<string> | null; autoManageJavaSystemProfiles?: Set<boolean> | null; modSources?: Set<ModSources> | null; proxy?: Set<FEProxySettingsUpdate> | null; termsAndPrivacyAccepted?: Set<boolean> | null; metricsEnabled?: Set<boolean> | null; gdlAccountId?: Set<string | null> | null }
+
+export type FEProxySettingsUpdate = { http: string | null; https: string | null; socks5: string | null; username: string | null; password?: Set<string | null> | null; noProxy: string[]; caBundle: string | null }
+
+export type FEDownloadStoreUsage = { files: number; bytes: string }
+
+export type FEHttpCacheStats = { entries: number; bytes: string; hits: number; misses: number; hitRate: number; hosts: FEHttpCacheHostStats[] }
+
+export type FEHttpCacheHostStats = { host: string; entries: number; bytes: string; hits: number; misses: number }
+
+export type FEHttpCachePurge = { hostOrPrefix: string }
 
 export type ModSources = { channels: ModChannelWithUsage[]; platform_blacklist: ModPlatform[] }
 
@@ -129,7 +149,7 @@ export type MRFEVersionsResponse = MRFEVersion[]
 
 export type ModSource = { Curseforge: CurseforgeMod } | { Modrinth: ModrinthMod }
 
-export type FESubtask = { name: Translation; progress: FESubtaskProgress }
+export type FESubtask = { name: Translation; progress: FESubtaskProgress; download_hosts: string[] }
 
 export type ExportEntry = { entries: { [key: string]: ExportEntry | null } }
 
@@ -153,7 +173,7 @@ export type FEManagedJavaArchMap = { [key: FEManagedJavaArch]: FEManagedJavaVers
 
 export type MRFETeamMember = { team_id: string; user: MRFEUser; role: string; ordering: string | null }
 
-export type ExportTarget = "Curseforge" | "Modrinth"
+export type ExportTarget = "Curseforge" | "Modrinth" | "MultiMC" | "Packwiz" | "ServerPack" | "GDLPack"
 
 export type FEUpdateJavaProfileArgs = { profileName: string; javaId?: string | null }
 
@@ -179,6 +199,8 @@ export type MRFEVersionIDs = string[]
 
 export type GameLogEntry = { id: GameLogId; instance_id: FEInstanceId; active: boolean }
 
+export type GameLogException = { class: string; message: string | null; causes: string[]; thrownAt: string | null; count: number; firstLine: number; lastSeen: string }
+
 export type ModpackInfo = { modpack: Modpack; locked: boolean }
 
 export type CFFEFilesParameters = { body: CFFEFilesParametersBody }
@@ -257,7 +279,7 @@ export type LatestModSource = { Curseforge: number } | { Modrinth: string }
 
 export type ModChannel = "Alpha" | "Beta" | "Stable"
 
-export type ImportEntity = "LegacyGDLauncher" | "MRPack" | "Modrinth" | "CurseForgeZip" | "CurseForge" | "ATLauncher" | "Technic" | "FTB" | "MultiMC" | "PrismLauncher"
+export type ImportEntity = "LegacyGDLauncher" | "MRPack" | "Modrinth" | "CurseForgeZip" | "CurseForge" | "ATLauncher" | "Technic" | "FTB" | "MultiMC" | "PrismLauncher" | "MinecraftLauncher" | "Packwiz" | "GDLPack"
 
 export type ImportableInstance = { filename: string; instance_name: string }
 
@@ -323,7 +345,7 @@ export type McType = "old_alpha" | "old_beta" | "release" | "snapshot"
 
 export type ModChannelWithUsage = { channel: ModChannel; allow_updates: boolean }
 
-export type Translation = { translation: "InstanceTaskDeleting" } | { translation: "InstanceTaskLaunch"; args: { name: string } } | { translation: "InstanceTaskPrepare"; args: { name: string } } | { translation: "InstanceTaskLaunchRequestVersions" } | { translation: "InstanceTaskLaunchRequestModpack" } | { translation: "InstanceTaskLaunchDownloadModpack" } | { translation: "InstanceTaskLaunchDownloadModpackFiles" } | { translation: "InstanceTaskLaunchExtractModpackFiles" } | { translation: "InstanceTaskLaunchRequestAddonMetadata" } | { translation: "InstanceTaskLaunchApplyStagedPatches" } | { translation: "InstanceTaskLaunchDownloadJava" } | { translation: "InstanceTaskLaunchExtractJava" } | { translation: "InstanceTaskRequestModloaderInfo" } | { translation: "InstanceTaskRequestMinecraftFiles" } | { translation: "InstanceTaskLaunchCheckingFiles" } | { translation: "InstanceTaskLaunchDownloadFiles" } | { translation: "InstanceTaskGeneratingPackInfo" } | { translation: "InstanceTaskFillCache" } | { translation: "InstanceTaskLaunchExtractNatives" } | { translation: "InstanceTaskReconstructAssets" } | { translation: "InstanceTaskLaunchRunForgeProcessors" } | { translation: "InstanceTaskLaunchRunNeoforgeProcessors" } | { translation: "InstanceTaskInstallMod"; args: { mod_name: string; instance_name: string } } | { translation: "InstanceTaskInstallModDownloadFile" } | { translation: "FinalizingImport" } | { translation: "InstanceImportLegacyBadConfigFile" } | { translation: "InstanceImportCfZipMalformed" } | { translation: "InstanceImportCfZipMissingManifest" } | { translation: "InstanceImportCfZipMalformedManifest" } | { translation: "InstanceImportCfZipNotMinecraftModpack" } | { translation: "InstanceImportMrpackMalformed" } | { translation: "InstanceImportMrpackMissingManifest" } | { translation: "InstanceImportMrpackMalformedManifest" } | { translation: "InstanceExport" } | { translation: "InstanceExportScanningMods" } | { translation: "InstanceExportCacheMods" } | { translation: "InstanceExportCalculateSize" } | { translation: "InstanceExportCreatingBundle" }
+export type Translation = { translation: "InstanceTaskDeleting" } | { translation: "InstanceTaskLaunch"; args: { name: string } } | { translation: "InstanceTaskPrepare"; args: { name: string } } | { translation: "InstanceTaskLaunchRequestVersions" } | { translation: "InstanceTaskLaunchRequestModpack" } | { translation: "InstanceTaskLaunchDownloadModpack" } | { translation: "InstanceTaskLaunchDownloadModpackFiles" } | { translation: "InstanceTaskLaunchExtractModpackFiles" } | { translation: "InstanceTaskLaunchRequestAddonMetadata" } | { translation: "InstanceTaskLaunchApplyStagedPatches" } | { translation: "InstanceTaskLaunchDownloadJava" } | { translation: "InstanceTaskLaunchExtractJava" } | { translation: "InstanceTaskRequestModloaderInfo" } | { translation: "InstanceTaskRequestMinecraftFiles" } | { translation: "InstanceTaskLaunchCheckingFiles" } | { translation: "InstanceTaskLaunchDownloadFiles" } | { translation: "InstanceTaskGeneratingPackInfo" } | { translation: "InstanceTaskFillCache" } | { translation: "InstanceTaskLaunchExtractNatives" } | { translation: "InstanceTaskReconstructAssets" } | { translation: "InstanceTaskLaunchRunForgeProcessors" } | { translation: "InstanceTaskLaunchRunNeoforgeProcessors" } | { translation: "InstanceTaskInstallMod"; args: { mod_name: string; instance_name: string } } | { translation: "InstanceTaskInstallModDownloadFile" } | { translation: "FinalizingImport" } | { translation: "InstanceImportLegacyBadConfigFile" } | { translation: "InstanceImportCfZipMalformed" } | { translation: "InstanceImportCfZipMissingManifest" } | { translation: "InstanceImportCfZipMalformedManifest" } | { translation: "InstanceImportCfZipNotMinecraftModpack" } | { translation: "InstanceImportMrpackMalformed" } | { translation: "InstanceImportMrpackMissingManifest" } | { translation: "InstanceImportMrpackMalformedManifest" } | { translation: "InstanceImportMmcMissingPack" } | { translation: "InstanceImportMmcMalformedPack" } | { translation: "InstanceImportAtlBadConfigFile" } | { translation: "InstanceImportAtlUnsupportedLoader" } | { translation: "InstanceImportTechnicMissingVersion" } | { translation: "InstanceImportTechnicMalformedVersion" } | { translation: "InstanceImportFtbBadConfigFile" } | { translation: "InstanceImportFtbUnsupportedLoader" } | { translation: "InstanceImportMinecraftLauncherMissingGameDir" } | { translation: "InstanceImportPackwizMalformedPack" } | { translation: "InstanceImportPackwizUnsupportedLoader" } | { translation: "InstanceImportGdlpackMalformed" } | { translation: "InstanceImportGdlpackMissingManifest" } | { translation: "InstanceImportGdlpackMalformedManifest" } | { translation: "InstanceExport" } | { translation: "InstanceExportScanningMods" } | { translation: "InstanceExportCacheMods" } | { translation: "InstanceExportCalculateSize" } | { translation: "InstanceExportCreatingBundle" }
 
 export type MRFETeamResponse = MRFETeamMember[]
 
@@ -356,7 +378,11 @@ export type ListInstance = { id: FEInstanceId; group_id: FEGroupId; name: string
 
 export type CFFEFileDependency = { modId: number; relationType: CFFEFileRelationType }
 
-export type FESettings = { theme: string; language: string; reducedMotion: boolean; discordIntegration: boolean; releaseChannel: FEReleaseChannel; lastAppVersion: string | null; concurrentDownloads: number; downloadDependencies: boolean; launcherActionOnGameLaunch: FELauncherActionOnGameLaunch; showAppCloseWarning: boolean; showNews: boolean; showFeatured: boolean; instancesSortBy: InstancesSortBy; instancesSortByAsc: boolean; instancesGroupBy: InstancesGroupBy; instancesGroupByAsc: boolean; instancesTileSize: number; deletionThroughRecycleBin: boolean; xmx: number; xms: number; preLaunchHook: string | null; wrapperCommand: string | null; postExitHook: string | null; isFirstLaunch: boolean; gameResolution: GameResolution | null; javaCustomArgs: string; autoManageJavaSystemProfiles: boolean; modSources: ModSources; termsAndPrivacyAccepted: boolean; metricsEnabled: boolean; metricsEnabledLastUpdate: string | null; randomUserUuid: string; gdlAccountId: string | null }
+export type FESettings = { theme: string; language: string; reducedMotion: boolean; discordIntegration: boolean; releaseChannel: FEReleaseChannel; lastAppVersion: string | null; concurrentDownloads: number; concurrentDownloadsPerHost: number | null; downloadBandwidthLimit: number | null; mojangMirror: string | null; httpCacheSizeLimit: number; offlineMode: boolean; downloadDependencies: boolean; launcherActionOnGameLaunch: FELauncherActionOnGameLaunch; showAppCloseWarning: boolean; showNews: boolean; showFeatured: boolean; instancesSortBy: InstancesSortBy; instancesSortByAsc: boolean; instancesGroupBy: InstancesGroupBy; instancesGroupByAsc: boolean; instancesTileSize: number; deletionThroughRecycleBin: boolean; xmx: number; xms: number; preLaunchHook: string | null; wrapperCommand: string | null; postExitHook: string | null; isFirstLaunch: boolean; gameResolution: GameResolution | null; javaCustomArgs: string; autoManageJavaSystemProfiles: boolean; modSources: ModSources; proxy: FEProxySettings; termsAndPrivacyAccepted: boolean; metricsEnabled: boolean; metricsEnabledLastUpdate: string | null; randomUserUuid: string; gdlAccountId: string | null }
+
+export type FEProxySettings = { http: string | null; https: string | null; socks5: string | null; username: string | null; hasPassword: boolean; noProxy: string[]; caBundle: string | null }
+
+export type FENetworkStatus = { offline: boolean; forcedOffline: boolean; unreachable: boolean }
 
 export type MRFEProjectIDs = string[]
 
@@ -508,6 +534,8 @@ export type AccountStatus = "ok" | "expired" | "refreshing" | "invalid"
 
 export type FETaskId = number
 
+export type FEInterruptedInstall = { instance_id: FEInstanceId; task: Translation; files: number; partial_files: number; interrupted_at: string }
+
 export type InvalidListInstance = "JsonMissing" | { JsonError: ConfigurationParseError } | { Other: string }
 
 export type MRFEProjectSupportRange = "required" | "optional" | "unsupported" | "unknown"